@@ -8,7 +8,7 @@ import { LightWasm } from "@lightprotocol/hasher.rs";
 import { BorshCoder, EventParser, Idl } from "@coral-xyz/anchor";
 import idl from "./lib/idl/zkcash.json";
 import { parseLogs } from "@debridge-finance/solana-transaction-parser";
-import { decodeEvent, ParsedEvent } from "./utils/event-decoder";
+import { decodeCpiEvent, decodeEvent, ParsedEvent } from "./utils/event-decoder";
 import { IsNull, Not, Repository } from "typeorm";
 import { CommitmentEvent } from "./entities/CommitmentEvent";
 import { Deposit } from "./entities/Deposit";
@@ -266,118 +266,20 @@ export class SolanaEventListener {
 
     this.connection.onLogs(
       this.programId,
-      async (logs, ctx) => {
+      async (logs) => {
         try {
           if (logs.err) {
             return;
           }
 
-          const signature = logs.signature;
-          const parsedLogs = parseLogs(logs.logs);
-          const relevantLogs = parsedLogs.filter(
-            (log) => log.programId === this.programId.toString()
-          );
-
-          const events: ParsedEvent[] = [];
-
-          // Decode events from data logs
-          for (const log of relevantLogs) {
-            for (const dataLog of log.dataLogs) {
-              const parsedEvent = decodeEvent(dataLog, signature);
-
-              if (parsedEvent) {
-                events.push(parsedEvent);
-              }
-            }
-          }
-
-          // Process decoded events
-          for (const event of events) {
-            if (event.type === "CommitmentData") {
-              const data = event.data;
-
-              if (data.isOldFormat) {
-                // Old format: single commitment
-                console.log(
-                  `Processing CommitmentData event (old format): index=${
-                    data.index
-                  }, commitment=${data.commitment0.substring(0, 16)}...`
-                );
-                console.log(`Adding commitment at index ${Number(data.index)}`);
-                await this.addCommitment(
-                  data.commitment0,
-                  Number(data.index),
-                  ctx.slot,
-                  signature,
-                  data.encryptedOutput
-                );
-              } else {
-                // New format: two commitments
-                console.log(
-                  `Processing CommitmentData event (new format): index=${
-                    data.index
-                  }, commitment0=${data.commitment0.substring(
-                    0,
-                    16
-                  )}..., commitment1=${data.commitment1?.substring(0, 16)}...`
-                );
-
-                // HARDCODED FIX: This specific transaction had a bug in the event (+1 offset)
-                // Real indices should be 223 and 224, not 225 and 226
-                const BUGGY_TX = "4Broavxx1HCBrJyDFenSNW9xPL2uKH5rcb4MYZCzEr3PdDUsg2n34mfKBpaxByAdLagmcuBqDMFNZ7Q97gW9cPvK";
-                let firstIndex = Number(data.index);
-                let secondIndex = Number(data.index) + 1;
-                
-                if (signature === BUGGY_TX) {
-                  console.log(`⚠️  Detected buggy transaction ${BUGGY_TX}, applying hardcoded fix: 225 -> 223, 226 -> 224`);
-                  firstIndex = firstIndex - 1;
-                  secondIndex = secondIndex - 1;
-                }
-
-                // Add first commitment at index
-                console.log(
-                  `Adding commitment0 at index ${firstIndex}`
-                );
-                await this.addCommitment(
-                  data.commitment0,
-                  firstIndex,
-                  ctx.slot,
-                  signature,
-                  data.encryptedOutput
-                );
-
-                // Add second commitment at index + 1 (if exists)
-                if (data.commitment1) {
-                  console.log(`Adding commitment1 at index ${secondIndex}`);
-                  await this.addCommitment(
-                    data.commitment1,
-                    secondIndex,
-                    ctx.slot,
-                    signature,
-                    data.encryptedOutput
-                  );
-                }
-              }
-            }
-          }
-
-          // Check if this is a deposit transaction and track first deposits
-          if (events.length > 0) {
-            const isDeposit = parsedLogs.some(log => 
-              log.logMessages?.some((msg: string) => msg === "Instruction: Deposit") ||
-              log.rawLogs?.some((msg: string) => msg.includes("Program log: Instruction: Deposit"))
-            );
-
-            if (isDeposit) {
-              // Fetch full transaction to get user address
-              const tx = await this.connection.getParsedTransaction(signature, {
-                maxSupportedTransactionVersion: 0,
-              });
+          // Events are emitted through self-CPI, their data is only in the inner instructions of the transaction
+          const tx = await this.connection.getParsedTransaction(logs.signature, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0,
+          });
 
-              if (tx) {
-                await this.trackFirstDeposit(tx, Number(events[0]?.data?.index));
-              }
-            }
+          if (tx) {
+            await this.processTransaction(tx, logs.signature);
           }
         } catch (error) {
           console.error("Error processing new transaction:", error);
@@ -417,7 +319,7 @@ export class SolanaEventListener {
 
       const events: ParsedEvent[] = [];
 
-      // Decode events from data logs
+      // Decode events from data logs (transactions made before events moved to self-CPI)
       for (const log of relevantLogs) {
         for (const dataLog of log.dataLogs) {
           const parsedEvent = decodeEvent(dataLog, signature);
@@ -428,8 +330,27 @@ export class SolanaEventListener {
         }
       }
 
+      // Decode events from the self-CPI instructions emit_cpi! makes
+      for (const inner of tx.meta.innerInstructions || []) {
+        for (const ix of inner.instructions) {
+          if (!("data" in ix) || !ix.programId.equals(this.programId)) {
+            continue;
+          }
+          const parsedEvent = decodeCpiEvent(ix.data, signature);
+
+          if (parsedEvent) {
+            events.push(parsedEvent);
+          }
+        }
+      }
+
+      const commitmentEvents = events.filter(
+        (event): event is Extract<ParsedEvent, { type: "CommitmentData" }> =>
+          event.type === "CommitmentData"
+      );
+
       // Process decoded events
-      for (const event of events) {
+      for (const event of commitmentEvents) {
         if (event.type === "CommitmentData") {
           const data = event.data;
 
@@ -451,7 +372,9 @@ export class SolanaEventListener {
           } else {
             // New format: two commitments
             console.log(
-              `Processing CommitmentData event (new format) from transaction ${signature}: index=${
+              `Processing CommitmentData event (new format) from transaction ${signature}: tree=${
+                data.treeId ?? 0
+              }, index=${
                 data.index
               }, commitment0=${data.commitment0.substring(
                 0,
@@ -497,14 +420,14 @@ export class SolanaEventListener {
       }
 
       // Check if this is a deposit transaction and track first deposits
-      if (events.length > 0 && events[0]?.data?.index !== undefined) {
+      if (commitmentEvents.length > 0) {
         const isDeposit = relevantLogs.some(log => 
           log.logMessages?.some((msg: string) => msg === "Instruction: Deposit") ||
           log.rawLogs?.some((msg: string) => msg.includes("Program log: Instruction: Deposit"))
         );
 
         if (isDeposit) {
-          await this.trackFirstDeposit(tx, Number(events[0].data.index));
+          await this.trackFirstDeposit(tx, Number(commitmentEvents[0].data.index));
         }
       }
     } catch (error) {
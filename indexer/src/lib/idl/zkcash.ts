@@ -13,6 +13,150 @@ export type Zkcash = {
     "description": "Anchor program for zkcash"
   },
   "instructions": [
+    {
+      "name": "acceptAuthority",
+      "discriminator": [
+        107,
+        86,
+        198,
+        91,
+        33,
+        12,
+        107,
+        160
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "newAuthority",
+          "docs": [
+            "The proposed authority, e.g. a multisig vault"
+          ],
+          "signer": true
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "addSwapProgram",
+      "discriminator": [
+        221,
+        234,
+        75,
+        24,
+        107,
+        165,
+        114,
+        39
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "swapProgramRegistry",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  119,
+                  97,
+                  112,
+                  95,
+                  112,
+                  114,
+                  111,
+                  103,
+                  114,
+                  97,
+                  109,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can manage swap programs"
+          ],
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "programId",
+          "type": "pubkey"
+        }
+      ]
+    },
     {
       "name": "deposit",
       "discriminator": [
@@ -46,17 +190,41 @@ export type Zkcash = {
                   101,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
               }
             ]
           }
         },
         {
-          "name": "nullifier0",
+          "name": "rootTreeAccount",
+          "docs": [
+            "Tree the proof root belongs to, only needed when it is not the active tree."
+          ],
+          "optional": true
+        },
+        {
+          "name": "rootHistoryAccount",
+          "docs": [
+            "Root history of the active tree, required once one is attached to it."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "rootTreeHistoryAccount",
           "docs": [
-            "Nullifier account to mark the first input as spent.",
-            "Using `init` without `init_if_needed` ensures that the transaction",
-            "will automatically fail with a system program error if this nullifier",
-            "has already been used (i.e., if the account already exists)."
+            "Root history of `root_tree_account`, required when that tree has one attached."
+          ],
+          "optional": true
+        },
+        {
+          "name": "nullifierShard0",
+          "docs": [
+            "Nullifier shard holding the first input's nullifier, it must not contain it yet."
           ],
           "writable": true,
           "pda": {
@@ -72,25 +240,84 @@ export type Zkcash = {
                   102,
                   105,
                   101,
-                  114
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
                 ]
               },
               {
                 "kind": "arg",
-                "path": "proof.input_nullifiers [0]"
+                "path": "proof.input_nullifiers [0] [31 ..]"
               }
             ]
           }
         },
         {
-          "name": "nullifier1",
+          "name": "nullifierShard1",
           "docs": [
-            "Nullifier account to mark the second input as spent.",
-            "Using `init` without `init_if_needed` ensures that the transaction",
-            "will automatically fail with a system program error if this nullifier",
-            "has already been used (i.e., if the account already exists)."
+            "Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0."
           ],
           "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier0",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier1",
           "pda": {
             "seeds": [
               {
@@ -139,9 +366,69 @@ export type Zkcash = {
             ]
           }
         },
+        {
+          "name": "verifyingKey",
+          "docs": [
+            "Verifying key of the circuit version the proof was generated with"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
         {
           "name": "inputMint"
         },
+        {
+          "name": "mintConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ]
+          }
+        },
         {
           "name": "reserveTokenAccount",
           "writable": true,
@@ -152,41 +439,8 @@ export type Zkcash = {
                 "path": "globalConfig"
               },
               {
-                "kind": "const",
-                "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
-                ]
+                "kind": "account",
+                "path": "tokenProgram"
               },
               {
                 "kind": "account",
@@ -237,7 +491,11 @@ export type Zkcash = {
         },
         {
           "name": "userTokenAccount",
+          "docs": [
+            "Omitted when depositing native SOL, which is then wrapped straight into the reserve"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
@@ -245,41 +503,8 @@ export type Zkcash = {
                 "path": "user"
               },
               {
-                "kind": "const",
-                "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
-                ]
+                "kind": "account",
+                "path": "tokenProgram"
               },
               {
                 "kind": "account",
@@ -335,8 +560,39 @@ export type Zkcash = {
           "address": "11111111111111111111111111111111"
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
         }
       ],
       "args": [
@@ -363,16 +619,16 @@ export type Zkcash = {
       ]
     },
     {
-      "name": "initialize",
+      "name": "depositDual",
       "discriminator": [
-        175,
-        175,
-        109,
-        31,
-        13,
-        152,
-        155,
-        237
+        85,
+        130,
+        224,
+        7,
+        159,
+        170,
+        198,
+        213
       ],
       "accounts": [
         {
@@ -395,114 +651,109 @@ export type Zkcash = {
                   101,
                   101
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
               }
             ]
           }
         },
         {
-          "name": "treeTokenAccount",
+          "name": "rootTreeAccount",
+          "docs": [
+            "Tree the proof root belongs to, only needed when it is not the active tree."
+          ],
+          "optional": true
+        },
+        {
+          "name": "rootHistoryAccount",
+          "docs": [
+            "Root history of the active tree, required once one is attached to it."
+          ],
           "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  116,
-                  114,
-                  101,
-                  101,
-                  95,
-                  116,
-                  111,
-                  107,
-                  101,
-                  110
-                ]
-              }
-            ]
-          }
+          "optional": true
         },
         {
-          "name": "globalConfig",
+          "name": "rootTreeHistoryAccount",
+          "docs": [
+            "Root history of `root_tree_account`, required when that tree has one attached."
+          ],
+          "optional": true
+        },
+        {
+          "name": "nullifierShard0",
+          "docs": [
+            "Nullifier shard holding the first input's nullifier, it must not contain it yet."
+          ],
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  103,
+                  110,
+                  117,
                   108,
-                  111,
-                  98,
-                  97,
                   108,
-                  95,
-                  99,
-                  111,
-                  110,
+                  105,
                   102,
                   105,
-                  103
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
                 ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0] [31 ..]"
               }
             ]
           }
         },
         {
-          "name": "authority",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "swap",
-      "discriminator": [
-        248,
-        198,
-        158,
-        145,
-        225,
-        117,
-        135,
-        200
-      ],
-      "accounts": [
-        {
-          "name": "treeAccount",
+          "name": "nullifierShard1",
+          "docs": [
+            "Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0."
+          ],
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  109,
-                  101,
-                  114,
-                  107,
+                  110,
+                  117,
+                  108,
                   108,
+                  105,
+                  102,
+                  105,
                   101,
+                  114,
                   95,
-                  116,
+                  115,
+                  104,
+                  97,
                   114,
-                  101,
-                  101
+                  100
                 ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1] [31 ..]"
               }
             ]
           }
         },
         {
-          "name": "nullifier0",
-          "docs": [
-            "Nullifier account to mark the first input as spent"
-          ],
-          "writable": true,
+          "name": "legacyNullifier0",
           "pda": {
             "seeds": [
               {
@@ -527,11 +778,7 @@ export type Zkcash = {
           }
         },
         {
-          "name": "nullifier1",
-          "docs": [
-            "Nullifier account to mark the second input as spent"
-          ],
-          "writable": true,
+          "name": "legacyNullifier1",
           "pda": {
             "seeds": [
               {
@@ -581,60 +828,114 @@ export type Zkcash = {
           }
         },
         {
-          "name": "inputMint"
+          "name": "verifyingKey",
+          "docs": [
+            "Verifying key of the circuit version the proof was generated with"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
         },
         {
-          "name": "outputMint"
+          "name": "inputMint0"
         },
         {
-          "name": "reserveTokenAccountInput",
-          "writable": true,
+          "name": "inputMint1"
+        },
+        {
+          "name": "mintConfig0",
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "globalConfig"
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
               },
+              {
+                "kind": "account",
+                "path": "inputMint0"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mintConfig1",
+          "pda": {
+            "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
                 ]
               },
               {
                 "kind": "account",
-                "path": "inputMint"
+                "path": "inputMint1"
+              }
+            ]
+          }
+        },
+        {
+          "name": "reserveTokenAccount0",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram0"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint0"
               }
             ],
             "program": {
@@ -677,7 +978,7 @@ export type Zkcash = {
           }
         },
         {
-          "name": "reserveTokenAccountOutput",
+          "name": "reserveTokenAccount1",
           "writable": true,
           "pda": {
             "seeds": [
@@ -686,45 +987,12 @@ export type Zkcash = {
                 "path": "globalConfig"
               },
               {
-                "kind": "const",
-                "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
-                ]
+                "kind": "account",
+                "path": "tokenProgram1"
               },
               {
                 "kind": "account",
-                "path": "outputMint"
+                "path": "inputMint1"
               }
             ],
             "program": {
@@ -767,27 +1035,177 @@ export type Zkcash = {
           }
         },
         {
-          "name": "feeRecipientAccount",
-          "writable": true
+          "name": "feeRecipientAccount0"
         },
         {
-          "name": "jupiterProgram",
-          "docs": [
-            "Jupiter aggregator program"
-          ]
+          "name": "feeRecipientAccount1"
         },
         {
-          "name": "user",
+          "name": "userTokenAccount0",
           "writable": true,
-          "signer": true
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "user"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram0"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint0"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "userTokenAccount1",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "user"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram1"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint1"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "user",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram0",
+          "docs": [
+            "Token program owning `input_mint0`"
+          ]
+        },
+        {
+          "name": "tokenProgram1",
+          "docs": [
+            "Token program owning `input_mint1`, may differ from the first one"
+          ]
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "program"
         }
       ],
       "args": [
@@ -803,31 +1221,27 @@ export type Zkcash = {
           "name": "extDataMinified",
           "type": {
             "defined": {
-              "name": "swapExtDataMinified"
+              "name": "dualExtDataMinified"
             }
           }
         },
         {
           "name": "encryptedOutput",
           "type": "bytes"
-        },
-        {
-          "name": "jupiterSwapData",
-          "type": "bytes"
         }
       ]
     },
     {
-      "name": "updateDepositLimit",
+      "name": "initialize",
       "discriminator": [
-        181,
-        115,
-        65,
-        169,
-        4,
-        1,
-        96,
-        109
+        175,
+        175,
+        109,
+        31,
+        13,
+        152,
+        155,
+        237
       ],
       "accounts": [
         {
@@ -850,41 +1264,46 @@ export type Zkcash = {
                   101,
                   101
                 ]
+              },
+              {
+                "kind": "const",
+                "value": [
+                  0,
+                  0,
+                  0,
+                  0,
+                  0,
+                  0,
+                  0,
+                  0
+                ]
               }
             ]
           }
         },
         {
-          "name": "authority",
-          "docs": [
-            "The authority account that can update the deposit limit"
-          ],
-          "signer": true,
-          "relations": [
-            "treeAccount"
-          ]
-        }
-      ],
-      "args": [
-        {
-          "name": "newLimit",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "updateGlobalConfig",
-      "discriminator": [
-        164,
-        84,
-        130,
-        189,
-        111,
-        58,
-        250,
-        200
-      ],
-      "accounts": [
+          "name": "treeTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  101,
+                  95,
+                  116,
+                  111,
+                  107,
+                  101,
+                  110
+                ]
+              }
+            ]
+          }
+        },
         {
           "name": "globalConfig",
           "writable": true,
@@ -911,10 +1330,202 @@ export type Zkcash = {
             ]
           }
         },
+        {
+          "name": "verifyingKey",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "const",
+                "value": [
+                  0,
+                  0,
+                  0,
+                  0
+                ]
+              }
+            ]
+          }
+        },
         {
           "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "height",
+          "type": "u8"
+        },
+        {
+          "name": "rootHistorySize",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "initializeNullifierShard",
+      "discriminator": [
+        210,
+        32,
+        6,
+        142,
+        116,
+        9,
+        19,
+        10
+      ],
+      "accounts": [
+        {
+          "name": "nullifierShard",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "shardId"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "shardId",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "initializeRootHistory",
+      "discriminator": [
+        96,
+        91,
+        201,
+        128,
+        225,
+        63,
+        30,
+        236
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "rootHistoryAccount",
           "docs": [
-            "The authority account that can update the global config"
+            "Created beforehand by the client with RootHistory::space(capacity) bytes, owned by this program"
+          ],
+          "writable": true
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can attach root histories"
           ],
           "signer": true,
           "relations": [
@@ -922,128 +1533,3662 @@ export type Zkcash = {
           ]
         }
       ],
-      "args": [
+      "args": []
+    },
+    {
+      "name": "migrateGlobalConfig",
+      "discriminator": [
+        207,
+        52,
+        247,
+        7,
+        1,
+        230,
+        228,
+        147
+      ],
+      "accounts": [
         {
-          "name": "depositFeeRate",
-          "type": {
-            "option": "u16"
+          "name": "globalConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
           }
         },
         {
-          "name": "withdrawalFeeRate",
-          "type": {
-            "option": "u16"
+          "name": "verifyingKey",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "const",
+                "value": [
+                  0,
+                  0,
+                  0,
+                  0
+                ]
+              }
+            ]
           }
         },
         {
-          "name": "feeErrorMargin",
-          "type": {
-            "option": "u16"
-          }
+          "name": "authority",
+          "docs": [
+            "The authority of the legacy global config"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "withdraw",
+      "name": "migrateLegacyTree",
       "discriminator": [
-        183,
+        207,
+        137,
+        191,
         18,
-        70,
-        156,
-        148,
-        109,
-        161,
-        34
+        93,
+        136,
+        136,
+        235
       ],
       "accounts": [
         {
-          "name": "treeAccount",
+          "name": "legacyTreeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "const",
+                "value": [
+                  0,
+                  0,
+                  0,
+                  0,
+                  0,
+                  0,
+                  0,
+                  0
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority of the legacy tree"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "proposeAuthority",
+      "discriminator": [
+        20,
+        148,
+        236,
+        198,
+        76,
+        119,
+        99,
+        142
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The current authority proposing its successor"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "newAuthority",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "registerMint",
+      "discriminator": [
+        242,
+        43,
+        74,
+        162,
+        217,
+        214,
+        191,
+        171
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "mintConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can register mints"
+          ],
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "maxDepositAmount",
+          "type": "u64"
+        },
+        {
+          "name": "minDepositAmount",
+          "type": "u64"
+        },
+        {
+          "name": "minTransferFee",
+          "type": "u64"
+        },
+        {
+          "name": "accountCreationFee",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "registerRelayer",
+      "discriminator": [
+        98,
+        213,
+        0,
+        0,
+        27,
+        134,
+        109,
+        48
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayerAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "relayer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can register relayers"
+          ],
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "relayer",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "registerVerifyingKey",
+      "discriminator": [
+        133,
+        73,
+        43,
+        38,
+        91,
+        47,
+        57,
+        192
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "version"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can register verifying keys"
+          ],
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "version",
+          "type": "u32"
+        },
+        {
+          "name": "treeHeight",
+          "type": "u8"
+        },
+        {
+          "name": "vkAlphaG1",
+          "type": {
+            "array": [
+              "u8",
+              64
+            ]
+          }
+        },
+        {
+          "name": "vkBetaG2",
+          "type": {
+            "array": [
+              "u8",
+              128
+            ]
+          }
+        },
+        {
+          "name": "vkGammaG2",
+          "type": {
+            "array": [
+              "u8",
+              128
+            ]
+          }
+        },
+        {
+          "name": "vkDeltaG2",
+          "type": {
+            "array": [
+              "u8",
+              128
+            ]
+          }
+        }
+      ]
+    },
+    {
+      "name": "removeSwapProgram",
+      "discriminator": [
+        173,
+        83,
+        7,
+        127,
+        57,
+        211,
+        188,
+        13
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "swapProgramRegistry",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  119,
+                  97,
+                  112,
+                  95,
+                  112,
+                  114,
+                  111,
+                  103,
+                  114,
+                  97,
+                  109,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can manage swap programs"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "programId",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "requestUnstake",
+      "discriminator": [
+        44,
+        154,
+        110,
+        253,
+        160,
+        202,
+        54,
+        34
+      ],
+      "accounts": [
+        {
+          "name": "relayerAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayer",
+          "signer": true
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "retireTree",
+      "discriminator": [
+        71,
+        68,
+        207,
+        154,
+        113,
+        57,
+        230,
+        238
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "treeAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can retire trees"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "rolloverTree",
+      "discriminator": [
+        246,
+        82,
+        71,
+        140,
+        243,
+        113,
+        201,
+        74
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "currentTreeAccount",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "docs": [
+            "An active verifying key whose circuit proves membership in trees of the new height"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "newTreeAccount",
+          "writable": true
+        },
+        {
+          "name": "payer",
+          "docs": [
+            "The authority, or anyone once the current tree is full"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "height",
+          "type": "u8"
+        },
+        {
+          "name": "rootHistorySize",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "setPause",
+      "discriminator": [
+        63,
+        32,
+        154,
+        2,
+        56,
+        103,
+        79,
+        45
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "signer",
+          "docs": [
+            "The authority, or the guardian when only adding pause flags"
+          ],
+          "signer": true
+        }
+      ],
+      "args": [
+        {
+          "name": "paused",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "setRelayerStatus",
+      "discriminator": [
+        134,
+        199,
+        89,
+        233,
+        117,
+        209,
+        122,
+        5
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayerAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer_account.relayer",
+                "account": "relayerAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can list and delist relayers"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "active",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "setVerifyingKeyStatus",
+      "discriminator": [
+        2,
+        150,
+        35,
+        20,
+        252,
+        240,
+        83,
+        56
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can activate and retire verifying keys"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "status",
+          "type": {
+            "defined": {
+              "name": "verifyingKeyStatus"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "shieldedTransfer",
+      "discriminator": [
+        191,
+        130,
+        5,
+        127,
+        124,
+        187,
+        238,
+        188
+      ],
+      "accounts": [
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "rootTreeAccount",
+          "docs": [
+            "Tree the proof root belongs to, only needed when it is not the active tree."
+          ],
+          "optional": true
+        },
+        {
+          "name": "rootHistoryAccount",
+          "docs": [
+            "Root history of the active tree, required once one is attached to it."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "rootTreeHistoryAccount",
+          "docs": [
+            "Root history of `root_tree_account`, required when that tree has one attached."
+          ],
+          "optional": true
+        },
+        {
+          "name": "nullifierShard0",
+          "docs": [
+            "Nullifier shard holding the first input's nullifier, it must not contain it yet."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "nullifierShard1",
+          "docs": [
+            "Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier0",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier1",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "docs": [
+            "Verifying key of the circuit version the proof was generated with"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "inputMint"
+        },
+        {
+          "name": "mintConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "reserveTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "feeRecipientAccount",
+          "docs": [
+            "Fee token account of the relayer"
+          ],
+          "writable": true
+        },
+        {
+          "name": "relayerAccount",
+          "docs": [
+            "Registry entry of the relayer submitting this transaction"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "proof",
+          "type": {
+            "defined": {
+              "name": "proof"
+            }
+          }
+        },
+        {
+          "name": "extDataMinified",
+          "type": {
+            "defined": {
+              "name": "extDataMinified"
+            }
+          }
+        },
+        {
+          "name": "encryptedOutput",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "slashRelayer",
+      "discriminator": [
+        74,
+        223,
+        6,
+        158,
+        134,
+        103,
+        165,
+        182
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayerAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer_account.relayer",
+                "account": "relayerAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can slash relayers, it receives the slashed lamports"
+          ],
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "stakeRelayer",
+      "discriminator": [
+        86,
+        158,
+        150,
+        21,
+        226,
+        219,
+        25,
+        131
+      ],
+      "accounts": [
+        {
+          "name": "relayerAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "swap",
+      "discriminator": [
+        248,
+        198,
+        158,
+        145,
+        225,
+        117,
+        135,
+        200
+      ],
+      "accounts": [
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "rootTreeAccount",
+          "docs": [
+            "Tree the proof root belongs to, only needed when it is not the active tree."
+          ],
+          "optional": true
+        },
+        {
+          "name": "rootHistoryAccount",
+          "docs": [
+            "Root history of the active tree, required once one is attached to it."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "rootTreeHistoryAccount",
+          "docs": [
+            "Root history of `root_tree_account`, required when that tree has one attached."
+          ],
+          "optional": true
+        },
+        {
+          "name": "nullifierShard0",
+          "docs": [
+            "Nullifier shard holding the first input's nullifier, it must not contain it yet."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "nullifierShard1",
+          "docs": [
+            "Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier0",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier1",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "docs": [
+            "Verifying key of the circuit version the proof was generated with"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "inputMint"
+        },
+        {
+          "name": "outputMint"
+        },
+        {
+          "name": "inputMintConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "outputMintConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "outputMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "reserveTokenAccountInput",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "inputTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "reserveTokenAccountOutput",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "outputTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "outputMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "feeRecipientAccount",
+          "docs": [
+            "Output token account of the relayer receiving the slippage surplus"
+          ],
+          "writable": true
+        },
+        {
+          "name": "inputFeeRecipientAccount",
+          "docs": [
+            "Input token account of the relayer receiving the upfront swap fee"
+          ],
+          "writable": true
+        },
+        {
+          "name": "inputPriceUpdate",
+          "optional": true
+        },
+        {
+          "name": "outputPriceUpdate",
+          "optional": true
+        },
+        {
+          "name": "relayerAccount",
+          "docs": [
+            "Registry entry of the relayer submitting this transaction"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "user"
+              }
+            ]
+          }
+        },
+        {
+          "name": "swapProgramRegistry",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  119,
+                  97,
+                  112,
+                  95,
+                  112,
+                  114,
+                  111,
+                  103,
+                  114,
+                  97,
+                  109,
+                  95,
+                  114,
+                  101,
+                  103,
+                  105,
+                  115,
+                  116,
+                  114,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "jupiterProgram",
+          "docs": [
+            "Jupiter aggregator program, or another approved swap program"
+          ]
+        },
+        {
+          "name": "user",
+          "docs": [
+            "Registered relayer submitting the swap"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "inputTokenProgram",
+          "docs": [
+            "Token program owning `input_mint`, may differ from the output one (e.g. SOL -> Token-2022)"
+          ]
+        },
+        {
+          "name": "outputTokenProgram",
+          "docs": [
+            "Token program owning `output_mint`"
+          ]
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "proof",
+          "type": {
+            "defined": {
+              "name": "proof"
+            }
+          }
+        },
+        {
+          "name": "extDataMinified",
+          "type": {
+            "defined": {
+              "name": "swapExtDataMinified"
+            }
+          }
+        },
+        {
+          "name": "encryptedOutput",
+          "type": "bytes"
+        },
+        {
+          "name": "jupiterSwapData",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "unstakeRelayer",
+      "discriminator": [
+        31,
+        52,
+        81,
+        89,
+        151,
+        184,
+        210,
+        232
+      ],
+      "accounts": [
+        {
+          "name": "relayerAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayer",
+          "writable": true,
+          "signer": true
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "updateGlobalConfig",
+      "discriminator": [
+        164,
+        84,
+        130,
+        189,
+        111,
+        58,
+        250,
+        200
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can update the global config"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "depositFeeRate",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "withdrawalFeeRate",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "feeErrorMargin",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "swapFeeRate",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "maxSwapSurplusRate",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "guardian",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "outflowWindowSlots",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "minRelayerStake",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "oracleProgram",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "rootValiditySlots",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateMintConfig",
+      "discriminator": [
+        243,
+        113,
+        184,
+        98,
+        212,
+        234,
+        198,
+        193
+      ],
+      "accounts": [
+        {
+          "name": "globalConfig",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "mintConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint_config.mint",
+                "account": "mintConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "docs": [
+            "The authority account that can update mint configs"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "maxDepositAmount",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "minDepositAmount",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "enabled",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "maxOutflowPerWindow",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "priceFeedId",
+          "type": {
+            "option": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        },
+        {
+          "name": "minTransferFee",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "accountCreationFee",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "withdraw",
+      "discriminator": [
+        183,
+        18,
+        70,
+        156,
+        148,
+        109,
+        161,
+        34
+      ],
+      "accounts": [
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "rootTreeAccount",
+          "docs": [
+            "Tree the proof root belongs to, only needed when it is not the active tree."
+          ],
+          "optional": true
+        },
+        {
+          "name": "rootHistoryAccount",
+          "docs": [
+            "Root history of the active tree, required once one is attached to it."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "rootTreeHistoryAccount",
+          "docs": [
+            "Root history of `root_tree_account`, required when that tree has one attached."
+          ],
+          "optional": true
+        },
+        {
+          "name": "nullifierShard0",
+          "docs": [
+            "Nullifier shard holding the first input's nullifier, it must not contain it yet."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "nullifierShard1",
+          "docs": [
+            "Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier0",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier1",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "globalConfig",
+          "docs": [
+            "Mutable because unwrapped WSOL passes through it on its way to the recipient"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "docs": [
+            "Verifying key of the circuit version the proof was generated with"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "inputMint"
+        },
+        {
+          "name": "mintConfig",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "reserveTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "recipient",
+          "writable": true
+        },
+        {
+          "name": "recipientTokenAccount",
+          "docs": [
+            "Any token account of the mint owned by the recipient, or its associated token account which the handler",
+            "creates if missing so that fresh addresses can receive tokens."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "wsolUnwrapAccount",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  115,
+                  111,
+                  108,
+                  95,
+                  117,
+                  110,
+                  119,
+                  114,
+                  97,
+                  112
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "feeRecipientAccount",
+          "docs": [
+            "Fee token account of the relayer"
+          ],
+          "writable": true
+        },
+        {
+          "name": "relayerAccount",
+          "docs": [
+            "Registry entry of the relayer submitting this transaction"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "relayer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "relayer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "proof",
+          "type": {
+            "defined": {
+              "name": "proof"
+            }
+          }
+        },
+        {
+          "name": "extDataMinified",
+          "type": {
+            "defined": {
+              "name": "extDataMinified"
+            }
+          }
+        },
+        {
+          "name": "encryptedOutput",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "withdrawDual",
+      "discriminator": [
+        175,
+        233,
+        229,
+        226,
+        242,
+        11,
+        114,
+        247
+      ],
+      "accounts": [
+        {
+          "name": "treeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  107,
+                  108,
+                  101,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "global_config.active_tree_id",
+                "account": "globalConfig"
+              }
+            ]
+          }
+        },
+        {
+          "name": "rootTreeAccount",
+          "docs": [
+            "Tree the proof root belongs to, only needed when it is not the active tree."
+          ],
+          "optional": true
+        },
+        {
+          "name": "rootHistoryAccount",
+          "docs": [
+            "Root history of the active tree, required once one is attached to it."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "rootTreeHistoryAccount",
+          "docs": [
+            "Root history of `root_tree_account`, required when that tree has one attached."
+          ],
+          "optional": true
+        },
+        {
+          "name": "nullifierShard0",
+          "docs": [
+            "Nullifier shard holding the first input's nullifier, it must not contain it yet."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "nullifierShard1",
+          "docs": [
+            "Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114,
+                  95,
+                  115,
+                  104,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1] [31 ..]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier0",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "legacyNullifier1",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  110,
+                  117,
+                  108,
+                  108,
+                  105,
+                  102,
+                  105,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [1]"
+              }
+            ]
+          }
+        },
+        {
+          "name": "globalConfig",
+          "docs": [
+            "Mutable because unwrapped WSOL passes through it on its way to the recipient"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "verifyingKey",
+          "docs": [
+            "Verifying key of the circuit version the proof was generated with"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  101,
+                  114,
+                  105,
+                  102,
+                  121,
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
+              }
+            ]
+          }
+        },
+        {
+          "name": "inputMint0"
+        },
+        {
+          "name": "inputMint1"
+        },
+        {
+          "name": "mintConfig0",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "inputMint0"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mintConfig1",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  105,
+                  110,
+                  116,
+                  95,
+                  99,
+                  111,
+                  110,
+                  102,
+                  105,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "inputMint1"
+              }
+            ]
+          }
+        },
+        {
+          "name": "reserveTokenAccount0",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram0"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint0"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "reserveTokenAccount1",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "globalConfig"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram1"
+              },
+              {
+                "kind": "account",
+                "path": "inputMint1"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "recipient",
+          "writable": true
+        },
+        {
+          "name": "recipientTokenAccount0",
+          "docs": [
+            "Any token account of the mint owned by the recipient, or its associated token account created if missing."
+          ],
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "recipientTokenAccount1",
+          "docs": [
+            "Any token account of the mint owned by the recipient, or its associated token account created if missing."
+          ],
           "writable": true,
+          "optional": true
+        },
+        {
+          "name": "wsolUnwrapAccount",
+          "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  109,
-                  101,
-                  114,
-                  107,
+                  119,
+                  115,
+                  111,
                   108,
-                  101,
                   95,
-                  116,
+                  117,
+                  110,
+                  119,
                   114,
-                  101,
-                  101
+                  97,
+                  112
                 ]
+              },
+              {
+                "kind": "arg",
+                "path": "proof.input_nullifiers [0]"
               }
             ]
           }
         },
         {
-          "name": "nullifier0",
+          "name": "feeRecipientAccount0",
           "docs": [
-            "Nullifier account to mark the first input as spent.",
-            "Using `init` without `init_if_needed` ensures that the transaction",
-            "will automatically fail with a system program error if this nullifier",
-            "has already been used (i.e., if the account already exists)."
+            "Fee token account of the relayer for input_mint0"
+          ],
+          "writable": true
+        },
+        {
+          "name": "feeRecipientAccount1",
+          "docs": [
+            "Fee token account of the relayer for input_mint1"
+          ],
+          "writable": true
+        },
+        {
+          "name": "relayerAccount",
+          "docs": [
+            "Registry entry of the relayer submitting this transaction"
           ],
-          "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  110,
-                  117,
-                  108,
+                  114,
+                  101,
                   108,
-                  105,
-                  102,
-                  105,
+                  97,
+                  121,
                   101,
                   114
                 ]
               },
               {
-                "kind": "arg",
-                "path": "proof.input_nullifiers [0]"
+                "kind": "account",
+                "path": "relayer"
               }
             ]
           }
         },
         {
-          "name": "nullifier1",
-          "docs": [
-            "Nullifier account to mark the second input as spent.",
-            "Using `init` without `init_if_needed` ensures that the transaction",
-            "will automatically fail with a system program error if this nullifier",
-            "has already been used (i.e., if the account already exists)."
-          ],
+          "name": "relayer",
           "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram0",
+          "docs": [
+            "Token program owning `input_mint0`"
+          ]
+        },
+        {
+          "name": "tokenProgram1",
+          "docs": [
+            "Token program owning `input_mint1`, may differ from the first one"
+          ]
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "eventAuthority",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
                   110,
+                  116,
+                  95,
+                  97,
                   117,
-                  108,
-                  108,
-                  105,
-                  102,
+                  116,
+                  104,
+                  111,
+                  114,
                   105,
-                  101,
-                  114
+                  116,
+                  121
                 ]
-              },
-              {
-                "kind": "arg",
-                "path": "proof.input_nullifiers [1]"
               }
             ]
           }
         },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "proof",
+          "type": {
+            "defined": {
+              "name": "proof"
+            }
+          }
+        },
+        {
+          "name": "extDataMinified",
+          "type": {
+            "defined": {
+              "name": "dualExtDataMinified"
+            }
+          }
+        },
+        {
+          "name": "encryptedOutput",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "writeVerifyingKeyIc",
+      "discriminator": [
+        173,
+        34,
+        131,
+        177,
+        234,
+        252,
+        158,
+        85
+      ],
+      "accounts": [
         {
           "name": "globalConfig",
           "pda": {
@@ -1070,143 +5215,62 @@ export type Zkcash = {
           }
         },
         {
-          "name": "inputMint"
-        },
-        {
-          "name": "reserveTokenAccount",
+          "name": "verifyingKey",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "globalConfig"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  118,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
+                  114,
+                  105,
+                  102,
                   121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  105,
+                  110,
+                  103,
+                  95,
+                  107,
+                  101,
+                  121
                 ]
               },
               {
                 "kind": "account",
-                "path": "inputMint"
+                "path": "verifying_key.version",
+                "account": "verifyingKeyAccount"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "recipient",
-          "writable": true
-        },
-        {
-          "name": "recipientTokenAccount",
-          "writable": true
-        },
-        {
-          "name": "feeRecipientAccount"
-        },
-        {
-          "name": "relayer",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "authority",
+          "docs": [
+            "The authority account that can register verifying keys"
+          ],
+          "signer": true,
+          "relations": [
+            "globalConfig"
+          ]
         }
       ],
       "args": [
         {
-          "name": "proof",
-          "type": {
-            "defined": {
-              "name": "proof"
-            }
-          }
+          "name": "startIndex",
+          "type": "u8"
         },
         {
-          "name": "extDataMinified",
+          "name": "vkIc",
           "type": {
-            "defined": {
-              "name": "extDataMinified"
+            "vec": {
+              "array": [
+                "u8",
+                64
+              ]
             }
           }
-        },
-        {
-          "name": "encryptedOutput",
-          "type": "bytes"
         }
       ]
     }
@@ -1239,16 +5303,68 @@ export type Zkcash = {
       ]
     },
     {
-      "name": "nullifierAccount",
+      "name": "mintConfig",
       "discriminator": [
-        250,
-        31,
-        238,
-        177,
-        213,
+        168,
+        252,
+        88,
+        182,
+        219,
+        205,
+        39,
+        53
+      ]
+    },
+    {
+      "name": "nullifierShard",
+      "discriminator": [
+        197,
+        157,
+        1,
+        146,
+        156,
+        137,
+        211,
+        61
+      ]
+    },
+    {
+      "name": "relayerAccount",
+      "discriminator": [
+        94,
+        235,
         98,
-        48,
-        172
+        227,
+        126,
+        208,
+        77,
+        139
+      ]
+    },
+    {
+      "name": "rootHistoryAccount",
+      "discriminator": [
+        6,
+        227,
+        100,
+        77,
+        159,
+        61,
+        207,
+        200
+      ]
+    },
+    {
+      "name": "swapProgramRegistry",
+      "discriminator": [
+        46,
+        126,
+        74,
+        145,
+        102,
+        153,
+        97,
+        218
       ]
     },
     {
@@ -1263,6 +5379,19 @@ export type Zkcash = {
         37,
         204
       ]
+    },
+    {
+      "name": "verifyingKeyAccount",
+      "discriminator": [
+        5,
+        54,
+        231,
+        84,
+        81,
+        70,
+        17,
+        168
+      ]
     }
   ],
   "events": [
@@ -1278,6 +5407,19 @@ export type Zkcash = {
         234,
         34
       ]
+    },
+    {
+      "name": "nullifiersSpent",
+      "discriminator": [
+        233,
+        70,
+        101,
+        208,
+        188,
+        47,
+        81,
+        92
+      ]
     }
   ],
   "errors": [
@@ -1349,7 +5491,7 @@ export type Zkcash = {
     {
       "code": 6013,
       "name": "invalidFeeRecipient",
-      "msg": "Fee recipient does not match global configuration"
+      "msg": "Fee recipient is not a token account of the relayer for this mint"
     },
     {
       "code": 6014,
@@ -1373,8 +5515,8 @@ export type Zkcash = {
     },
     {
       "code": 6018,
-      "name": "dualTokenNotSupported",
-      "msg": "Dual-token transactions are not yet supported"
+      "name": "invalidDualTokenMints",
+      "msg": "Dual-token transactions require two different mints"
     },
     {
       "code": 6019,
@@ -1390,6 +5532,231 @@ export type Zkcash = {
       "code": 6021,
       "name": "insufficientSwapOutput",
       "msg": "Insufficient swap output: received amount is less than minimum required"
+    },
+    {
+      "code": 6022,
+      "name": "reserveCreditMismatch",
+      "msg": "Amount credited to the reserve does not match the public amount"
+    },
+    {
+      "code": 6023,
+      "name": "treeRetired",
+      "msg": "Merkle tree is retired: its roots are no longer accepted"
+    },
+    {
+      "code": 6024,
+      "name": "cannotRetireActiveTree",
+      "msg": "The active merkle tree cannot be retired"
+    },
+    {
+      "code": 6025,
+      "name": "mintDisabled",
+      "msg": "Deposits and swaps into this mint are disabled"
+    },
+    {
+      "code": 6026,
+      "name": "depositBelowMinimum",
+      "msg": "Deposit amount is below the minimum for this mint"
+    },
+    {
+      "code": 6027,
+      "name": "invalidDepositLimits",
+      "msg": "Invalid deposit limits: minimum must not exceed maximum"
+    },
+    {
+      "code": 6028,
+      "name": "instructionPaused",
+      "msg": "This instruction is currently paused"
+    },
+    {
+      "code": 6029,
+      "name": "invalidPauseFlags",
+      "msg": "Invalid pause flags"
+    },
+    {
+      "code": 6030,
+      "name": "outflowLimitExceeded",
+      "msg": "Outflow limit of this mint exceeded for the current window"
+    },
+    {
+      "code": 6031,
+      "name": "nullifierAlreadyUsed",
+      "msg": "Nullifier has already been used"
+    },
+    {
+      "code": 6032,
+      "name": "invalidNullifierShard",
+      "msg": "Nullifier shard does not match the nullifier"
+    },
+    {
+      "code": 6033,
+      "name": "verifyingKeyNotActive",
+      "msg": "Verifying key is not active"
+    },
+    {
+      "code": 6034,
+      "name": "verifyingKeyNotPending",
+      "msg": "Verifying key can only be written while pending"
+    },
+    {
+      "code": 6035,
+      "name": "invalidVerifyingKeyStatus",
+      "msg": "Invalid verifying key status transition"
+    },
+    {
+      "code": 6036,
+      "name": "invalidVerifyingKeyIcRange",
+      "msg": "Verifying key IC points out of range"
+    },
+    {
+      "code": 6037,
+      "name": "relayerNotActive",
+      "msg": "Relayer is not registered, delisted or under-staked"
+    },
+    {
+      "code": 6038,
+      "name": "insufficientRelayerStake",
+      "msg": "Unstake amount exceeds the relayer stake"
+    },
+    {
+      "code": 6039,
+      "name": "swapSurplusExceeded",
+      "msg": "Swap output surplus exceeds the maximum allowed"
+    },
+    {
+      "code": 6040,
+      "name": "unknownSwapProgram",
+      "msg": "Swap program is not in the swap program registry"
+    },
+    {
+      "code": 6041,
+      "name": "swapProgramAlreadyRegistered",
+      "msg": "Swap program is already registered"
+    },
+    {
+      "code": 6042,
+      "name": "swapProgramRegistryFull",
+      "msg": "Swap program registry is full"
+    },
+    {
+      "code": 6043,
+      "name": "swapInputMismatch",
+      "msg": "Swap debited a different amount from the input reserve than ext_amount"
+    },
+    {
+      "code": 6044,
+      "name": "missingPriceUpdate",
+      "msg": "Price update accounts are required when both swap mints have a price feed"
+    },
+    {
+      "code": 6045,
+      "name": "invalidPriceUpdate",
+      "msg": "Invalid price update account"
+    },
+    {
+      "code": 6046,
+      "name": "stalePriceUpdate",
+      "msg": "Price update is too old"
+    },
+    {
+      "code": 6047,
+      "name": "swapMinAmountBelowOracle",
+      "msg": "Swap minimum output is below the oracle price by more than the surplus cap"
+    },
+    {
+      "code": 6048,
+      "name": "missingWsolUnwrapAccount",
+      "msg": "WSOL withdrawals require the wsol_unwrap_account"
+    },
+    {
+      "code": 6049,
+      "name": "missingRecipientTokenAccount",
+      "msg": "Token withdrawals require the recipient_token_account"
+    },
+    {
+      "code": 6050,
+      "name": "missingUserTokenAccount",
+      "msg": "Only native SOL deposits can omit the user_token_account"
+    },
+    {
+      "code": 6051,
+      "name": "missingRootHistoryAccount",
+      "msg": "The root history account of the tree is required"
+    },
+    {
+      "code": 6052,
+      "name": "invalidRootHistoryAccount",
+      "msg": "Root history account does not belong to the tree"
+    },
+    {
+      "code": 6053,
+      "name": "rootHistoryAlreadyAttached",
+      "msg": "The tree already has a root history account"
+    },
+    {
+      "code": 6054,
+      "name": "invalidRootHistoryCapacity",
+      "msg": "Root history account is too small"
+    },
+    {
+      "code": 6055,
+      "name": "rootExpired",
+      "msg": "Root has expired: generate the proof against a more recent root"
+    },
+    {
+      "code": 6056,
+      "name": "invalidTreeHeight",
+      "msg": "Invalid merkle tree height"
+    },
+    {
+      "code": 6057,
+      "name": "invalidRootHistorySize",
+      "msg": "Invalid root history size: must be at least 1"
+    },
+    {
+      "code": 6058,
+      "name": "treeHeightMismatch",
+      "msg": "Verifying key circuit depth does not match the merkle tree height"
+    },
+    {
+      "code": 6059,
+      "name": "invalidLegacyAccount",
+      "msg": "Account is not in the legacy layout"
+    },
+    {
+      "code": 6060,
+      "name": "nullifierShardFull",
+      "msg": "Nullifier shard is full"
+    },
+    {
+      "code": 6061,
+      "name": "relayerUnstakeCooldown",
+      "msg": "Unstaked lamports can only be withdrawn once the cooldown has passed"
+    },
+    {
+      "code": 6062,
+      "name": "invalidRecipientTokenAccount",
+      "msg": "Recipient token account must hold the withdrawn mint and belong to the recipient"
+    },
+    {
+      "code": 6063,
+      "name": "transferFeeBelowMinimum",
+      "msg": "Shielded transfer fee is below the minimum for this mint"
+    },
+    {
+      "code": 6064,
+      "name": "treeAccountTooLarge",
+      "msg": "Merkle tree account is too large to be created: lower the height or the root history size"
+    },
+    {
+      "code": 6065,
+      "name": "accountCreationFeeNotCovered",
+      "msg": "Withdrawal fee does not cover the creation of the recipient token account"
+    },
+    {
+      "code": 6066,
+      "name": "noPendingUnstake",
+      "msg": "Relayer has no unstake request to withdraw"
     }
   ],
   "types": [
@@ -1398,10 +5765,65 @@ export type Zkcash = {
       "type": {
         "kind": "struct",
         "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "kind",
+            "type": {
+              "defined": {
+                "name": "transactionKind"
+              }
+            }
+          },
+          {
+            "name": "treeId",
+            "type": "u64"
+          },
           {
             "name": "index",
             "type": "u64"
           },
+          {
+            "name": "root",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "rootIndex",
+            "type": "u64"
+          },
+          {
+            "name": "mintA",
+            "type": "pubkey"
+          },
+          {
+            "name": "mintB",
+            "type": "pubkey"
+          },
+          {
+            "name": "inputNullifiers",
+            "type": {
+              "array": [
+                {
+                  "array": [
+                    "u8",
+                    32
+                  ]
+                },
+                2
+              ]
+            }
+          },
+          {
+            "name": "slot",
+            "type": "u64"
+          },
           {
             "name": "commitment0",
             "type": {
@@ -1427,6 +5849,30 @@ export type Zkcash = {
         ]
       }
     },
+    {
+      "name": "dualExtDataMinified",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "extAmount0",
+            "type": "i64"
+          },
+          {
+            "name": "extAmount1",
+            "type": "i64"
+          },
+          {
+            "name": "fee0",
+            "type": "u64"
+          },
+          {
+            "name": "fee1",
+            "type": "u64"
+          }
+        ]
+      }
+    },
     {
       "name": "extDataMinified",
       "type": {
@@ -1448,10 +5894,18 @@ export type Zkcash = {
       "type": {
         "kind": "struct",
         "fields": [
+          {
+            "name": "version",
+            "type": "u8"
+          },
           {
             "name": "authority",
             "type": "pubkey"
           },
+          {
+            "name": "pendingAuthority",
+            "type": "pubkey"
+          },
           {
             "name": "depositFeeRate",
             "type": "u16"
@@ -1460,85 +5914,195 @@ export type Zkcash = {
             "name": "withdrawalFeeRate",
             "type": "u16"
           },
+          {
+            "name": "swapFeeRate",
+            "type": "u16"
+          },
+          {
+            "name": "maxSwapSurplusRate",
+            "type": "u16"
+          },
           {
             "name": "feeErrorMargin",
             "type": "u16"
           },
           {
-            "name": "bump",
-            "type": "u8"
+            "name": "activeTreeId",
+            "type": "u64"
+          },
+          {
+            "name": "guardian",
+            "type": "pubkey"
+          },
+          {
+            "name": "paused",
+            "type": "u8"
+          },
+          {
+            "name": "outflowWindowSlots",
+            "type": "u64"
+          },
+          {
+            "name": "minRelayerStake",
+            "type": "u64"
+          },
+          {
+            "name": "oracleProgram",
+            "type": "pubkey"
+          },
+          {
+            "name": "rootValiditySlots",
+            "type": "u64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "merkleTreeAccount",
+      "serialization": "bytemuck",
+      "repr": {
+        "kind": "c"
+      },
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "rootHistoryAccount",
+            "type": "pubkey"
+          },
+          {
+            "name": "nextIndex",
+            "type": "u64"
+          },
+          {
+            "name": "treeId",
+            "type": "u64"
+          },
+          {
+            "name": "root",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "rootIndex",
+            "type": "u64"
+          },
+          {
+            "name": "height",
+            "type": "u8"
+          },
+          {
+            "name": "rootHistorySize",
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "retired",
+            "type": "u8"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                3
+              ]
+            }
           }
         ]
       }
     },
     {
-      "name": "merkleTreeAccount",
-      "serialization": "bytemuck",
-      "repr": {
-        "kind": "c"
-      },
+      "name": "mintConfig",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "authority",
+            "name": "mint",
             "type": "pubkey"
           },
           {
-            "name": "nextIndex",
+            "name": "maxDepositAmount",
             "type": "u64"
           },
           {
-            "name": "subtrees",
-            "type": {
-              "array": [
-                {
-                  "array": [
-                    "u8",
-                    32
-                  ]
-                },
-                26
-              ]
-            }
+            "name": "minDepositAmount",
+            "type": "u64"
           },
           {
-            "name": "root",
-            "type": {
-              "array": [
-                "u8",
-                32
-              ]
-            }
+            "name": "enabled",
+            "type": "bool"
+          },
+          {
+            "name": "maxOutflowPerWindow",
+            "type": "u64"
+          },
+          {
+            "name": "windowStartSlot",
+            "type": "u64"
           },
           {
-            "name": "rootHistory",
+            "name": "currentWindowOutflow",
+            "type": "u64"
+          },
+          {
+            "name": "previousWindowOutflow",
+            "type": "u64"
+          },
+          {
+            "name": "priceFeedId",
             "type": {
               "array": [
-                {
-                  "array": [
-                    "u8",
-                    32
-                  ]
-                },
-                100
+                "u8",
+                32
               ]
             }
           },
           {
-            "name": "rootIndex",
+            "name": "minTransferFee",
             "type": "u64"
           },
           {
-            "name": "maxDepositAmount",
+            "name": "accountCreationFee",
             "type": "u64"
           },
           {
-            "name": "height",
+            "name": "bump",
             "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "nullifierShard",
+      "serialization": "bytemuck",
+      "repr": {
+        "kind": "c"
+      },
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "count",
+            "type": "u32"
           },
           {
-            "name": "rootHistorySize",
+            "name": "shardId",
             "type": "u8"
           },
           {
@@ -1550,7 +6114,7 @@ export type Zkcash = {
             "type": {
               "array": [
                 "u8",
-                5
+                2
               ]
             }
           }
@@ -1558,17 +6122,39 @@ export type Zkcash = {
       }
     },
     {
-      "name": "nullifierAccount",
+      "name": "nullifiersSpent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "bump",
-            "docs": [
-              "This account's existence indicates that the nullifier has been used.",
-              "No fields needed other than bump for PDA verification."
-            ],
+            "name": "version",
             "type": "u8"
+          },
+          {
+            "name": "kind",
+            "type": {
+              "defined": {
+                "name": "transactionKind"
+              }
+            }
+          },
+          {
+            "name": "nullifiers",
+            "type": {
+              "array": [
+                {
+                  "array": [
+                    "u8",
+                    32
+                  ]
+                },
+                2
+              ]
+            }
+          },
+          {
+            "name": "slot",
+            "type": "u64"
           }
         ]
       }
@@ -1672,6 +6258,75 @@ export type Zkcash = {
         ]
       }
     },
+    {
+      "name": "relayerAccount",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "relayer",
+            "type": "pubkey"
+          },
+          {
+            "name": "active",
+            "type": "bool"
+          },
+          {
+            "name": "stake",
+            "type": "u64"
+          },
+          {
+            "name": "pendingUnstake",
+            "type": "u64"
+          },
+          {
+            "name": "unstakeRequestedSlot",
+            "type": "u64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "rootHistoryAccount",
+      "serialization": "bytemuck",
+      "repr": {
+        "kind": "c"
+      },
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "tree",
+            "type": "pubkey"
+          },
+          {
+            "name": "capacity",
+            "type": "u32"
+          },
+          {
+            "name": "head",
+            "type": "u32"
+          },
+          {
+            "name": "count",
+            "type": "u32"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                4
+              ]
+            }
+          }
+        ]
+      }
+    },
     {
       "name": "swapExtDataMinified",
       "type": {
@@ -1692,6 +6347,50 @@ export type Zkcash = {
         ]
       }
     },
+    {
+      "name": "swapProgramRegistry",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "programs",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "transactionKind",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "deposit"
+          },
+          {
+            "name": "withdraw"
+          },
+          {
+            "name": "swap"
+          },
+          {
+            "name": "transfer"
+          },
+          {
+            "name": "depositDual"
+          },
+          {
+            "name": "withdrawDual"
+          }
+        ]
+      }
+    },
     {
       "name": "treeTokenAccount",
       "type": {
@@ -1707,6 +6406,101 @@ export type Zkcash = {
           }
         ]
       }
+    },
+    {
+      "name": "verifyingKeyAccount",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "version",
+            "type": "u32"
+          },
+          {
+            "name": "status",
+            "type": {
+              "defined": {
+                "name": "verifyingKeyStatus"
+              }
+            }
+          },
+          {
+            "name": "treeHeight",
+            "type": "u8"
+          },
+          {
+            "name": "vkAlphaG1",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          },
+          {
+            "name": "vkBetaG2",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          },
+          {
+            "name": "vkGammaG2",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          },
+          {
+            "name": "vkDeltaG2",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          },
+          {
+            "name": "vkIc",
+            "type": {
+              "array": [
+                {
+                  "array": [
+                    "u8",
+                    64
+                  ]
+                },
+                11
+              ]
+            }
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "verifyingKeyStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "pending"
+          },
+          {
+            "name": "active"
+          },
+          {
+            "name": "retired"
+          }
+        ]
+      }
     }
   ]
 };
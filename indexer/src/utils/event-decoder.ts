@@ -1,6 +1,18 @@
 import { Buffer } from 'buffer';
 import bs58 from 'bs58';
 
+// Transaction kinds, in the order of the TransactionKind enum of the program
+export const TRANSACTION_KINDS = [
+  'Deposit',
+  'Withdraw',
+  'Swap',
+  'Transfer',
+  'DepositDual',
+  'WithdrawDual',
+] as const;
+
+export type TransactionKind = typeof TRANSACTION_KINDS[number];
+
 // Event type interfaces
 export interface CommitmentDataEvent {
   index: bigint;           // 8 bytes - u64
@@ -8,17 +20,39 @@ export interface CommitmentDataEvent {
   commitment1?: string;    // 32 bytes - [u8; 32] as decimal string (field element) - optional for old format
   encryptedOutput: string; // variable length - bytes as hex string
   isOldFormat?: boolean;  // true if this is old format with single commitment
+  // Only set for events emitted through self-CPI (version >= 1)
+  version?: number;
+  kind?: TransactionKind;
+  treeId?: bigint;
+  root?: string;
+  rootIndex?: bigint;
+  mintA?: string;
+  mintB?: string;
+  inputNullifiers?: string[];
+  slot?: bigint;
+}
+
+export interface NullifiersSpentEvent {
+  version: number;
+  kind: TransactionKind;
+  nullifiers: string[];    // [[u8; 32]; 2] as hex strings
+  slot: bigint;
 }
 
 // Event discriminators from IDL
 const EVENT_DISCRIMINATORS = {
   CommitmentData: Buffer.from([13, 110, 215, 127, 244, 62, 234, 34]),
+  NullifiersSpent: Buffer.from([233, 70, 101, 208, 188, 47, 81, 92]),
 } as const;
 
+// Instruction data prefix of the self-CPI Anchor's emit_cpi! makes, followed by the event discriminator and payload
+const EVENT_IX_TAG = Buffer.from([228, 69, 165, 46, 81, 203, 154, 29]);
+
 type EventType = keyof typeof EVENT_DISCRIMINATORS;
 
 export type ParsedEvent =
-  | { type: 'CommitmentData'; data: CommitmentDataEvent; signature?: string };
+  | { type: 'CommitmentData'; data: CommitmentDataEvent; signature?: string }
+  | { type: 'NullifiersSpent'; data: NullifiersSpentEvent; signature?: string };
 
 // Helper function to read Borsh-encoded bytes (length-prefixed)
 function readBorshBytes(buffer: Buffer, offset: number): { value: Buffer; newOffset: number } {
@@ -100,6 +134,74 @@ function decodeCommitmentDataEvent(payload: Buffer): CommitmentDataEvent {
   };
 }
 
+function decodeTransactionKind(value: number): TransactionKind {
+  const kind = TRANSACTION_KINDS[value];
+  if (kind === undefined) {
+    throw new Error(`Unknown transaction kind: ${value}`);
+  }
+  return kind;
+}
+
+// Layout of CommitmentData since it is emitted through self-CPI
+function decodeCommitmentDataCpiEvent(payload: Buffer): CommitmentDataEvent {
+  let offset = 0;
+
+  const version = payload.readUInt8(offset);
+  offset += 1;
+  const kind = decodeTransactionKind(payload.readUInt8(offset));
+  offset += 1;
+  const treeId = payload.readBigUInt64LE(offset);
+  offset += 8;
+  const index = payload.readBigUInt64LE(offset);
+  offset += 8;
+  const root = bytesToFieldElement(payload.subarray(offset, offset + 32));
+  offset += 32;
+  const rootIndex = payload.readBigUInt64LE(offset);
+  offset += 8;
+  const mintA = bufferToBase58(payload.subarray(offset, offset + 32));
+  offset += 32;
+  const mintB = bufferToBase58(payload.subarray(offset, offset + 32));
+  offset += 32;
+  const inputNullifiers = [0, 1].map((i) =>
+    bufferToHex(payload.subarray(offset + i * 32, offset + (i + 1) * 32))
+  );
+  offset += 64;
+  const slot = payload.readBigUInt64LE(offset);
+  offset += 8;
+  const commitment0 = bytesToFieldElement(payload.subarray(offset, offset + 32));
+  offset += 32;
+  const commitment1 = bytesToFieldElement(payload.subarray(offset, offset + 32));
+  offset += 32;
+  const { value: encryptedOutputBytes } = readBorshBytes(payload, offset);
+
+  return {
+    index,
+    commitment0,
+    commitment1,
+    encryptedOutput: bufferToHex(encryptedOutputBytes),
+    isOldFormat: false,
+    version,
+    kind,
+    treeId,
+    root,
+    rootIndex,
+    mintA,
+    mintB,
+    inputNullifiers,
+    slot,
+  };
+}
+
+function decodeNullifiersSpentEvent(payload: Buffer): NullifiersSpentEvent {
+  const version = payload.readUInt8(0);
+  const kind = decodeTransactionKind(payload.readUInt8(1));
+  const nullifiers = [0, 1].map((i) =>
+    bufferToHex(payload.subarray(2 + i * 32, 2 + (i + 1) * 32))
+  );
+  const slot = payload.readBigUInt64LE(66);
+  return { version, kind, nullifiers, slot };
+}
+
 // Helper function to convert 32 bytes (big-endian) to field element string
 function bytesToFieldElement(bytes: Buffer): string {
   // Read as big-endian BigInt (as per Rust comment: "all public inputs needs to be in big endian format")
@@ -111,7 +213,7 @@ function bytesToFieldElement(bytes: Buffer): string {
 }
 
 /**
- * Decode event from base64 data
+ * Decode event from the base64 data of a "Program data:" log, how events were emitted before they moved to self-CPI
  */
 export function decodeEvent(base64Data: string, signature?: string): ParsedEvent | null {
   try {
@@ -162,6 +264,34 @@ export function decodeEvent(base64Data: string, signature?: string): ParsedEvent
   }
 }
 
+/**
+ * Decode event from the data of a self-CPI instruction made by emit_cpi! (base58, as in parsed transactions)
+ */
+export function decodeCpiEvent(base58Data: string, signature?: string): ParsedEvent | null {
+  try {
+    const buffer = Buffer.from(bs58.decode(base58Data));
+    if (buffer.length < 16 || !buffer.subarray(0, 8).equals(EVENT_IX_TAG)) {
+      return null;
+    }
+
+    const discriminator = buffer.subarray(8, 16);
+    const payload = buffer.subarray(16);
+
+    if (discriminator.equals(EVENT_DISCRIMINATORS.CommitmentData)) {
+      return { type: 'CommitmentData', data: decodeCommitmentDataCpiEvent(payload), signature };
+    }
+    if (discriminator.equals(EVENT_DISCRIMINATORS.NullifiersSpent)) {
+      return { type: 'NullifiersSpent', data: decodeNullifiersSpentEvent(payload), signature };
+    }
+
+    console.log(`No matching CPI event discriminator found for: [${discriminator.join(', ')}]`);
+    return null;
+  } catch (error) {
+    console.error('Error decoding CPI event:', error);
+    return null;
+  }
+}
+
 export function bufferToBase58(buffer: Buffer): string {
   return bs58.encode(buffer);
 }
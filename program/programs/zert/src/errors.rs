@@ -53,7 +53,7 @@ pub enum ErrorCode {
     DepositLimitExceeded,
     #[msg("Invalid fee rate: must be between 0 and 10000 basis points")]
     InvalidFeeRate,
-    #[msg("Fee recipient does not match global configuration")]
+    #[msg("Fee recipient is not a token account of the relayer for this mint")]
     InvalidFeeRecipient,
     #[msg("Fee amount is below minimum required (must be at least (1 - fee_error_margin) * expected_fee)")]
     InvalidFeeAmount,
@@ -63,12 +63,102 @@ pub enum ErrorCode {
     MerkleTreeFull,
     #[msg("Unsupported mint address")]
     UnsupportedMintAddress,
-    #[msg("Dual-token transactions are not yet supported")]
-    DualTokenNotSupported,
+    #[msg("Dual-token transactions require two different mints")]
+    InvalidDualTokenMints,
     #[msg("Invalid Jupiter swap data")]
     InvalidJupiterSwapData,
     #[msg("Math overflow or underflow occurred")]
     MathOverflow,
     #[msg("Insufficient swap output: received amount is less than minimum required")]
     InsufficientSwapOutput,
+    #[msg("Amount credited to the reserve does not match the public amount")]
+    ReserveCreditMismatch,
+    #[msg("Merkle tree is retired: its roots are no longer accepted")]
+    TreeRetired,
+    #[msg("The active merkle tree cannot be retired")]
+    CannotRetireActiveTree,
+    #[msg("Deposits and swaps into this mint are disabled")]
+    MintDisabled,
+    #[msg("Deposit amount is below the minimum for this mint")]
+    DepositBelowMinimum,
+    #[msg("Invalid deposit limits: minimum must not exceed maximum")]
+    InvalidDepositLimits,
+    #[msg("This instruction is currently paused")]
+    InstructionPaused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Outflow limit of this mint exceeded for the current window")]
+    OutflowLimitExceeded,
+    #[msg("Nullifier has already been used")]
+    NullifierAlreadyUsed,
+    #[msg("Nullifier shard does not match the nullifier")]
+    InvalidNullifierShard,
+    #[msg("Verifying key is not active")]
+    VerifyingKeyNotActive,
+    #[msg("Verifying key can only be written while pending")]
+    VerifyingKeyNotPending,
+    #[msg("Invalid verifying key status transition")]
+    InvalidVerifyingKeyStatus,
+    #[msg("Verifying key IC points out of range")]
+    InvalidVerifyingKeyIcRange,
+    #[msg("Relayer is not registered, delisted or under-staked")]
+    RelayerNotActive,
+    #[msg("Unstake amount exceeds the relayer stake")]
+    InsufficientRelayerStake,
+    #[msg("Swap output surplus exceeds the maximum allowed")]
+    SwapSurplusExceeded,
+    #[msg("Swap program is not in the swap program registry")]
+    UnknownSwapProgram,
+    #[msg("Swap program is already registered")]
+    SwapProgramAlreadyRegistered,
+    #[msg("Swap program registry is full")]
+    SwapProgramRegistryFull,
+    #[msg("Swap debited a different amount from the input reserve than ext_amount")]
+    SwapInputMismatch,
+    #[msg("Price update accounts are required when both swap mints have a price feed")]
+    MissingPriceUpdate,
+    #[msg("Invalid price update account")]
+    InvalidPriceUpdate,
+    #[msg("Price update is too old")]
+    StalePriceUpdate,
+    #[msg("Swap minimum output is below the oracle price by more than the surplus cap")]
+    SwapMinAmountBelowOracle,
+    #[msg("WSOL withdrawals require the wsol_unwrap_account")]
+    MissingWsolUnwrapAccount,
+    #[msg("Token withdrawals require the recipient_token_account")]
+    MissingRecipientTokenAccount,
+    #[msg("Only native SOL deposits can omit the user_token_account")]
+    MissingUserTokenAccount,
+    #[msg("The root history account of the tree is required")]
+    MissingRootHistoryAccount,
+    #[msg("Root history account does not belong to the tree")]
+    InvalidRootHistoryAccount,
+    #[msg("The tree already has a root history account")]
+    RootHistoryAlreadyAttached,
+    #[msg("Root history account is too small")]
+    InvalidRootHistoryCapacity,
+    #[msg("Root has expired: generate the proof against a more recent root")]
+    RootExpired,
+    #[msg("Invalid merkle tree height")]
+    InvalidTreeHeight,
+    #[msg("Invalid root history size: must be at least 1")]
+    InvalidRootHistorySize,
+    #[msg("Verifying key circuit depth does not match the merkle tree height")]
+    TreeHeightMismatch,
+    #[msg("Account is not in the legacy layout")]
+    InvalidLegacyAccount,
+    #[msg("Nullifier shard is full")]
+    NullifierShardFull,
+    #[msg("Unstaked lamports can only be withdrawn once the cooldown has passed")]
+    RelayerUnstakeCooldown,
+    #[msg("Recipient token account must hold the withdrawn mint and belong to the recipient")]
+    InvalidRecipientTokenAccount,
+    #[msg("Shielded transfer fee is below the minimum for this mint")]
+    TransferFeeBelowMinimum,
+    #[msg("Merkle tree account is too large to be created: lower the height or the root history size")]
+    TreeAccountTooLarge,
+    #[msg("Withdrawal fee does not cover the creation of the recipient token account")]
+    AccountCreationFeeNotCovered,
+    #[msg("Relayer has no unstake request to withdraw")]
+    NoPendingUnstake,
 } 
\ No newline at end of file
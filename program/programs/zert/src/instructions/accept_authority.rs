@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/**
+ * Accept a pending authority transfer. Only the proposed authority can call this.
+ */
+pub fn handler(ctx: Context<crate::AcceptAuthority>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    global_config.authority = new_authority;
+    global_config.pending_authority = Pubkey::default();
+
+    msg!("Authority transferred to: {}", new_authority);
+    Ok(())
+}
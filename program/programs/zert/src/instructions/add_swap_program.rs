@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::state::MAX_SWAP_PROGRAMS;
+use crate::ErrorCode;
+
+/**
+ * Approve a program that swap may route through, e.g. Jupiter v6 or a direct AMM adapter.
+ * Only the authority can call this.
+ *
+ * The registry is created on the first call.
+ */
+pub fn handler(ctx: Context<crate::AddSwapProgram>, program_id: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.swap_program_registry;
+    registry.bump = ctx.bumps.swap_program_registry;
+
+    require!(!registry.contains(&program_id), ErrorCode::SwapProgramAlreadyRegistered);
+    require!(registry.programs.len() < MAX_SWAP_PROGRAMS, ErrorCode::SwapProgramRegistryFull);
+    registry.programs.push(program_id);
+
+    msg!("Swap program {} added", program_id);
+    Ok(())
+}
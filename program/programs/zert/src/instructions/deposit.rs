@@ -1,66 +1,101 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::sysvar::rent::Rent;
 use ark_ff::PrimeField;
 use ark_bn254::Fr;
 use light_hasher::Poseidon;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use anchor_spl::token::{transfer, Token, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token::{sync_native, SyncNative};
+use anchor_spl::token::spl_token::native_mint;
 
-use crate::merkle_tree::MerkleTree;
-use crate::state::{MerkleTreeAccount, TreeTokenAccount, GlobalConfig, NullifierAccount};
-use crate::types::{Proof, ExtData, ExtDataMinified, CommitmentData};
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::nullifier_set::NullifierSet;
+use crate::root_history::RootHistory;
+use crate::state::{MerkleTreeAccount, GlobalConfig, NullifierShard, RootHistoryAccount, VerifyingKeyAccount, VerifyingKeyStatus, PAUSE_DEPOSIT};
+use crate::types::{Proof, ExtData, ExtDataMinified, CommitmentData, NullifiersSpent, TransactionKind};
 use crate::ErrorCode;
-use crate::utils::{verify_proof, VERIFYING_KEY};
+use crate::utils::verify_proof;
 use crate::utils;
 
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(proof: Proof, ext_data_minified: ExtDataMinified, encrypted_output: Vec<u8>)]
 pub struct Transact<'info> {
     #[account(
         mut,
-        seeds = [b"merkle_tree"],
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
         bump = tree_account.load()?.bump
     )]
     pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Tree the proof root belongs to, only needed when it is not the active tree.
+    pub root_tree_account: Option<AccountLoader<'info, MerkleTreeAccount>>,
+
+    /// Root history of the active tree, required once one is attached to it.
+    #[account(mut)]
+    pub root_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Root history of `root_tree_account`, required when that tree has one attached.
+    pub root_tree_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
     
-    /// Nullifier account to mark the first input as spent.
-    /// Using `init` without `init_if_needed` ensures that the transaction
-    /// will automatically fail with a system program error if this nullifier
-    /// has already been used (i.e., if the account already exists).
+    /// Nullifier shard holding the first input's nullifier, it must not contain it yet.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[0][31..].as_ref()],
+        bump = nullifier_shard0.load()?.bump
+    )]
+    pub nullifier_shard0: AccountLoader<'info, NullifierShard>,
+
+    /// Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[1][31..].as_ref()],
+        bump = nullifier_shard1.load()?.bump
+    )]
+    pub nullifier_shard1: AccountLoader<'info, NullifierShard>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
     #[account(
-        init,
-        payer = user,
-        space = 8 + std::mem::size_of::<NullifierAccount>(),
         seeds = [b"nullifier", proof.input_nullifiers[0].as_ref()],
         bump
     )]
-    pub nullifier0: Box<Account<'info, NullifierAccount>>,
-    
-    /// Nullifier account to mark the second input as spent.
-    /// Using `init` without `init_if_needed` ensures that the transaction
-    /// will automatically fail with a system program error if this nullifier
-    /// has already been used (i.e., if the account already exists).
+    pub legacy_nullifier0: UncheckedAccount<'info>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
     #[account(
-        init,
-        payer = user,
-        space = 8 + std::mem::size_of::<NullifierAccount>(),
         seeds = [b"nullifier", proof.input_nullifiers[1].as_ref()],
         bump
     )]
-    pub nullifier1: Box<Account<'info, NullifierAccount>>,
+    pub legacy_nullifier1: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"global_config"],
         bump
     )]
     pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Verifying key of the circuit version the proof was generated with
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
     
+    #[account(mint::token_program = token_program)]
     pub input_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// CHECK: config PDA of the input mint, empty when the mint is not registered, read by utils::load_mint_config
+    #[account(
+        seeds = [b"mint_config", input_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: UncheckedAccount<'info>,
+
     #[account(mut,
         associated_token::mint = input_mint,  
         associated_token::authority = global_config,
+        associated_token::token_program = token_program,
     )]
     pub reserve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -68,18 +103,20 @@ pub struct Transact<'info> {
     /// CHECK: user should be able to send fees to any types of accounts
     pub fee_recipient_account: UncheckedAccount<'info>,
 
+    /// Omitted when depositing native SOL, which is then wrapped straight into the reserve
     #[account(mut,
         associated_token::mint = input_mint,  
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub user_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     
     #[account(mut)]
     pub user: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 
@@ -90,8 +127,12 @@ pub fn handler(
     ext_data_minified: ExtDataMinified, 
     encrypted_output: Vec<u8>,
 ) -> Result<()> {
-    let tree_account = &mut ctx.accounts.tree_account.load_mut()?;
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
     let global_config = &ctx.accounts.global_config;
+    require!(!global_config.is_paused(PAUSE_DEPOSIT), ErrorCode::InstructionPaused);
+    let mint_config = utils::load_mint_config(&ctx.accounts.mint_config)?;
 
     // Reconstruct full ExtData from minified version and context accounts
     let ext_data = ExtData::from_minified(
@@ -100,11 +141,17 @@ pub fn handler(
         ext_data_minified,
     );
 
-    // Check if proof.root is in the tree_account's proof history
-    require!(
-        MerkleTree::is_known_root(&tree_account, proof.root),
-        ErrorCode::UnknownRoot
-    );
+    // Check if proof.root is in the proof history of the tree it references
+    utils::check_known_root(
+        tree_account,
+        ctx.accounts.tree_account.key(),
+        ctx.accounts.root_history_account.as_ref(),
+        ctx.accounts.root_tree_account.as_ref(),
+        ctx.accounts.root_tree_history_account.as_ref(),
+        proof.root,
+        global_config.root_validity_slots,
+        ctx.accounts.verifying_key.tree_height,
+    )?;
     // Check if the ext_data hashes to the same ext_data in the proof
     let calculated_ext_data_hash = utils::calculate_complete_ext_data_hash(
         ext_data.recipient,
@@ -141,50 +188,201 @@ pub fn handler(
     )?;
 
     // Verify the proof
-    require!(verify_proof(proof.clone(), VERIFYING_KEY, ctx.accounts.input_mint.key(), ctx.accounts.input_mint.key()), ErrorCode::InvalidProof);
+    require!(verify_proof(proof.clone(), ctx.accounts.verifying_key.groth16_verifying_key(), ctx.accounts.input_mint.key(), ctx.accounts.input_mint.key()), ErrorCode::InvalidProof);
+
+    // Mark both inputs as spent, this fails if either nullifier was used before
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard0,
+        &ctx.accounts.legacy_nullifier0,
+        proof.input_nullifiers[0],
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard1,
+        &ctx.accounts.legacy_nullifier1,
+        proof.input_nullifiers[1],
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
 
     require!(ext_amount > 0, ErrorCode::InvalidExtAmount);
     let deposit_amount = ext_amount as u64;
 
-    require!(
-        deposit_amount <= tree_account.max_deposit_amount,
-        ErrorCode::DepositLimitExceeded
-    );
+    utils::check_deposit_limits(&mint_config, deposit_amount)?;
+
+    match ctx.accounts.user_token_account.as_deref() {
+        Some(user_token_account) => transfer_deposit(
+            &ctx.accounts.token_program,
+            &ctx.accounts.input_mint,
+            user_token_account,
+            &mut ctx.accounts.reserve_token_account,
+            &ctx.accounts.fee_recipient_account,
+            &ctx.accounts.user,
+            deposit_amount,
+            fee,
+        )?,
+        None => {
+            require!(native_mint::ID == ctx.accounts.input_mint.key(), ErrorCode::MissingUserTokenAccount);
+            transfer_native_deposit(
+                &ctx.accounts.system_program,
+                &ctx.accounts.token_program,
+                &ctx.accounts.input_mint,
+                &ctx.accounts.global_config,
+                &mut ctx.accounts.reserve_token_account,
+                &ctx.accounts.fee_recipient_account,
+                &ctx.accounts.user,
+                deposit_amount,
+                fee,
+            )?
+        }
+    }
+
+    let next_index_to_insert = tree_account.next_index;
+    MerkleTree::append_pair::<Poseidon>(proof.output_commitments, tree_account, Clock::get()?.slot)?;
+    RootHistory::record(tree_account, ctx.accounts.root_history_account.as_ref())?;
+
+    emit_cpi!(NullifiersSpent::new(TransactionKind::Deposit, &proof)?);
+    emit_cpi!(CommitmentData::new(
+        TransactionKind::Deposit,
+        tree_account,
+        next_index_to_insert,
+        &proof,
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.input_mint.key(),
+        encrypted_output,
+    )?);
+
     
+    Ok(())
+}
+
+/**
+ * Moves a deposit from the user's token account into the reserve.
+ *
+ * The reserve must be credited exactly publicAmount (deposit_amount - fee), the fee goes to the fee recipient.
+ * Token-2022 mints with a transfer fee withhold part of every transfer, so the amount is grossed up.
+ */
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_deposit<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    user_token_account: &InterfaceAccount<'info, TokenAccount>,
+    reserve_token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    fee_recipient_account: &AccountInfo<'info>,
+    user: &Signer<'info>,
+    deposit_amount: u64,
+    fee: u64,
+) -> Result<()> {
+    let credited_amount = deposit_amount.checked_sub(fee)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let decimals = mint.decimals;
+
+    let transfer_amount = utils::calculate_gross_transfer_amount(
+        &mint.to_account_info(),
+        credited_amount,
+    )?;
+    let reserve_balance_before = reserve_token_account.amount;
+
     let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.user_token_account.to_account_info(),
-            to: ctx.accounts.reserve_token_account.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
+        token_program.to_account_info(),
+        TransferChecked {
+            from: user_token_account.to_account_info(),
+            mint: mint.to_account_info(),
+            to: reserve_token_account.to_account_info(),
+            authority: user.to_account_info(),
         },
     );
-    transfer(transfer_ctx, deposit_amount)?;
-    
+    transfer_checked(transfer_ctx, transfer_amount, decimals)?;
+
+    reserve_token_account.reload()?;
+    let reserve_balance_after = reserve_token_account.amount;
+    require!(
+        reserve_balance_after.checked_sub(reserve_balance_before) == Some(credited_amount),
+        ErrorCode::ReserveCreditMismatch
+    );
+
     if fee > 0 {
         let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                to: ctx.accounts.fee_recipient_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
+            token_program.to_account_info(),
+            TransferChecked {
+                from: user_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: fee_recipient_account.clone(),
+                authority: user.to_account_info(),
             },
         );
-        transfer(transfer_ctx, fee)?;
+        transfer_checked(transfer_ctx, fee, decimals)?;
     }
 
-    let next_index_to_insert = tree_account.next_index;
-    MerkleTree::append::<Poseidon>(proof.output_commitments[0], tree_account)?;
-    MerkleTree::append::<Poseidon>(proof.output_commitments[1], tree_account)?;
+    Ok(())
+}
 
-    emit!(CommitmentData {
-        index: next_index_to_insert,
-        commitment0: proof.output_commitments[0],
-        commitment1: proof.output_commitments[1],
-        encrypted_output: encrypted_output.to_vec(),
-    });
+/**
+ * Moves a native SOL deposit from the user's wallet into the WSOL reserve.
+ *
+ * Lamports are sent to the reserve token account and wrapped with sync_native, so the user
+ * does not need a WSOL account. The fee is wrapped along with the deposit and paid from the
+ * reserve in WSOL, like on the token path, so the fee recipient is always a WSOL token account.
+ */
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_native_deposit<'info>(
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    global_config: &Account<'info, GlobalConfig>,
+    reserve_token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    fee_recipient_account: &AccountInfo<'info>,
+    user: &Signer<'info>,
+    deposit_amount: u64,
+    fee: u64,
+) -> Result<()> {
+    let credited_amount = deposit_amount.checked_sub(fee)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let reserve_balance_before = reserve_token_account.amount;
+
+    let transfer_ctx = CpiContext::new(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: user.to_account_info(),
+            to: reserve_token_account.to_account_info(),
+        },
+    );
+    system_program::transfer(transfer_ctx, deposit_amount)?;
+
+    sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        SyncNative {
+            account: reserve_token_account.to_account_info(),
+        },
+    ))?;
+
+    if fee > 0 {
+        let global_config_seeds = &[
+            b"global_config".as_ref(),
+            &[global_config.bump],
+        ];
+        let signer_seeds = &[&global_config_seeds[..]];
+
+        let fee_transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: reserve_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: fee_recipient_account.clone(),
+                authority: global_config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(fee_transfer_ctx, fee, mint.decimals)?;
+    }
+
+    reserve_token_account.reload()?;
+    let reserve_balance_after = reserve_token_account.amount;
+    require!(
+        reserve_balance_after.checked_sub(reserve_balance_before) == Some(credited_amount),
+        ErrorCode::ReserveCreditMismatch
+    );
 
-    
     Ok(())
 }
-
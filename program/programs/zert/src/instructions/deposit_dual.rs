@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+use ark_ff::PrimeField;
+use ark_bn254::Fr;
+use light_hasher::Poseidon;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::deposit::transfer_deposit;
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::nullifier_set::NullifierSet;
+use crate::root_history::RootHistory;
+use crate::state::{MerkleTreeAccount, GlobalConfig, NullifierShard, RootHistoryAccount, VerifyingKeyAccount, VerifyingKeyStatus, PAUSE_DEPOSIT};
+use crate::types::{Proof, DualExtData, DualExtDataMinified, CommitmentData, NullifiersSpent, TransactionKind};
+use crate::ErrorCode;
+use crate::utils::verify_proof;
+use crate::utils;
+
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(proof: Proof, ext_data_minified: DualExtDataMinified, encrypted_output: Vec<u8>)]
+pub struct DepositDual<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
+        bump = tree_account.load()?.bump
+    )]
+    pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Tree the proof root belongs to, only needed when it is not the active tree.
+    pub root_tree_account: Option<AccountLoader<'info, MerkleTreeAccount>>,
+
+    /// Root history of the active tree, required once one is attached to it.
+    #[account(mut)]
+    pub root_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Root history of `root_tree_account`, required when that tree has one attached.
+    pub root_tree_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Nullifier shard holding the first input's nullifier, it must not contain it yet.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[0][31..].as_ref()],
+        bump = nullifier_shard0.load()?.bump
+    )]
+    pub nullifier_shard0: AccountLoader<'info, NullifierShard>,
+
+    /// Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[1][31..].as_ref()],
+        bump = nullifier_shard1.load()?.bump
+    )]
+    pub nullifier_shard1: AccountLoader<'info, NullifierShard>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
+    #[account(
+        seeds = [b"nullifier", proof.input_nullifiers[0].as_ref()],
+        bump
+    )]
+    pub legacy_nullifier0: UncheckedAccount<'info>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
+    #[account(
+        seeds = [b"nullifier", proof.input_nullifiers[1].as_ref()],
+        bump
+    )]
+    pub legacy_nullifier1: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Verifying key of the circuit version the proof was generated with
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    #[account(mint::token_program = token_program0)]
+    pub input_mint0: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mint::token_program = token_program1,
+        constraint = input_mint1.key() != input_mint0.key() @ ErrorCode::InvalidDualTokenMints
+    )]
+    pub input_mint1: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: config PDA of the first mint, empty when the mint is not registered, read by utils::load_mint_config
+    #[account(
+        seeds = [b"mint_config", input_mint0.key().as_ref()],
+        bump
+    )]
+    pub mint_config0: UncheckedAccount<'info>,
+    /// CHECK: config PDA of the second mint, empty when the mint is not registered, read by utils::load_mint_config
+    #[account(
+        seeds = [b"mint_config", input_mint1.key().as_ref()],
+        bump
+    )]
+    pub mint_config1: UncheckedAccount<'info>,
+
+    #[account(mut,
+        associated_token::mint = input_mint0,
+        associated_token::authority = global_config,
+        associated_token::token_program = token_program0,
+    )]
+    pub reserve_token_account0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut,
+        associated_token::mint = input_mint1,
+        associated_token::authority = global_config,
+        associated_token::token_program = token_program1,
+    )]
+    pub reserve_token_account1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: user should be able to send fees to any types of accounts
+    pub fee_recipient_account0: UncheckedAccount<'info>,
+    /// CHECK: user should be able to send fees to any types of accounts
+    pub fee_recipient_account1: UncheckedAccount<'info>,
+
+    #[account(mut,
+        associated_token::mint = input_mint0,
+        associated_token::authority = user,
+        associated_token::token_program = token_program0,
+    )]
+    pub user_token_account0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut,
+        associated_token::mint = input_mint1,
+        associated_token::authority = user,
+        associated_token::token_program = token_program1,
+    )]
+    pub user_token_account1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning `input_mint0`
+    pub token_program0: Interface<'info, TokenInterface>,
+    /// Token program owning `input_mint1`, may differ from the first one
+    pub token_program1: Interface<'info, TokenInterface>,
+}
+
+
+/**
+ * Shield two different mints with a single proof, e.g. SOL + USDC.
+ *
+ * publicAmount0 = ext_amount0 - fee0 is credited to the reserve of input_mint0 and
+ * publicAmount1 = ext_amount1 - fee1 to the reserve of input_mint1.
+ */
+pub fn handler(
+    ctx: Context<DepositDual>,
+    proof: Proof,
+    ext_data_minified: DualExtDataMinified,
+    encrypted_output: Vec<u8>,
+) -> Result<()> {
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
+    let global_config = &ctx.accounts.global_config;
+    require!(!global_config.is_paused(PAUSE_DEPOSIT), ErrorCode::InstructionPaused);
+    let mint_config0 = utils::load_mint_config(&ctx.accounts.mint_config0)?;
+    let mint_config1 = utils::load_mint_config(&ctx.accounts.mint_config1)?;
+
+    // Reconstruct full DualExtData from minified version and context accounts
+    let ext_data = DualExtData::from_minified(
+        &ctx.accounts.reserve_token_account0.key(),
+        &ctx.accounts.fee_recipient_account0.key(),
+        &ctx.accounts.fee_recipient_account1.key(),
+        ext_data_minified,
+    );
+
+    // Check if proof.root is in the proof history of the tree it references
+    utils::check_known_root(
+        tree_account,
+        ctx.accounts.tree_account.key(),
+        ctx.accounts.root_history_account.as_ref(),
+        ctx.accounts.root_tree_account.as_ref(),
+        ctx.accounts.root_tree_history_account.as_ref(),
+        proof.root,
+        global_config.root_validity_slots,
+        ctx.accounts.verifying_key.tree_height,
+    )?;
+
+    // Check if the ext_data hashes to the same ext_data in the proof
+    let calculated_ext_data_hash = utils::calculate_dual_ext_data_hash(
+        &ext_data,
+        &encrypted_output,
+        ctx.accounts.input_mint0.key(),
+        ctx.accounts.input_mint1.key(),
+    )?;
+    require!(
+        Fr::from_le_bytes_mod_order(&calculated_ext_data_hash) == Fr::from_be_bytes_mod_order(&proof.ext_data_hash),
+        ErrorCode::ExtDataHashMismatch
+    );
+
+    require!(ext_data.ext_amount0 > 0 && ext_data.ext_amount1 > 0, ErrorCode::InvalidExtAmount);
+    require!(
+        utils::check_public_amount(ext_data.ext_amount0, ext_data.fee0, proof.public_amount0),
+        ErrorCode::InvalidPublicAmountData
+    );
+    require!(
+        utils::check_public_amount(ext_data.ext_amount1, ext_data.fee1, proof.public_amount1),
+        ErrorCode::InvalidPublicAmountData
+    );
+
+    // Each leg pays the deposit fee in its own mint
+    for (ext_amount, fee) in [(ext_data.ext_amount0, ext_data.fee0), (ext_data.ext_amount1, ext_data.fee1)] {
+        utils::validate_fee(
+            ext_amount,
+            fee,
+            global_config.deposit_fee_rate,
+            global_config.withdrawal_fee_rate,
+            global_config.fee_error_margin,
+        )?;
+    }
+
+    // Verify the proof with both mint addresses
+    require!(
+        verify_proof(
+            proof.clone(),
+            ctx.accounts.verifying_key.groth16_verifying_key(),
+            ctx.accounts.input_mint0.key(),
+            ctx.accounts.input_mint1.key()
+        ),
+        ErrorCode::InvalidProof
+    );
+
+    // Mark both inputs as spent, this fails if either nullifier was used before
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard0,
+        &ctx.accounts.legacy_nullifier0,
+        proof.input_nullifiers[0],
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard1,
+        &ctx.accounts.legacy_nullifier1,
+        proof.input_nullifiers[1],
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let deposit_amount0 = ext_data.ext_amount0 as u64;
+    let deposit_amount1 = ext_data.ext_amount1 as u64;
+
+    utils::check_deposit_limits(&mint_config0, deposit_amount0)?;
+    utils::check_deposit_limits(&mint_config1, deposit_amount1)?;
+
+    transfer_deposit(
+        &ctx.accounts.token_program0,
+        &ctx.accounts.input_mint0,
+        &ctx.accounts.user_token_account0,
+        &mut ctx.accounts.reserve_token_account0,
+        &ctx.accounts.fee_recipient_account0,
+        &ctx.accounts.user,
+        deposit_amount0,
+        ext_data.fee0,
+    )?;
+    transfer_deposit(
+        &ctx.accounts.token_program1,
+        &ctx.accounts.input_mint1,
+        &ctx.accounts.user_token_account1,
+        &mut ctx.accounts.reserve_token_account1,
+        &ctx.accounts.fee_recipient_account1,
+        &ctx.accounts.user,
+        deposit_amount1,
+        ext_data.fee1,
+    )?;
+
+    let next_index_to_insert = tree_account.next_index;
+    MerkleTree::append_pair::<Poseidon>(proof.output_commitments, tree_account, Clock::get()?.slot)?;
+    RootHistory::record(tree_account, ctx.accounts.root_history_account.as_ref())?;
+
+    emit_cpi!(NullifiersSpent::new(TransactionKind::DepositDual, &proof)?);
+    emit_cpi!(CommitmentData::new(
+        TransactionKind::DepositDual,
+        tree_account,
+        next_index_to_insert,
+        &proof,
+        ctx.accounts.input_mint0.key(),
+        ctx.accounts.input_mint1.key(),
+        encrypted_output,
+    )?);
+
+    Ok(())
+}
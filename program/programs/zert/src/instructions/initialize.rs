@@ -1,37 +1,50 @@
 use anchor_lang::prelude::*;
-use crate::merkle_tree::MerkleTree;
-use crate::state::MERKLE_TREE_HEIGHT;
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::state::{GlobalConfig, VerifyingKeyAccount, MERKLE_TREE_VERSION};
+use crate::utils;
 use crate::ADMIN_PUBKEY;
 use crate::ErrorCode;
 use light_hasher::Poseidon;
 
-pub fn handler(ctx: Context<crate::Initialize>) -> Result<()> { 
+/**
+ * Initialize the pool with a first merkle tree of `height` keeping its last `root_history_size` roots.
+ *
+ * The genesis verifying key is the only active key of a new pool and checks merkle proofs of MERKLE_TREE_HEIGHT
+ * levels, so the first tree must have that height. A pool moves to another height by registering and activating
+ * the verifying key of a circuit of that depth, then rolling over to a tree of that height with rollover_tree.
+ */
+pub fn handler(ctx: Context<crate::Initialize>, height: u8, root_history_size: u8) -> Result<()> { 
     if let Some(admin_key) = ADMIN_PUBKEY {
         require!(ctx.accounts.authority.key().eq(&admin_key), ErrorCode::Unauthorized);
     }
+    let genesis_verifying_key = VerifyingKeyAccount::genesis(ctx.bumps.verifying_key);
+    utils::check_initial_tree(height, root_history_size, genesis_verifying_key.tree_height)?;
     
-    let tree_account = &mut ctx.accounts.tree_account.load_init()?;
-    tree_account.authority = ctx.accounts.authority.key();
-    tree_account.next_index = 0;
-    tree_account.root_index = 0;
-    tree_account.bump = ctx.bumps.tree_account;
-    tree_account.max_deposit_amount = 1_000_000_000_000; // 1000 SOL default limit
-    tree_account.height = MERKLE_TREE_HEIGHT; // Hardcoded height
-    tree_account.root_history_size = 100; // Hardcoded root history size
+    {
+        let tree_account = &mut ctx.accounts.tree_account.load_init()?;
+        tree_account.root_history_account = Pubkey::default();
+        tree_account.next_index = 0;
+        tree_account.tree_id = 0;
+        tree_account.root_index = 0;
+        tree_account.bump = ctx.bumps.tree_account;
+        tree_account.height = height;
+        tree_account.root_history_size = root_history_size;
+        tree_account.version = MERKLE_TREE_VERSION;
+    }
 
-    MerkleTree::initialize::<Poseidon>(tree_account)?;
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    MerkleTree::initialize::<Poseidon>(&mut MerkleTreeData::from_bytes(&mut tree_account_info.try_borrow_mut_data()?)?)?;
     
     // Initialize global config
-    let global_config = &mut ctx.accounts.global_config;
-    global_config.authority = ctx.accounts.authority.key();
-    global_config.deposit_fee_rate = 0; // 0% - Free deposits
-    global_config.withdrawal_fee_rate = 25; // 0.25% (25 basis points)
-    global_config.fee_error_margin = 500; // 5% (500 basis points)
-    global_config.bump = ctx.bumps.global_config;
+    ctx.accounts.global_config.set_inner(GlobalConfig::new(ctx.accounts.authority.key(), ctx.bumps.global_config));
+    let global_config = &ctx.accounts.global_config;
+
+    // Register the genesis circuit as verifying key version 0
+    ctx.accounts.verifying_key.set_inner(genesis_verifying_key);
     
-    msg!("Sparse Merkle Tree initialized successfully with height: {}, root history size: {}, deposit limit: {} lamports, 
+    msg!("Sparse Merkle Tree initialized successfully with height: {}, root history size: {}, 
         deposit fee rate: {}, withdrawal fee rate: {}, fee error margin: {}",
-        MERKLE_TREE_HEIGHT, 100, tree_account.max_deposit_amount, global_config.deposit_fee_rate, global_config.withdrawal_fee_rate, global_config.fee_error_margin);
+        height, root_history_size, global_config.deposit_fee_rate, global_config.withdrawal_fee_rate, global_config.fee_error_margin);
     Ok(())
 }
 
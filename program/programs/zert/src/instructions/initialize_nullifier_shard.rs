@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/**
+ * Create one of the 256 nullifier shards. Anyone can call this, the shard only grows when nullifiers are spent.
+ */
+pub fn handler(ctx: Context<crate::InitializeNullifierShard>, shard_id: u8) -> Result<()> {
+    let nullifier_shard = &mut ctx.accounts.nullifier_shard.load_init()?;
+    nullifier_shard.count = 0;
+    nullifier_shard.shard_id = shard_id;
+    nullifier_shard.bump = ctx.bumps.nullifier_shard;
+
+    msg!("Nullifier shard {} initialized", shard_id);
+    Ok(())
+}
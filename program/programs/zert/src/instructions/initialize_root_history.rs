@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::merkle_tree::MerkleTreeData;
+use crate::root_history::{RootHistory, ROOT_ENTRY_SIZE};
+use crate::ErrorCode;
+
+/**
+ * Attach a root history account to the active merkle tree. Only the authority can call this.
+ *
+ * The account is too large to be created through a CPI, so the client creates it beforehand with
+ * RootHistory::space(capacity) bytes and this program as owner. It is seeded with the roots the tree
+ * still remembers, and from then on every transaction records its new root in it.
+ */
+pub fn handler(ctx: Context<crate::InitializeRootHistory>) -> Result<()> {
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
+    require!(
+        tree_account.root_history_account == Pubkey::default(),
+        ErrorCode::RootHistoryAlreadyAttached
+    );
+
+    let root_history_info = ctx.accounts.root_history_account.to_account_info();
+    let capacity = RootHistory::capacity(root_history_info.data_len());
+    require!(
+        (tree_account.root_history_size as usize..=u32::MAX as usize).contains(&capacity),
+        ErrorCode::InvalidRootHistoryCapacity
+    );
+
+    // Roots recorded before the history existed are stamped with the current slot
+    let slot = Clock::get()?.slot;
+    let (head, count) = {
+        let mut data = root_history_info.try_borrow_mut_data()?;
+        let (entries, index) = data[RootHistory::ENTRIES_OFFSET..].split_at_mut(capacity * ROOT_ENTRY_SIZE);
+        RootHistory::seed(entries, index, tree_account.recent_roots(), slot)
+    };
+
+    let root_history = &mut ctx.accounts.root_history_account.load_init()?;
+    root_history.tree = ctx.accounts.tree_account.key();
+    root_history.capacity = capacity as u32;
+    root_history.head = head as u32;
+    root_history.count = count as u32;
+
+    tree_account.root_history_account = ctx.accounts.root_history_account.key();
+
+    msg!("Root history of merkle tree {} initialized with capacity {}", tree_account.tree_id, capacity);
+    Ok(())
+}
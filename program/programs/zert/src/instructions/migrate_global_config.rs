@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::legacy::LegacyGlobalConfig;
+use crate::state::{GlobalConfig, VerifyingKeyAccount};
+use crate::ErrorCode;
+
+/**
+ * Upgrade the global config of a legacy pool to the current layout. Only the authority can call this.
+ *
+ * The account is reallocated in place, the authority paying for the additional rent. The fee settings are kept
+ * and everything added since gets the defaults of a new pool. The genesis circuit is registered as verifying key
+ * version 0 like initialize does. Then migrate_legacy_tree carries the tree over.
+ */
+pub fn handler(ctx: Context<crate::MigrateGlobalConfig>) -> Result<()> {
+    let global_config_info = ctx.accounts.global_config.to_account_info();
+    let global_config = LegacyGlobalConfig::from_bytes(&global_config_info.try_borrow_data()?)?.upgrade();
+    require_keys_eq!(global_config.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+    let new_len = 8 + std::mem::size_of::<GlobalConfig>();
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(global_config_info.lamports());
+    if rent_due > 0 {
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: global_config_info.clone(),
+            },
+        );
+        system_program::transfer(transfer_ctx, rent_due)?;
+    }
+    global_config_info.resize(new_len)?;
+    global_config.try_serialize(&mut &mut global_config_info.try_borrow_mut_data()?[..])?;
+
+    ctx.accounts.verifying_key.set_inner(VerifyingKeyAccount::genesis(ctx.bumps.verifying_key));
+
+    msg!("Global config migrated to version {}", global_config.version);
+    Ok(())
+}
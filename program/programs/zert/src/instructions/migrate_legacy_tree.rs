@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::legacy::LegacyMerkleTreeAccount;
+use crate::merkle_tree::MerkleTreeData;
+use crate::state::MERKLE_TREE_VERSION;
+use crate::ErrorCode;
+
+/**
+ * Carry the merkle tree of a legacy pool, stored at the [b"merkle_tree"] address, over to tree 0.
+ * Only the authority of the legacy tree can call this.
+ *
+ * Its leaves, subtrees and root history are copied, so existing notes remain spendable and new commitments
+ * extend the same tree. Legacy roots did not record their slot, they expire as if recorded at the migration.
+ * The legacy account is then closed and its rent returned to the authority.
+ * Its max_deposit_amount is not carried over, deposit limits are set per mint with register_mint.
+ */
+pub fn handler(ctx: Context<crate::MigrateLegacyTree>) -> Result<()> {
+    let legacy_tree_info = ctx.accounts.legacy_tree_account.to_account_info();
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let next_index = {
+        let legacy_tree_data = legacy_tree_info.try_borrow_data()?;
+        let legacy_tree = LegacyMerkleTreeAccount::from_bytes(&legacy_tree_data)?;
+        require_keys_eq!(legacy_tree.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+        {
+            let tree_account = &mut ctx.accounts.tree_account.load_init()?;
+            tree_account.root_history_account = Pubkey::default();
+            tree_account.tree_id = 0;
+            tree_account.bump = ctx.bumps.tree_account;
+            tree_account.height = legacy_tree.height;
+            tree_account.root_history_size = legacy_tree.root_history_size;
+            tree_account.version = MERKLE_TREE_VERSION;
+        }
+
+        let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+        legacy_tree.upgrade(&mut MerkleTreeData::from_bytes(&mut tree_account_data)?, Clock::get()?.slot)?;
+        legacy_tree.next_index
+    };
+
+    // Close the legacy tree so it can't be migrated twice
+    let authority_info = ctx.accounts.authority.to_account_info();
+    **authority_info.try_borrow_mut_lamports()? += legacy_tree_info.lamports();
+    **legacy_tree_info.try_borrow_mut_lamports()? = 0;
+    legacy_tree_info.resize(0)?;
+    legacy_tree_info.assign(&System::id());
+
+    msg!("Legacy merkle tree migrated to tree 0 with {} leaves", next_index);
+    Ok(())
+}
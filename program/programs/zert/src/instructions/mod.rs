@@ -1,13 +1,39 @@
+#![allow(ambiguous_glob_reexports)] // every module has a handler, called through its module path
 pub mod initialize;
 pub mod deposit;
-pub mod update_deposit_limit;
+pub mod deposit_dual;
 pub mod update_global_config;
+pub mod set_pause;
+pub mod propose_authority;
+pub mod accept_authority;
+pub mod migrate_global_config;
+pub mod migrate_legacy_tree;
+pub mod rollover_tree;
+pub mod retire_tree;
+pub mod initialize_root_history;
+pub mod initialize_nullifier_shard;
+pub mod register_verifying_key;
+pub mod write_verifying_key_ic;
+pub mod set_verifying_key_status;
+pub mod register_relayer;
+pub mod set_relayer_status;
+pub mod stake_relayer;
+pub mod request_unstake;
+pub mod unstake_relayer;
+pub mod slash_relayer;
+pub mod add_swap_program;
+pub mod remove_swap_program;
+pub mod register_mint;
+pub mod update_mint_config;
 pub mod swap;
-pub mod withdraw;   
+pub mod withdraw;
+pub mod withdraw_dual;
+pub mod shielded_transfer;
 
-pub use initialize::*;
+// Accounts structs defined next to their handler, #[program] looks them up at the crate root
 pub use deposit::*;
-pub use update_deposit_limit::*;
-pub use update_global_config::*;
+pub use deposit_dual::*;
 pub use swap::*;
-pub use withdraw::*;
\ No newline at end of file
+pub use withdraw::*;
+pub use withdraw_dual::*;
+pub use shielded_transfer::*;
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/**
+ * Propose a new authority for the pool. Only the authority can call this.
+ *
+ * The transfer only takes effect once the new authority calls accept_authority.
+ * Proposing Pubkey::default() cancels a pending transfer.
+ */
+pub fn handler(ctx: Context<crate::ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.pending_authority = new_authority;
+
+    msg!("Authority transfer proposed to: {}", new_authority);
+    Ok(())
+}
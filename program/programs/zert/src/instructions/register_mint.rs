@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::ErrorCode;
+
+/**
+ * Register a mint so that it can be deposited and swapped into. Only the authority can call this.
+ *
+ * Limits, the minimum shielded transfer fee and the fee reimbursing the recipient token accounts
+ * relayers create on withdrawal are expressed in raw token units of the mint.
+ */
+pub fn handler(
+    ctx: Context<crate::RegisterMint>,
+    max_deposit_amount: u64,
+    min_deposit_amount: u64,
+    min_transfer_fee: u64,
+    account_creation_fee: u64,
+) -> Result<()> {
+    require!(min_deposit_amount <= max_deposit_amount, ErrorCode::InvalidDepositLimits);
+
+    let mint_config = &mut ctx.accounts.mint_config;
+    mint_config.mint = ctx.accounts.mint.key();
+    mint_config.max_deposit_amount = max_deposit_amount;
+    mint_config.min_deposit_amount = min_deposit_amount;
+    mint_config.enabled = true;
+    mint_config.max_outflow_per_window = 0; // unlimited until configured
+    mint_config.window_start_slot = 0;
+    mint_config.current_window_outflow = 0;
+    mint_config.previous_window_outflow = 0;
+    mint_config.price_feed_id = [0u8; 32]; // no oracle check until configured
+    mint_config.min_transfer_fee = min_transfer_fee;
+    mint_config.account_creation_fee = account_creation_fee;
+    mint_config.bump = ctx.bumps.mint_config;
+
+    msg!("Mint {} registered with deposit limits: min {}, max {}, min transfer fee {}, account creation fee {}",
+        mint_config.mint, min_deposit_amount, max_deposit_amount, min_transfer_fee, account_creation_fee);
+    Ok(())
+}
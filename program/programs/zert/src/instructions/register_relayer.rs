@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+/**
+ * Register a relayer so that it can submit withdrawals, swaps and transfers. Only the authority can call this.
+ */
+pub fn handler(ctx: Context<crate::RegisterRelayer>, relayer: Pubkey) -> Result<()> {
+    let relayer_account = &mut ctx.accounts.relayer_account;
+    relayer_account.relayer = relayer;
+    relayer_account.active = true;
+    relayer_account.stake = 0;
+    relayer_account.pending_unstake = 0;
+    relayer_account.unstake_requested_slot = 0;
+    relayer_account.bump = ctx.bumps.relayer_account;
+
+    msg!("Relayer {} registered", relayer);
+    Ok(())
+}
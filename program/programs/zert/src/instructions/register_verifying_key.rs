@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{VerifyingKeyStatus, MAX_MERKLE_TREE_HEIGHT};
+use crate::ErrorCode;
+
+/**
+ * Register the verifying key of a new circuit version. Only the authority can call this.
+ *
+ * The key starts out pending: its IC points are written with write_verifying_key_ic,
+ * and proofs are only accepted against it once it has been activated.
+ * `tree_height` is the depth of the merkle proofs the circuit checks, it only verifies proofs of trees of that height.
+ */
+pub fn handler(
+    ctx: Context<crate::RegisterVerifyingKey>,
+    version: u32,
+    tree_height: u8,
+    vk_alpha_g1: [u8; 64],
+    vk_beta_g2: [u8; 128],
+    vk_gamma_g2: [u8; 128],
+    vk_delta_g2: [u8; 128],
+) -> Result<()> {
+    require!(
+        (1..=MAX_MERKLE_TREE_HEIGHT).contains(&tree_height),
+        ErrorCode::InvalidTreeHeight
+    );
+
+    let verifying_key = &mut ctx.accounts.verifying_key;
+    verifying_key.version = version;
+    verifying_key.status = VerifyingKeyStatus::Pending;
+    verifying_key.tree_height = tree_height;
+    verifying_key.vk_alpha_g1 = vk_alpha_g1;
+    verifying_key.vk_beta_g2 = vk_beta_g2;
+    verifying_key.vk_gamma_g2 = vk_gamma_g2;
+    verifying_key.vk_delta_g2 = vk_delta_g2;
+    verifying_key.bump = ctx.bumps.verifying_key;
+
+    msg!("Verifying key version {} registered for trees of height {}", version, tree_height);
+    Ok(())
+}
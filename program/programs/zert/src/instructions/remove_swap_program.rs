@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use crate::ErrorCode;
+
+/**
+ * Revoke a previously approved swap program. Only the authority can call this.
+ */
+pub fn handler(ctx: Context<crate::RemoveSwapProgram>, program_id: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.swap_program_registry;
+
+    let position = registry.programs.iter()
+        .position(|program| *program == program_id)
+        .ok_or(ErrorCode::UnknownSwapProgram)?;
+    registry.programs.remove(position);
+
+    msg!("Swap program {} removed", program_id);
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use crate::ErrorCode;
+
+/**
+ * Request the withdrawal of lamports from the stake of the calling relayer.
+ *
+ * The lamports stop counting towards the stake right away but stay in the relayer account,
+ * where the authority can still slash them, until unstake_relayer withdraws them
+ * RELAYER_UNSTAKE_COOLDOWN_SLOTS later. Every request restarts the cooldown of all pending lamports.
+ */
+pub fn handler(ctx: Context<crate::RequestUnstake>, amount: u64) -> Result<()> {
+    let relayer_account = &mut ctx.accounts.relayer_account;
+    require!(amount > 0 && amount <= relayer_account.stake, ErrorCode::InsufficientRelayerStake);
+
+    relayer_account.stake -= amount;
+    relayer_account.pending_unstake = relayer_account.pending_unstake.checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    relayer_account.unstake_requested_slot = Clock::get()?.slot;
+
+    msg!("Relayer {} requested to unstake {} lamports, pending: {}, remaining stake: {}",
+        relayer_account.relayer, amount, relayer_account.pending_unstake, relayer_account.stake);
+    Ok(())
+}
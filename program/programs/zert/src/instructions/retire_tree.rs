@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::ErrorCode;
+
+/**
+ * Stop accepting proofs against the roots of a previous tree. Only the authority can call this.
+ *
+ * Notes that were never moved out of a retired tree can no longer be spent.
+ */
+pub fn handler(ctx: Context<crate::RetireTree>) -> Result<()> {
+    let tree_account = &mut ctx.accounts.tree_account.load_mut()?;
+
+    require!(
+        tree_account.tree_id != ctx.accounts.global_config.active_tree_id,
+        ErrorCode::CannotRetireActiveTree
+    );
+
+    tree_account.retired = 1;
+
+    msg!("Merkle tree {} retired", tree_account.tree_id);
+    Ok(())
+}
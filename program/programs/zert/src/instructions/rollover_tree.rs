@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::state::MERKLE_TREE_VERSION;
+use crate::ErrorCode;
+use light_hasher::Poseidon;
+
+/**
+ * Switch new commitments to a fresh merkle tree of `height` keeping its last `root_history_size` roots.
+ * The authority can roll over at any time and to any height an active verifying key supports, e.g. to move
+ * a test pool to a shallower tree. Anyone else can only roll over once the active tree is full, to a tree
+ * of the same shape, so the pool can never get stuck.
+ *
+ * The previous tree keeps its root history, so notes stored in it remain spendable.
+ */
+pub fn handler(ctx: Context<crate::RolloverTree>, height: u8, root_history_size: u8) -> Result<()> {
+    let current_tree = ctx.accounts.current_tree_account.load()?;
+    let global_config = &mut ctx.accounts.global_config;
+
+    let same_shape = height == current_tree.height && root_history_size == current_tree.root_history_size;
+    require!(
+        ctx.accounts.payer.key() == global_config.authority || (current_tree.is_full() && same_shape),
+        ErrorCode::Unauthorized
+    );
+
+    let new_tree_id = global_config.active_tree_id
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    {
+        let new_tree = &mut ctx.accounts.new_tree_account.load_init()?;
+        new_tree.root_history_account = Pubkey::default();
+        new_tree.next_index = 0;
+        new_tree.tree_id = new_tree_id;
+        new_tree.root_index = 0;
+        new_tree.bump = ctx.bumps.new_tree_account;
+        new_tree.height = height;
+        new_tree.root_history_size = root_history_size;
+        new_tree.version = MERKLE_TREE_VERSION;
+    }
+
+    let new_tree_info = ctx.accounts.new_tree_account.to_account_info();
+    MerkleTree::initialize::<Poseidon>(&mut MerkleTreeData::from_bytes(&mut new_tree_info.try_borrow_mut_data()?)?)?;
+
+    global_config.active_tree_id = new_tree_id;
+
+    msg!("Merkle tree rolled over from tree {} ({} leaves) to tree {} of height {}",
+        current_tree.tree_id, current_tree.next_index, new_tree_id, height);
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::state::PAUSE_ALL;
+use crate::ErrorCode;
+
+/**
+ * Set the pause flags of the pool. The authority can set any combination of flags,
+ * the guardian can only pause more instructions, never unpause them.
+ */
+pub fn handler(ctx: Context<crate::SetPause>, paused: u8) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let signer = ctx.accounts.signer.key();
+
+    require!(paused & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+
+    if signer != global_config.authority {
+        require!(
+            global_config.guardian != Pubkey::default() && signer == global_config.guardian,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            paused & global_config.paused == global_config.paused,
+            ErrorCode::Unauthorized
+        );
+    }
+
+    global_config.paused = paused;
+
+    msg!("Pause flags updated to: {:#06b}", paused);
+    Ok(())
+}
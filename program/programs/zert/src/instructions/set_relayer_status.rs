@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/**
+ * List or delist a relayer. Only the authority can call this.
+ *
+ * Delisting does not release the stake: it is withdrawn through request_unstake and unstake_relayer,
+ * and can be slashed until then.
+ */
+pub fn handler(ctx: Context<crate::SetRelayerStatus>, active: bool) -> Result<()> {
+    let relayer_account = &mut ctx.accounts.relayer_account;
+    relayer_account.active = active;
+
+    msg!("Relayer {} active updated to: {}", relayer_account.relayer, active);
+    Ok(())
+}
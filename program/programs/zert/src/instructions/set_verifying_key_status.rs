@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use crate::state::VerifyingKeyStatus;
+use crate::ErrorCode;
+
+/**
+ * Activate or retire a verifying key. Only the authority can call this.
+ *
+ * Several versions can be active at once so that proofs generated against the
+ * previous circuit keep working while clients upgrade. A key cannot go back to pending.
+ */
+pub fn handler(
+    ctx: Context<crate::SetVerifyingKeyStatus>,
+    status: VerifyingKeyStatus,
+) -> Result<()> {
+    require!(status != VerifyingKeyStatus::Pending, ErrorCode::InvalidVerifyingKeyStatus);
+
+    let verifying_key = &mut ctx.accounts.verifying_key;
+    verifying_key.status = status;
+
+    msg!("Verifying key version {} status updated to: {:?}", verifying_key.version, status);
+    Ok(())
+}
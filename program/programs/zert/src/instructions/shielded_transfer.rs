@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use ark_ff::PrimeField;
+use ark_bn254::Fr;
+use light_hasher::Poseidon;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::nullifier_set::NullifierSet;
+use crate::root_history::RootHistory;
+use crate::state::{MerkleTreeAccount, GlobalConfig, MintConfig, NullifierShard, RootHistoryAccount, RelayerAccount, VerifyingKeyAccount, VerifyingKeyStatus, PAUSE_TRANSFER};
+use crate::types::{Proof, ExtData, ExtDataMinified, CommitmentData, NullifiersSpent, TransactionKind};
+use crate::ErrorCode;
+use crate::utils::verify_proof;
+use crate::utils;
+
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(proof: Proof, ext_data_minified: ExtDataMinified, encrypted_output: Vec<u8>)]
+pub struct ShieldedTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
+        bump = tree_account.load()?.bump
+    )]
+    pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Tree the proof root belongs to, only needed when it is not the active tree.
+    pub root_tree_account: Option<AccountLoader<'info, MerkleTreeAccount>>,
+
+    /// Root history of the active tree, required once one is attached to it.
+    #[account(mut)]
+    pub root_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Root history of `root_tree_account`, required when that tree has one attached.
+    pub root_tree_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Nullifier shard holding the first input's nullifier, it must not contain it yet.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[0][31..].as_ref()],
+        bump = nullifier_shard0.load()?.bump
+    )]
+    pub nullifier_shard0: AccountLoader<'info, NullifierShard>,
+
+    /// Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[1][31..].as_ref()],
+        bump = nullifier_shard1.load()?.bump
+    )]
+    pub nullifier_shard1: AccountLoader<'info, NullifierShard>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
+    #[account(
+        seeds = [b"nullifier", proof.input_nullifiers[0].as_ref()],
+        bump
+    )]
+    pub legacy_nullifier0: UncheckedAccount<'info>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
+    #[account(
+        seeds = [b"nullifier", proof.input_nullifiers[1].as_ref()],
+        bump
+    )]
+    pub legacy_nullifier1: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Verifying key of the circuit version the proof was generated with
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    #[account(mint::token_program = token_program)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", input_mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    #[account(mut,
+        associated_token::mint = input_mint,
+        associated_token::authority = global_config,
+        associated_token::token_program = token_program,
+    )]
+    pub reserve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Fee token account of the relayer
+    #[account(
+        mut,
+        constraint = fee_recipient_account.owner == relayer.key() @ ErrorCode::InvalidFeeRecipient,
+        constraint = fee_recipient_account.mint == input_mint.key() @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_recipient_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Registry entry of the relayer submitting this transaction
+    #[account(
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_account.bump,
+        constraint = relayer_account.is_eligible(global_config.min_relayer_stake) @ ErrorCode::RelayerNotActive
+    )]
+    pub relayer_account: Box<Account<'info, RelayerAccount>>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+
+/**
+ * Spend notes into new notes without moving tokens in or out of the pool, e.g. to pay
+ * another shielded recipient or to merge UTXOs. Submitted by a relayer.
+ *
+ * ext_amount must be zero, so the only value leaving the reserve is the fee, which is
+ * paid from the shielded inputs (publicAmount0 = -fee) and must be at least the mint's min_transfer_fee.
+ * The mint must be enabled. Nothing is sent to a recipient, so the ext data hash is computed with
+ * the default pubkey as recipient.
+ */
+pub fn handler(
+    ctx: Context<ShieldedTransfer>,
+    proof: Proof,
+    ext_data_minified: ExtDataMinified,
+    encrypted_output: Vec<u8>,
+) -> Result<()> {
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
+    let global_config = &ctx.accounts.global_config;
+    require!(!global_config.is_paused(PAUSE_TRANSFER), ErrorCode::InstructionPaused);
+
+    let ext_data = ExtData::from_minified(
+        &Pubkey::default(),
+        &ctx.accounts.fee_recipient_account.key(),
+        ext_data_minified,
+    );
+    utils::check_transfer(&ctx.accounts.mint_config, ext_data.ext_amount, ext_data.fee)?;
+
+    // Check if proof.root is in the proof history of the tree it references
+    utils::check_known_root(
+        tree_account,
+        ctx.accounts.tree_account.key(),
+        ctx.accounts.root_history_account.as_ref(),
+        ctx.accounts.root_tree_account.as_ref(),
+        ctx.accounts.root_tree_history_account.as_ref(),
+        proof.root,
+        global_config.root_validity_slots,
+        ctx.accounts.verifying_key.tree_height,
+    )?;
+
+    // Check if the ext_data hashes to the same ext_data in the proof
+    let calculated_ext_data_hash = utils::calculate_complete_ext_data_hash(
+        ext_data.recipient,
+        ext_data.ext_amount,
+        &encrypted_output,
+        ext_data.fee,
+        ext_data.fee_recipient,
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.input_mint.key(),
+    )?;
+    require!(
+        Fr::from_le_bytes_mod_order(&calculated_ext_data_hash) == Fr::from_be_bytes_mod_order(&proof.ext_data_hash),
+        ErrorCode::ExtDataHashMismatch
+    );
+
+    // publicAmount0 = -fee, publicAmount1 is unused in single-token mode
+    require!(
+        utils::check_public_amount(ext_data.ext_amount, ext_data.fee, proof.public_amount0),
+        ErrorCode::InvalidPublicAmountData
+    );
+    require!(proof.public_amount1 == [0; 32], ErrorCode::InvalidPublicAmountData);
+
+    require!(verify_proof(proof.clone(), ctx.accounts.verifying_key.groth16_verifying_key(), ctx.accounts.input_mint.key(), ctx.accounts.input_mint.key()), ErrorCode::InvalidProof);
+
+    // Mark both inputs as spent, this fails if either nullifier was used before
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard0,
+        &ctx.accounts.legacy_nullifier0,
+        proof.input_nullifiers[0],
+        &ctx.accounts.relayer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard1,
+        &ctx.accounts.legacy_nullifier1,
+        proof.input_nullifiers[1],
+        &ctx.accounts.relayer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let fee = ext_data.fee;
+    if fee > 0 {
+        require!(
+            ctx.accounts.reserve_token_account.amount >= fee,
+            ErrorCode::InsufficientFundsForFee
+        );
+
+        utils::record_outflow(
+            &mut ctx.accounts.mint_config,
+            fee,
+            Clock::get()?.slot,
+            global_config.outflow_window_slots,
+        )?;
+
+        let global_config_seeds = &[
+            b"global_config".as_ref(),
+            &[global_config.bump],
+        ];
+        let signer_seeds = &[&global_config_seeds[..]];
+
+        let fee_transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reserve_token_account.to_account_info(),
+                mint: ctx.accounts.input_mint.to_account_info(),
+                to: ctx.accounts.fee_recipient_account.to_account_info(),
+                authority: ctx.accounts.global_config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(fee_transfer_ctx, fee, ctx.accounts.input_mint.decimals)?;
+    }
+
+    let next_index_to_insert = tree_account.next_index;
+    MerkleTree::append_pair::<Poseidon>(proof.output_commitments, tree_account, Clock::get()?.slot)?;
+    RootHistory::record(tree_account, ctx.accounts.root_history_account.as_ref())?;
+
+    emit_cpi!(NullifiersSpent::new(TransactionKind::Transfer, &proof)?);
+    emit_cpi!(CommitmentData::new(
+        TransactionKind::Transfer,
+        tree_account,
+        next_index_to_insert,
+        &proof,
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.input_mint.key(),
+        encrypted_output,
+    )?);
+
+    Ok(())
+}
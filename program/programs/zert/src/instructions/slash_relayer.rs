@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use crate::utils;
+
+/**
+ * Slash lamports from the stake of a misbehaving relayer. Only the authority can call this.
+ *
+ * Lamports pending withdrawal are slashed first, so that a relayer can't escape by requesting
+ * to unstake. The slashed lamports go to the authority, to compensate the affected users.
+ */
+pub fn handler(ctx: Context<crate::SlashRelayer>, amount: u64) -> Result<()> {
+    let relayer_account = &mut ctx.accounts.relayer_account;
+    utils::slash_stake(relayer_account, amount)?;
+
+    // The relayer account is owned by this program, so lamports can be moved directly
+    let relayer_account_info = relayer_account.to_account_info();
+    **relayer_account_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    msg!("Relayer {} slashed {} lamports, remaining stake: {}, pending unstake: {}",
+        relayer_account.relayer, amount, relayer_account.stake, relayer_account.pending_unstake);
+    Ok(())
+}
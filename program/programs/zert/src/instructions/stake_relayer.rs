@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::ErrorCode;
+
+/**
+ * Add lamports to the stake of the calling relayer.
+ */
+pub fn handler(ctx: Context<crate::StakeRelayer>, amount: u64) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.relayer.to_account_info(),
+                to: ctx.accounts.relayer_account.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let relayer_account = &mut ctx.accounts.relayer_account;
+    relayer_account.stake = relayer_account.stake.checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    msg!("Relayer {} staked {} lamports, total stake: {}", relayer_account.relayer, amount, relayer_account.stake);
+    Ok(())
+}
@@ -2,83 +2,172 @@ use anchor_lang::prelude::*;
 use ark_ff::PrimeField;
 use ark_bn254::Fr;
 use light_hasher::Poseidon;
-use anchor_spl::token_interface::{Mint, TokenAccount};
-use anchor_spl::token::{transfer, Token, Transfer};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::instruction::Instruction;
 
-use crate::merkle_tree::MerkleTree;
-use crate::state::{MerkleTreeAccount, GlobalConfig, NullifierAccount};
-use crate::types::{Proof, SwapExtData, SwapExtDataMinified, CommitmentData};
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::nullifier_set::NullifierSet;
+use crate::root_history::RootHistory;
+use crate::oracle::{self, PriceUpdate};
+use crate::state::{MerkleTreeAccount, GlobalConfig, MintConfig, NullifierShard, RootHistoryAccount, RelayerAccount, SwapProgramRegistry, VerifyingKeyAccount, VerifyingKeyStatus, PAUSE_SWAP};
+use crate::types::{Proof, SwapExtData, SwapExtDataMinified, CommitmentData, NullifiersSpent, TransactionKind};
 use crate::ErrorCode;
-use crate::utils::{verify_proof, VERIFYING_KEY};
+use crate::utils::verify_proof;
 use crate::utils;
 
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(proof: Proof, ext_data_minified: SwapExtDataMinified, encrypted_output: Vec<u8>, jupiter_swap_data: Vec<u8>)]
 pub struct Swap<'info> {
     #[account(
         mut,
-        seeds = [b"merkle_tree"],
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
         bump = tree_account.load()?.bump
     )]
     pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Tree the proof root belongs to, only needed when it is not the active tree.
+    pub root_tree_account: Option<AccountLoader<'info, MerkleTreeAccount>>,
+
+    /// Root history of the active tree, required once one is attached to it.
+    #[account(mut)]
+    pub root_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Root history of `root_tree_account`, required when that tree has one attached.
+    pub root_tree_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
     
-    /// Nullifier account to mark the first input as spent
+    /// Nullifier shard holding the first input's nullifier, it must not contain it yet.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[0][31..].as_ref()],
+        bump = nullifier_shard0.load()?.bump
+    )]
+    pub nullifier_shard0: AccountLoader<'info, NullifierShard>,
+
+    /// Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[1][31..].as_ref()],
+        bump = nullifier_shard1.load()?.bump
+    )]
+    pub nullifier_shard1: AccountLoader<'info, NullifierShard>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
     #[account(
-        init,
-        payer = user,
-        space = 8 + std::mem::size_of::<NullifierAccount>(),
         seeds = [b"nullifier", proof.input_nullifiers[0].as_ref()],
         bump
     )]
-    pub nullifier0: Box<Account<'info, NullifierAccount>>,
-    
-    /// Nullifier account to mark the second input as spent
+    pub legacy_nullifier0: UncheckedAccount<'info>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
     #[account(
-        init,
-        payer = user,
-        space = 8 + std::mem::size_of::<NullifierAccount>(),
         seeds = [b"nullifier", proof.input_nullifiers[1].as_ref()],
         bump
     )]
-    pub nullifier1: Box<Account<'info, NullifierAccount>>,
+    pub legacy_nullifier1: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"global_config"],
         bump
     )]
     pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Verifying key of the circuit version the proof was generated with
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
     
+    #[account(mint::token_program = input_token_program)]
     pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = output_token_program)]
     pub output_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    #[account(
+        mut,
+        seeds = [b"mint_config", input_mint.key().as_ref()],
+        bump = input_mint_config.bump
+    )]
+    pub input_mint_config: Box<Account<'info, MintConfig>>,
+
+    /// CHECK: config PDA of the output mint, empty when the mint is not registered, read by utils::load_mint_config
+    #[account(
+        seeds = [b"mint_config", output_mint.key().as_ref()],
+        bump
+    )]
+    pub output_mint_config: UncheckedAccount<'info>,
+
     #[account(mut,
         associated_token::mint = input_mint,  
         associated_token::authority = global_config,
+        associated_token::token_program = input_token_program,
     )]
     pub reserve_token_account_input: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut,
         associated_token::mint = output_mint,  
         associated_token::authority = global_config,
+        associated_token::token_program = output_token_program,
     )]
     pub reserve_token_account_output: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut)]
-    /// CHECK: user should be able to send fees to any types of accounts
-    pub fee_recipient_account: UncheckedAccount<'info>,
+    /// Output token account of the relayer receiving the slippage surplus
+    #[account(
+        mut,
+        constraint = fee_recipient_account.owner == user.key() @ ErrorCode::InvalidFeeRecipient,
+        constraint = fee_recipient_account.mint == output_mint.key() @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_recipient_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Input token account of the relayer receiving the upfront swap fee
+    #[account(
+        mut,
+        constraint = input_fee_recipient_account.owner == user.key() @ ErrorCode::InvalidFeeRecipient,
+        constraint = input_fee_recipient_account.mint == input_mint.key() @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub input_fee_recipient_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pyth price update of the input mint, validated in the handler. Required when both mints have a price feed
+    pub input_price_update: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price update of the output mint, validated in the handler. Required when both mints have a price feed
+    pub output_price_update: Option<UncheckedAccount<'info>>,
+
+    /// Registry entry of the relayer submitting this transaction
+    #[account(
+        seeds = [b"relayer", user.key().as_ref()],
+        bump = relayer_account.bump,
+        constraint = relayer_account.is_eligible(global_config.min_relayer_stake) @ ErrorCode::RelayerNotActive
+    )]
+    pub relayer_account: Box<Account<'info, RelayerAccount>>,
 
-    /// Jupiter aggregator program
-    /// CHECK: Jupiter program ID
+    #[account(
+        seeds = [b"swap_program_registry"],
+        bump = swap_program_registry.bump
+    )]
+    pub swap_program_registry: Box<Account<'info, SwapProgramRegistry>>,
+
+    /// Jupiter aggregator program, or another approved swap program
+    /// CHECK: must be an executable program listed in swap_program_registry
+    #[account(
+        executable,
+        constraint = swap_program_registry.contains(&jupiter_program.key()) @ ErrorCode::UnknownSwapProgram
+    )]
     pub jupiter_program: UncheckedAccount<'info>,
 
+    /// Registered relayer submitting the swap
     #[account(mut)]
     pub user: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// Token program owning `input_mint`, may differ from the output one (e.g. SOL -> Token-2022)
+    pub input_token_program: Interface<'info, TokenInterface>,
+    /// Token program owning `output_mint`
+    pub output_token_program: Interface<'info, TokenInterface>,
 }
 
 
@@ -89,7 +178,10 @@ pub struct Swap<'info> {
  * User burns UTXO with mintA and creates UTXO with mintB.
  * extAmount should be 0 for pure swaps (no deposit/withdrawal).
  * 
- * Reentrant attacks are not possible, because nullifier creation is checked by anchor first.
+ * Reentrant attacks are not possible, because both nullifiers are marked as spent before the Jupiter CPI.
+ *
+ * The shielded input value is |ext_amount| + fee: the swap route must debit exactly |ext_amount|
+ * from the input reserve, and the fee is then paid to the relayer in the input token.
  */
 pub fn handler(
     ctx: Context<Swap>, 
@@ -98,8 +190,12 @@ pub fn handler(
     encrypted_output: Vec<u8>, 
     jupiter_swap_data: Vec<u8>,   
 ) -> Result<()> {    
-    let tree_account = &mut ctx.accounts.tree_account.load_mut()?;
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
     let global_config = &ctx.accounts.global_config;
+    require!(!global_config.is_paused(PAUSE_SWAP), ErrorCode::InstructionPaused);
+    let output_mint_config = utils::load_mint_config(&ctx.accounts.output_mint_config)?;
 
     // Reconstruct full SwapExtData from minified version and context accounts
     let ext_data = SwapExtData::from_minified(
@@ -107,11 +203,17 @@ pub fn handler(
         ext_data_minified,
     );
 
-    // Check if proof.root is in the tree_account's proof history
-    require!(
-        MerkleTree::is_known_root(&tree_account, proof.root),
-        ErrorCode::UnknownRoot
-    );
+    // Check if proof.root is in the proof history of the tree it references
+    utils::check_known_root(
+        tree_account,
+        ctx.accounts.tree_account.key(),
+        ctx.accounts.root_history_account.as_ref(),
+        ctx.accounts.root_tree_account.as_ref(),
+        ctx.accounts.root_tree_history_account.as_ref(),
+        proof.root,
+        global_config.root_validity_slots,
+        ctx.accounts.verifying_key.tree_height,
+    )?;
 
 
     // Check if the ext_data hashes to the same ext_data in the proof
@@ -133,8 +235,7 @@ pub fn handler(
     // Calculate swap amounts from public amounts
     // publicAmount0 is the net change in input mint (negative for swap out)
     // publicAmount1 is the net change in output mint (positive for swap in)
-    require!(ext_data.ext_amount < 0, ErrorCode::InvalidExtAmount);
-    require!(ext_data.ext_min_amount_out >= 0, ErrorCode::InvalidExtAmount);
+    utils::check_swap_amounts(ext_data.ext_amount, ext_data.ext_min_amount_out)?;
 
     require!(
         utils::check_public_amount(ext_data.ext_amount, ext_data.fee, proof.public_amount0),
@@ -149,12 +250,53 @@ pub fn handler(
     let ext_amount = ext_data.ext_amount;
     let fee = ext_data.fee;
 
-    // Validate fee calculation
+    // The swap output is a deposit into the output mint's pool
+    utils::check_deposit_limits(&output_mint_config, ext_data.ext_min_amount_out as u64)?;
+
+    // With price feeds for both mints, min_amount_out may not undercut the oracle price by more than the surplus cap
+    let input_feed_id = ctx.accounts.input_mint_config.price_feed_id;
+    let output_feed_id = output_mint_config.price_feed_id;
+    if input_feed_id != [0u8; 32] && output_feed_id != [0u8; 32] {
+        let (Some(input_price_update), Some(output_price_update)) =
+            (&ctx.accounts.input_price_update, &ctx.accounts.output_price_update)
+        else {
+            return err!(ErrorCode::MissingPriceUpdate);
+        };
+        let now = Clock::get()?.unix_timestamp;
+        let input_price = PriceUpdate::load(input_price_update, &global_config.oracle_program, &input_feed_id, now)?;
+        let output_price = PriceUpdate::load(output_price_update, &global_config.oracle_program, &output_feed_id, now)?;
+
+        let expected_amount_out = oracle::expected_amount_out(
+            ext_amount.checked_neg().ok_or(ErrorCode::ArithmeticOverflow)? as u64,
+            &input_price,
+            ctx.accounts.input_mint.decimals,
+            &output_price,
+            ctx.accounts.output_mint.decimals,
+        )?;
+        oracle::check_min_amount_out(
+            ext_data.ext_min_amount_out as u64,
+            expected_amount_out,
+            global_config.max_swap_surplus_rate,
+        )?;
+    }
+
+    // The shielded input value (swapped amount plus fee) counts against the input mint's outflow limit
+    let input_outflow = (ext_amount.checked_neg().ok_or(ErrorCode::ArithmeticOverflow)? as u64)
+        .checked_add(fee)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    utils::record_outflow(
+        &mut ctx.accounts.input_mint_config,
+        input_outflow,
+        Clock::get()?.slot,
+        global_config.outflow_window_slots,
+    )?;
+
+    // Validate the upfront input-token fee, ext_amount < 0 so it is checked against the swap rate
     utils::validate_fee(
         ext_amount,
         fee,
         global_config.deposit_fee_rate,
-        global_config.deposit_fee_rate,
+        global_config.swap_fee_rate,
         global_config.fee_error_margin,
     )?;
 
@@ -162,17 +304,36 @@ pub fn handler(
     require!(
         verify_proof(
             proof.clone(), 
-            VERIFYING_KEY, 
+            ctx.accounts.verifying_key.groth16_verifying_key(), 
             ctx.accounts.input_mint.key(), 
             ctx.accounts.output_mint.key()
         ), 
         ErrorCode::InvalidProof
     );
 
-    // Get balance before swap
+    // Mark both inputs as spent, this fails if either nullifier was used before
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard0,
+        &ctx.accounts.legacy_nullifier0,
+        proof.input_nullifiers[0],
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard1,
+        &ctx.accounts.legacy_nullifier1,
+        proof.input_nullifiers[1],
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let swap_amount = ext_amount.checked_neg().ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+
+    // Get balances before swap
+    let input_balance_before = ctx.accounts.reserve_token_account_input.amount;
     let balance_before = ctx.accounts.reserve_token_account_output.amount;
 
-    if jupiter_swap_data.len() > 0 {
+    if !jupiter_swap_data.is_empty() {
         let mut account_metas = Vec::new();
         
         // Add remaining accounts (these are the accounts needed by Jupiter)
@@ -214,6 +375,31 @@ pub fn handler(
        return Err(ErrorCode::InvalidJupiterSwapData.into());
     }
 
+    // The route must not pull more (or less) of the input mint than the proof committed to
+    ctx.accounts.reserve_token_account_input.reload()?;
+    utils::check_swap_input(input_balance_before, ctx.accounts.reserve_token_account_input.amount, swap_amount)?;
+
+    // Pay the upfront fee to the relayer in the input token
+    if fee > 0 {
+        let global_config_seeds = &[
+            b"global_config".as_ref(),
+            &[global_config.bump],
+        ];
+        let signer_seeds = &[&global_config_seeds[..]];
+
+        let fee_transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.input_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reserve_token_account_input.to_account_info(),
+                mint: ctx.accounts.input_mint.to_account_info(),
+                to: ctx.accounts.input_fee_recipient_account.to_account_info(),
+                authority: ctx.accounts.global_config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(fee_transfer_ctx, fee, ctx.accounts.input_mint.decimals)?;
+    }
+
     // Reload the output token account to get updated balance
     ctx.accounts.reserve_token_account_output.reload()?;
     let balance_after = ctx.accounts.reserve_token_account_output.amount;
@@ -226,6 +412,7 @@ pub fn handler(
     let min_amount = ext_data.ext_min_amount_out as u64;
     let calculated_fee = actual_amount_received.checked_sub(min_amount)
         .ok_or(ErrorCode::InsufficientSwapOutput)?;
+    utils::validate_swap_surplus(calculated_fee, min_amount, global_config.max_swap_surplus_rate)?;
     msg!("calculated_fee: {}", calculated_fee);
     msg!("actual_amount_received: {}", actual_amount_received);
     msg!("min_amount: {}", min_amount);
@@ -238,28 +425,33 @@ pub fn handler(
         let signer_seeds = &[&global_config_seeds[..]];
 
         let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.output_token_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.reserve_token_account_output.to_account_info(),
+                mint: ctx.accounts.output_mint.to_account_info(),
                 to: ctx.accounts.fee_recipient_account.to_account_info(),
                 authority: ctx.accounts.global_config.to_account_info(),
             },
             signer_seeds,
         );
-        transfer(transfer_ctx, calculated_fee)?;
+        transfer_checked(transfer_ctx, calculated_fee, ctx.accounts.output_mint.decimals)?;
         println!("Slippage fee: {}", calculated_fee);
     }
 
     let next_index_to_insert = tree_account.next_index;
-    MerkleTree::append::<Poseidon>(proof.output_commitments[0], tree_account)?;
-    MerkleTree::append::<Poseidon>(proof.output_commitments[1], tree_account)?;
-
-    emit!(CommitmentData {
-        index: next_index_to_insert,
-        commitment0: proof.output_commitments[0],
-        commitment1: proof.output_commitments[1],
-        encrypted_output: encrypted_output.to_vec(),
-    });
+    MerkleTree::append_pair::<Poseidon>(proof.output_commitments, tree_account, Clock::get()?.slot)?;
+    RootHistory::record(tree_account, ctx.accounts.root_history_account.as_ref())?;
+
+    emit_cpi!(NullifiersSpent::new(TransactionKind::Swap, &proof)?);
+    emit_cpi!(CommitmentData::new(
+        TransactionKind::Swap,
+        tree_account,
+        next_index_to_insert,
+        &proof,
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.output_mint.key(),
+        encrypted_output,
+    )?);
 
     
     Ok(())
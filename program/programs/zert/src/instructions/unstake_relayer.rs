@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::utils;
+
+/**
+ * Withdraw the lamports the calling relayer requested to unstake, once the cooldown has passed.
+ */
+pub fn handler(ctx: Context<crate::UnstakeRelayer>) -> Result<()> {
+    let relayer_account = &mut ctx.accounts.relayer_account;
+    utils::check_unstake(relayer_account, Clock::get()?.slot)?;
+
+    let amount = relayer_account.pending_unstake;
+    relayer_account.pending_unstake = 0;
+
+    // The relayer account is owned by this program, so lamports can be moved directly
+    let relayer_account_info = relayer_account.to_account_info();
+    **relayer_account_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.relayer.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    msg!("Relayer {} unstaked {} lamports, remaining stake: {}", relayer_account.relayer, amount, relayer_account.stake);
+    Ok(())
+}
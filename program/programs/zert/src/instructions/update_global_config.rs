@@ -4,11 +4,19 @@ use crate::ErrorCode;
 /**
  * Update global configuration. Only the authority can call this.
  */
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<crate::UpdateGlobalConfig>, 
     deposit_fee_rate: Option<u16>,
     withdrawal_fee_rate: Option<u16>,
-    fee_error_margin: Option<u16>
+    fee_error_margin: Option<u16>,
+    swap_fee_rate: Option<u16>,
+    max_swap_surplus_rate: Option<u16>,
+    guardian: Option<Pubkey>,
+    outflow_window_slots: Option<u64>,
+    min_relayer_stake: Option<u64>,
+    oracle_program: Option<Pubkey>,
+    root_validity_slots: Option<u64>,
 ) -> Result<()> {
     let global_config = &mut ctx.accounts.global_config;
     
@@ -29,6 +37,43 @@ pub fn handler(
         global_config.fee_error_margin = fee_error_margin_val;
         msg!("Fee error margin updated to: {} basis points", fee_error_margin_val);
     }
+
+    if let Some(swap_rate) = swap_fee_rate {
+        require!(swap_rate <= 10000, ErrorCode::InvalidFeeRate);
+        global_config.swap_fee_rate = swap_rate;
+        msg!("Swap fee rate updated to: {} basis points", swap_rate);
+    }
+
+    if let Some(surplus_rate) = max_swap_surplus_rate {
+        require!(surplus_rate <= 10000, ErrorCode::InvalidFeeRate);
+        global_config.max_swap_surplus_rate = surplus_rate;
+        msg!("Max swap surplus rate updated to: {} basis points", surplus_rate);
+    }
+
+    if let Some(guardian_key) = guardian {
+        global_config.guardian = guardian_key;
+        msg!("Guardian updated to: {}", guardian_key);
+    }
+
+    if let Some(window_slots) = outflow_window_slots {
+        global_config.outflow_window_slots = window_slots;
+        msg!("Outflow window updated to: {} slots", window_slots);
+    }
+
+    if let Some(min_stake) = min_relayer_stake {
+        global_config.min_relayer_stake = min_stake;
+        msg!("Min relayer stake updated to: {} lamports", min_stake);
+    }
+
+    if let Some(oracle_program_id) = oracle_program {
+        global_config.oracle_program = oracle_program_id;
+        msg!("Oracle program updated to: {}", oracle_program_id);
+    }
+
+    if let Some(validity_slots) = root_validity_slots {
+        global_config.root_validity_slots = validity_slots;
+        msg!("Root validity updated to: {} slots", validity_slots);
+    }
     
     Ok(())
 }
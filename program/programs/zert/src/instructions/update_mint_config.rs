@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::ErrorCode;
+
+/**
+ * Update the deposit and outflow limits or the minimum transfer and account creation fees of a registered mint,
+ * or enable/disable it.
+ * Only the authority can call this.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<crate::UpdateMintConfig>,
+    max_deposit_amount: Option<u64>,
+    min_deposit_amount: Option<u64>,
+    enabled: Option<bool>,
+    max_outflow_per_window: Option<u64>,
+    price_feed_id: Option<[u8; 32]>,
+    min_transfer_fee: Option<u64>,
+    account_creation_fee: Option<u64>,
+) -> Result<()> {
+    let mint_config = &mut ctx.accounts.mint_config;
+
+    if let Some(max_amount) = max_deposit_amount {
+        mint_config.max_deposit_amount = max_amount;
+        msg!("Max deposit amount updated to: {}", max_amount);
+    }
+
+    if let Some(min_amount) = min_deposit_amount {
+        mint_config.min_deposit_amount = min_amount;
+        msg!("Min deposit amount updated to: {}", min_amount);
+    }
+
+    require!(
+        mint_config.min_deposit_amount <= mint_config.max_deposit_amount,
+        ErrorCode::InvalidDepositLimits
+    );
+
+    if let Some(enabled_val) = enabled {
+        mint_config.enabled = enabled_val;
+        msg!("Mint enabled updated to: {}", enabled_val);
+    }
+
+    if let Some(max_outflow) = max_outflow_per_window {
+        mint_config.max_outflow_per_window = max_outflow;
+        msg!("Max outflow per window updated to: {}", max_outflow);
+    }
+
+    if let Some(feed_id) = price_feed_id {
+        mint_config.price_feed_id = feed_id;
+        msg!("Price feed id updated to: {:?}", feed_id);
+    }
+
+    if let Some(min_fee) = min_transfer_fee {
+        mint_config.min_transfer_fee = min_fee;
+        msg!("Min transfer fee updated to: {}", min_fee);
+    }
+
+    if let Some(creation_fee) = account_creation_fee {
+        mint_config.account_creation_fee = creation_fee;
+        msg!("Account creation fee updated to: {}", creation_fee);
+    }
+
+    Ok(())
+}
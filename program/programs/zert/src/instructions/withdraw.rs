@@ -1,70 +1,107 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::sysvar::rent::Rent;
-use anchor_lang::system_program;
 use ark_ff::PrimeField;
 use ark_bn254::Fr;
 use light_hasher::Poseidon;
-use anchor_spl::token_interface::{Mint, TokenAccount};
-use anchor_spl::token::{transfer, close_account, Token, Transfer, CloseAccount};
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::{initialize_account3, InitializeAccount3, TokenAccount as TokenAccountLayout};
 use anchor_spl::token::spl_token::native_mint;
 
-use crate::merkle_tree::MerkleTree;
-use crate::state::{MerkleTreeAccount, GlobalConfig, NullifierAccount};
-use crate::types::{Proof, ExtData, ExtDataMinified, CommitmentData};
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::nullifier_set::NullifierSet;
+use crate::root_history::RootHistory;
+use crate::state::{MerkleTreeAccount, GlobalConfig, MintConfig, NullifierShard, RootHistoryAccount, RelayerAccount, VerifyingKeyAccount, VerifyingKeyStatus, PAUSE_WITHDRAW};
+use crate::types::{Proof, ExtData, ExtDataMinified, CommitmentData, NullifiersSpent, TransactionKind};
 use crate::ErrorCode;
-use crate::utils::{verify_proof, VERIFYING_KEY};
+use crate::utils::verify_proof;
 use crate::utils;
 
 
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(proof: Proof, ext_data_minified: ExtDataMinified, encrypted_output: Vec<u8>)]
 pub struct Withdraw<'info> {
     #[account(
         mut,
-        seeds = [b"merkle_tree"],
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
         bump = tree_account.load()?.bump
     )]
     pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Tree the proof root belongs to, only needed when it is not the active tree.
+    pub root_tree_account: Option<AccountLoader<'info, MerkleTreeAccount>>,
+
+    /// Root history of the active tree, required once one is attached to it.
+    #[account(mut)]
+    pub root_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Root history of `root_tree_account`, required when that tree has one attached.
+    pub root_tree_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
     
-    /// Nullifier account to mark the first input as spent.
-    /// Using `init` without `init_if_needed` ensures that the transaction
-    /// will automatically fail with a system program error if this nullifier
-    /// has already been used (i.e., if the account already exists).
+    /// Nullifier shard holding the first input's nullifier, it must not contain it yet.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[0][31..].as_ref()],
+        bump = nullifier_shard0.load()?.bump
+    )]
+    pub nullifier_shard0: AccountLoader<'info, NullifierShard>,
+
+    /// Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[1][31..].as_ref()],
+        bump = nullifier_shard1.load()?.bump
+    )]
+    pub nullifier_shard1: AccountLoader<'info, NullifierShard>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
     #[account(
-        init,
-        payer = relayer,
-        space = 8 + std::mem::size_of::<NullifierAccount>(),
         seeds = [b"nullifier", proof.input_nullifiers[0].as_ref()],
         bump
     )]
-    pub nullifier0: Box<Account<'info, NullifierAccount>>,
-    
-    /// Nullifier account to mark the second input as spent.
-    /// Using `init` without `init_if_needed` ensures that the transaction
-    /// will automatically fail with a system program error if this nullifier
-    /// has already been used (i.e., if the account already exists).
+    pub legacy_nullifier0: UncheckedAccount<'info>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
     #[account(
-        init,
-        payer = relayer,
-        space = 8 + std::mem::size_of::<NullifierAccount>(),
         seeds = [b"nullifier", proof.input_nullifiers[1].as_ref()],
         bump
     )]
-    pub nullifier1: Box<Account<'info, NullifierAccount>>,
+    pub legacy_nullifier1: UncheckedAccount<'info>,
 
 
+    /// Mutable because unwrapped WSOL passes through it on its way to the recipient
     #[account(
+        mut,
         seeds = [b"global_config"],
         bump
     )]
     pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Verifying key of the circuit version the proof was generated with
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
     
+    #[account(mint::token_program = token_program)]
     pub input_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    #[account(
+        mut,
+        seeds = [b"mint_config", input_mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
     #[account(mut,
         associated_token::mint = input_mint,  
         associated_token::authority = global_config,
+        associated_token::token_program = token_program,
     )]
     pub reserve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -72,18 +109,42 @@ pub struct Withdraw<'info> {
     #[account(mut)]
     pub recipient: UncheckedAccount<'info>,
     
-    /// CHECK: authority will be validated in handler based on token type
+    /// CHECK: token account of the recipient, omitted when withdrawing WSOL which is paid out as native SOL.
+    /// Any token account of the mint owned by the recipient, or its associated token account which the handler
+    /// creates if missing so that fresh addresses can receive tokens.
     #[account(mut)]
-    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub recipient_token_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: temporary WSOL account the withdrawal is unwrapped through, created and closed by the handler
+    #[account(
+        mut,
+        seeds = [b"wsol_unwrap", proof.input_nullifiers[0].as_ref()],
+        bump
+    )]
+    pub wsol_unwrap_account: Option<UncheckedAccount<'info>>,
 
-    /// CHECK: user should be able to send fees to any types of accounts
-    pub fee_recipient_account: UncheckedAccount<'info>,
+    /// Fee token account of the relayer
+    #[account(
+        mut,
+        constraint = fee_recipient_account.owner == relayer.key() @ ErrorCode::InvalidFeeRecipient,
+        constraint = fee_recipient_account.mint == input_mint.key() @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_recipient_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Registry entry of the relayer submitting this transaction
+    #[account(
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_account.bump,
+        constraint = relayer_account.is_eligible(global_config.min_relayer_stake) @ ErrorCode::RelayerNotActive
+    )]
+    pub relayer_account: Box<Account<'info, RelayerAccount>>,
 
     #[account(mut)]
     pub relayer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 
@@ -93,8 +154,11 @@ pub fn handler(
     ext_data_minified: ExtDataMinified, 
     encrypted_output: Vec<u8>, 
 ) -> Result<()> {
-    let tree_account = &mut ctx.accounts.tree_account.load_mut()?;
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
     let global_config = &ctx.accounts.global_config;
+    require!(!global_config.is_paused(PAUSE_WITHDRAW), ErrorCode::InstructionPaused);
 
     // Reconstruct full ExtData from minified version and context accounts
     let recipient_key = ctx.accounts.recipient.key();
@@ -105,11 +169,17 @@ pub fn handler(
         ext_data_minified,
     );
 
-    // Check if proof.root is in the tree_account's proof history
-    require!(
-        MerkleTree::is_known_root(&tree_account, proof.root),
-        ErrorCode::UnknownRoot
-    );
+    // Check if proof.root is in the proof history of the tree it references
+    utils::check_known_root(
+        tree_account,
+        ctx.accounts.tree_account.key(),
+        ctx.accounts.root_history_account.as_ref(),
+        ctx.accounts.root_tree_account.as_ref(),
+        ctx.accounts.root_tree_history_account.as_ref(),
+        proof.root,
+        global_config.root_validity_slots,
+        ctx.accounts.verifying_key.tree_height,
+    )?;
 
     // Check if the ext_data hashes to the same ext_data in the proof
     let calculated_ext_data_hash = utils::calculate_complete_ext_data_hash(
@@ -137,16 +207,39 @@ pub fn handler(
     let ext_amount = ext_data.ext_amount;
     let fee = ext_data.fee;
 
+    // A fee creating the recipient's token account reimburses its rent first
+    let withdrawal_fee = utils::withdrawal_fee_after_account_creation(
+        &ctx.accounts.mint_config,
+        fee,
+        creates_recipient_account(&ctx.accounts.input_mint, ctx.accounts.recipient_token_account.as_deref()),
+    )?;
+
     // Validate fee calculation using utility function
     utils::validate_fee(
         ext_amount,
-        fee,
+        withdrawal_fee,
         global_config.deposit_fee_rate,
         global_config.withdrawal_fee_rate,
         global_config.fee_error_margin,
     )?;
 
-    require!(verify_proof(proof.clone(), VERIFYING_KEY, ctx.accounts.input_mint.key(), ctx.accounts.input_mint.key()), ErrorCode::InvalidProof);
+    require!(verify_proof(proof.clone(), ctx.accounts.verifying_key.groth16_verifying_key(), ctx.accounts.input_mint.key(), ctx.accounts.input_mint.key()), ErrorCode::InvalidProof);
+
+    // Mark both inputs as spent, this fails if either nullifier was used before
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard0,
+        &ctx.accounts.legacy_nullifier0,
+        proof.input_nullifiers[0],
+        &ctx.accounts.relayer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard1,
+        &ctx.accounts.legacy_nullifier1,
+        proof.input_nullifiers[1],
+        &ctx.accounts.relayer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
     require!(ext_amount < 0, ErrorCode::InvalidExtAmount);
 
     // For withdrawals, ext_amount is negative, so we need to negate it
@@ -155,9 +248,108 @@ pub fn handler(
     
     let withdrawal_amount_u64 = withdrawal_amount as u64;
 
+    // Everything leaving the reserve counts against the mint's outflow limit
+    let reserve_outflow = withdrawal_amount_u64.checked_add(fee)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    utils::record_outflow(
+        &mut ctx.accounts.mint_config,
+        reserve_outflow,
+        Clock::get()?.slot,
+        global_config.outflow_window_slots,
+    )?;
+
+    let wsol_unwrap_bump = [ctx.bumps.wsol_unwrap_account.unwrap_or_default()];
+    let wsol_unwrap_seeds: &[&[u8]] = &[b"wsol_unwrap", proof.input_nullifiers[0].as_ref(), &wsol_unwrap_bump];
+
+    pay_out_withdrawal(
+        WithdrawalLeg {
+            token_program: &ctx.accounts.token_program,
+            mint: &ctx.accounts.input_mint,
+            reserve_token_account: &ctx.accounts.reserve_token_account,
+            associated_token_program: &ctx.accounts.associated_token_program,
+            recipient_token_account: ctx.accounts.recipient_token_account.as_deref(),
+            fee_recipient_account: &ctx.accounts.fee_recipient_account,
+            wsol_unwrap_account: ctx.accounts.wsol_unwrap_account.as_deref(),
+            wsol_unwrap_seeds,
+        },
+        global_config,
+        &ctx.accounts.recipient,
+        &ctx.accounts.relayer,
+        &ctx.accounts.system_program,
+        withdrawal_amount_u64,
+        fee,
+    )?;
+
+
+    let next_index_to_insert = tree_account.next_index;
+    MerkleTree::append_pair::<Poseidon>(proof.output_commitments, tree_account, Clock::get()?.slot)?;
+    RootHistory::record(tree_account, ctx.accounts.root_history_account.as_ref())?;
+
+    emit_cpi!(NullifiersSpent::new(TransactionKind::Withdraw, &proof)?);
+    emit_cpi!(CommitmentData::new(
+        TransactionKind::Withdraw,
+        tree_account,
+        next_index_to_insert,
+        &proof,
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.input_mint.key(),
+        encrypted_output,
+    )?);
+
+    
+    Ok(())
+}
+
+/// Accounts of one withdrawn mint
+pub(crate) struct WithdrawalLeg<'a, 'info> {
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub reserve_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: &'a Program<'info, AssociatedToken>,
+    /// Token account of the recipient, not needed for WSOL which is paid out as native SOL
+    pub recipient_token_account: Option<&'a AccountInfo<'info>>,
+    pub fee_recipient_account: &'a InterfaceAccount<'info, TokenAccount>,
+    /// Temporary PDA WSOL is unwrapped through, only needed for WSOL
+    pub wsol_unwrap_account: Option<&'a AccountInfo<'info>>,
+    pub wsol_unwrap_seeds: &'a [&'a [u8]],
+}
+
+/**
+ * Whether paying out a withdrawal of `mint` creates the recipient's associated token account.
+ * WSOL is paid out as native SOL and never needs one.
+ */
+pub(crate) fn creates_recipient_account(
+    mint: &InterfaceAccount<Mint>,
+    recipient_token_account: Option<&AccountInfo>,
+) -> bool {
+    native_mint::ID != mint.key()
+        && recipient_token_account.is_some_and(|account| account.data_is_empty())
+}
+
+/**
+ * Pays `withdrawal_amount` of the leg's mint out of the reserve to the recipient and `fee` to the relayer.
+ *
+ * WSOL is unwrapped through a temporary token account owned by global_config, so that the recipient
+ * receives native SOL straight from the pool and the relayer only fronts the temporary account's rent,
+ * which is refunded in the same instruction.
+ *
+ * Other tokens are paid to any token account of the mint the recipient owns. A recipient without one
+ * gets its associated token account created, with the relayer paying the rent. The caller must have
+ * checked that `fee` includes the mint's account_creation_fee in that case, see
+ * `utils::withdrawal_fee_after_account_creation`, which reimburses the relayer in the withdrawn token.
+ */
+pub(crate) fn pay_out_withdrawal<'info>(
+    leg: WithdrawalLeg<'_, 'info>,
+    global_config: &Account<'info, GlobalConfig>,
+    recipient: &AccountInfo<'info>,
+    relayer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    withdrawal_amount: u64,
+    fee: u64,
+) -> Result<()> {
     // Ensure reserve has enough balance
     require!(
-        ctx.accounts.reserve_token_account.amount >= withdrawal_amount_u64,
+        leg.reserve_token_account.amount >= withdrawal_amount,
         ErrorCode::InsufficientFundsForWithdrawal
     );
 
@@ -167,98 +359,134 @@ pub fn handler(
         &[global_config.bump],
     ];
     let signer_seeds = &[&global_config_seeds[..]];
+    let decimals = leg.mint.decimals;
     
-    // fee first because we destroy token account
     if fee > 0 {
         let fee_transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.reserve_token_account.to_account_info(),
-                to: ctx.accounts.fee_recipient_account.to_account_info(),
-                authority: ctx.accounts.global_config.to_account_info(),
+            leg.token_program.to_account_info(),
+            TransferChecked {
+                from: leg.reserve_token_account.to_account_info(),
+                mint: leg.mint.to_account_info(),
+                to: leg.fee_recipient_account.to_account_info(),
+                authority: global_config.to_account_info(),
             },
             signer_seeds,
         );
-        transfer(fee_transfer_ctx, fee)?;
+        transfer_checked(fee_transfer_ctx, fee, decimals)?;
     }
 
     // Check if the mint is native SOL (Wrapped SOL)
-    let is_native_sol = native_mint::ID == ctx.accounts.input_mint.key();
+    let is_native_sol = native_mint::ID == leg.mint.key();
 
     if is_native_sol {
-        // For WSOL: validate that recipient_token_account authority is relayer
-        require!(
-            ctx.accounts.recipient_token_account.owner == ctx.accounts.relayer.key(),
-            ErrorCode::Unauthorized
-        );
+        let wsol_unwrap_account = leg.wsol_unwrap_account
+            .ok_or(ErrorCode::MissingWsolUnwrapAccount)?;
+
+        // Step 1: Create the temporary WSOL account, owned by global_config. The relayer fronts the rent
+        utils::create_pda_account(
+            &relayer.to_account_info(),
+            wsol_unwrap_account,
+            &system_program.to_account_info(),
+            TokenAccountLayout::LEN,
+            &leg.token_program.key(),
+            leg.wsol_unwrap_seeds,
+        )?;
+        initialize_account3(CpiContext::new(
+            leg.token_program.to_account_info(),
+            InitializeAccount3 {
+                account: wsol_unwrap_account.clone(),
+                mint: leg.mint.to_account_info(),
+                authority: global_config.to_account_info(),
+            },
+        ))?;
 
-        // For WSOL: transfer tokens, close account to relayer, then relayer sends SOL to recipient
-        
-        // Step 1: Transfer WSOL tokens from reserve to recipient token account
+        // Step 2: Transfer WSOL tokens from reserve to the temporary account
         let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.reserve_token_account.to_account_info(),
-                to: ctx.accounts.recipient_token_account.to_account_info(),
-                authority: ctx.accounts.global_config.to_account_info(),
+            leg.token_program.to_account_info(),
+            TransferChecked {
+                from: leg.reserve_token_account.to_account_info(),
+                mint: leg.mint.to_account_info(),
+                to: wsol_unwrap_account.clone(),
+                authority: global_config.to_account_info(),
             },
             signer_seeds,
         );
-        transfer(transfer_ctx, withdrawal_amount_u64)?;
+        transfer_checked(transfer_ctx, withdrawal_amount, decimals)?;
 
-        // Step 2: Close the recipient WSOL account to relayer
-        // ALL lamports (rent + WSOL balance) go to relayer
-        let close_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+        // Step 3: Close the temporary account into global_config, which receives rent + WSOL balance
+        let global_config_info = global_config.to_account_info();
+        let lamports_before = global_config_info.lamports();
+        let close_ctx = CpiContext::new_with_signer(
+            leg.token_program.to_account_info(),
             CloseAccount {
-                account: ctx.accounts.recipient_token_account.to_account_info(),
-                destination: ctx.accounts.relayer.to_account_info(),
-                authority: ctx.accounts.relayer.to_account_info(),
+                account: wsol_unwrap_account.clone(),
+                destination: global_config_info.clone(),
+                authority: global_config_info.clone(),
             },
+            signer_seeds,
         );
         close_account(close_ctx)?;
+        let unwrapped_lamports = global_config_info.lamports()
+            .checked_sub(lamports_before)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let rent_refund = unwrapped_lamports.checked_sub(withdrawal_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-        // Step 3: Relayer transfers SOL to recipient
-        let transfer_sol_ctx = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.relayer.to_account_info(),
-                to: ctx.accounts.recipient.to_account_info(),
-            },
-        );
-        system_program::transfer(transfer_sol_ctx, withdrawal_amount_u64)?;
+        // Step 4: Pay the recipient and refund the rent to the relayer. global_config is owned
+        // by this program, so its lamports can be moved directly
+        **global_config_info.try_borrow_mut_lamports()? -= unwrapped_lamports;
+        **recipient.try_borrow_mut_lamports()? += withdrawal_amount;
+        **relayer.to_account_info().try_borrow_mut_lamports()? += rent_refund;
     } else {
-        // For regular SPL tokens: validate that recipient_token_account authority is recipient
-        require!(
-            ctx.accounts.recipient_token_account.owner == ctx.accounts.recipient.key(),
-            ErrorCode::Unauthorized
-        );
+        let recipient_token_account = leg.recipient_token_account
+            .ok_or(ErrorCode::MissingRecipientTokenAccount)?;
+        if recipient_token_account.data_is_empty() {
+            // Fresh recipient: create its associated token account, the relayer pays the rent
+            let associated_token_address = get_associated_token_address_with_program_id(
+                &recipient.key(),
+                &leg.mint.key(),
+                &leg.token_program.key(),
+            );
+            require_keys_eq!(
+                recipient_token_account.key(),
+                associated_token_address,
+                ErrorCode::InvalidRecipientTokenAccount
+            );
+            associated_token::create(CpiContext::new(
+                leg.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: relayer.to_account_info(),
+                    associated_token: recipient_token_account.clone(),
+                    authority: recipient.clone(),
+                    mint: leg.mint.to_account_info(),
+                    system_program: system_program.to_account_info(),
+                    token_program: leg.token_program.to_account_info(),
+                },
+            ))?;
+        } else {
+            require_keys_eq!(
+                *recipient_token_account.owner,
+                leg.token_program.key(),
+                ErrorCode::InvalidRecipientTokenAccount
+            );
+            let token_account = TokenAccount::try_deserialize(&mut &recipient_token_account.try_borrow_data()?[..])?;
+            utils::check_recipient_token_account(&token_account, &recipient.key(), &leg.mint.key())?;
+        }
 
-        // For regular SPL tokens: just transfer
+        // For regular SPL tokens: just transfer. For Token-2022 mints with a transfer fee
+        // the reserve is debited the full amount and the recipient bears the withheld fee.
         let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.reserve_token_account.to_account_info(),
-                to: ctx.accounts.recipient_token_account.to_account_info(),
-                authority: ctx.accounts.global_config.to_account_info(),
+            leg.token_program.to_account_info(),
+            TransferChecked {
+                from: leg.reserve_token_account.to_account_info(),
+                mint: leg.mint.to_account_info(),
+                to: recipient_token_account.clone(),
+                authority: global_config.to_account_info(),
             },
             signer_seeds,
         );
-        transfer(transfer_ctx, withdrawal_amount_u64)?;
+        transfer_checked(transfer_ctx, withdrawal_amount, decimals)?;
     }
 
-
-    let next_index_to_insert = tree_account.next_index;
-    MerkleTree::append::<Poseidon>(proof.output_commitments[0], tree_account)?;
-    MerkleTree::append::<Poseidon>(proof.output_commitments[1], tree_account)?;
-
-    emit!(CommitmentData {
-        index: next_index_to_insert,
-        commitment0: proof.output_commitments[0],
-        commitment1: proof.output_commitments[1],
-        encrypted_output: encrypted_output.to_vec(),
-    });
-
-    
     Ok(())
 }
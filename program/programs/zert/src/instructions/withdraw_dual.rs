@@ -0,0 +1,369 @@
+use anchor_lang::prelude::*;
+use ark_ff::PrimeField;
+use ark_bn254::Fr;
+use light_hasher::Poseidon;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::withdraw::{creates_recipient_account, pay_out_withdrawal, WithdrawalLeg};
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::nullifier_set::NullifierSet;
+use crate::root_history::RootHistory;
+use crate::state::{MerkleTreeAccount, GlobalConfig, MintConfig, NullifierShard, RootHistoryAccount, RelayerAccount, VerifyingKeyAccount, VerifyingKeyStatus, PAUSE_WITHDRAW};
+use crate::types::{Proof, DualExtData, DualExtDataMinified, CommitmentData, NullifiersSpent, TransactionKind};
+use crate::ErrorCode;
+use crate::utils::verify_proof;
+use crate::utils;
+
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(proof: Proof, ext_data_minified: DualExtDataMinified, encrypted_output: Vec<u8>)]
+pub struct WithdrawDual<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
+        bump = tree_account.load()?.bump
+    )]
+    pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Tree the proof root belongs to, only needed when it is not the active tree.
+    pub root_tree_account: Option<AccountLoader<'info, MerkleTreeAccount>>,
+
+    /// Root history of the active tree, required once one is attached to it.
+    #[account(mut)]
+    pub root_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Root history of `root_tree_account`, required when that tree has one attached.
+    pub root_tree_history_account: Option<AccountLoader<'info, RootHistoryAccount>>,
+
+    /// Nullifier shard holding the first input's nullifier, it must not contain it yet.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[0][31..].as_ref()],
+        bump = nullifier_shard0.load()?.bump
+    )]
+    pub nullifier_shard0: AccountLoader<'info, NullifierShard>,
+
+    /// Nullifier shard holding the second input's nullifier, may be the same account as nullifier_shard0.
+    #[account(
+        mut,
+        seeds = [b"nullifier_shard", proof.input_nullifiers[1][31..].as_ref()],
+        bump = nullifier_shard1.load()?.bump
+    )]
+    pub nullifier_shard1: AccountLoader<'info, NullifierShard>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
+    #[account(
+        seeds = [b"nullifier", proof.input_nullifiers[0].as_ref()],
+        bump
+    )]
+    pub legacy_nullifier0: UncheckedAccount<'info>,
+
+    /// CHECK: nullifier PDA of the previous one-account-per-nullifier scheme, it must not exist.
+    #[account(
+        seeds = [b"nullifier", proof.input_nullifiers[1].as_ref()],
+        bump
+    )]
+    pub legacy_nullifier1: UncheckedAccount<'info>,
+
+    /// Mutable because unwrapped WSOL passes through it on its way to the recipient
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Verifying key of the circuit version the proof was generated with
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    #[account(mint::token_program = token_program0)]
+    pub input_mint0: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mint::token_program = token_program1,
+        constraint = input_mint1.key() != input_mint0.key() @ ErrorCode::InvalidDualTokenMints
+    )]
+    pub input_mint1: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", input_mint0.key().as_ref()],
+        bump = mint_config0.bump
+    )]
+    pub mint_config0: Box<Account<'info, MintConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", input_mint1.key().as_ref()],
+        bump = mint_config1.bump
+    )]
+    pub mint_config1: Box<Account<'info, MintConfig>>,
+
+    #[account(mut,
+        associated_token::mint = input_mint0,
+        associated_token::authority = global_config,
+        associated_token::token_program = token_program0,
+    )]
+    pub reserve_token_account0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut,
+        associated_token::mint = input_mint1,
+        associated_token::authority = global_config,
+        associated_token::token_program = token_program1,
+    )]
+    pub reserve_token_account1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: user should be able to receive withdrawals to any types of accounts
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: token account of the recipient for input_mint0, omitted when that leg is WSOL.
+    /// Any token account of the mint owned by the recipient, or its associated token account created if missing.
+    #[account(mut)]
+    pub recipient_token_account0: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token account of the recipient for input_mint1, omitted when that leg is WSOL.
+    /// Any token account of the mint owned by the recipient, or its associated token account created if missing.
+    #[account(mut)]
+    pub recipient_token_account1: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: temporary WSOL account the WSOL leg is unwrapped through, created and closed by the handler
+    #[account(
+        mut,
+        seeds = [b"wsol_unwrap", proof.input_nullifiers[0].as_ref()],
+        bump
+    )]
+    pub wsol_unwrap_account: Option<UncheckedAccount<'info>>,
+
+    /// Fee token account of the relayer for input_mint0
+    #[account(
+        mut,
+        constraint = fee_recipient_account0.owner == relayer.key() @ ErrorCode::InvalidFeeRecipient,
+        constraint = fee_recipient_account0.mint == input_mint0.key() @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_recipient_account0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Fee token account of the relayer for input_mint1
+    #[account(
+        mut,
+        constraint = fee_recipient_account1.owner == relayer.key() @ ErrorCode::InvalidFeeRecipient,
+        constraint = fee_recipient_account1.mint == input_mint1.key() @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_recipient_account1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Registry entry of the relayer submitting this transaction
+    #[account(
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_account.bump,
+        constraint = relayer_account.is_eligible(global_config.min_relayer_stake) @ ErrorCode::RelayerNotActive
+    )]
+    pub relayer_account: Box<Account<'info, RelayerAccount>>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning `input_mint0`
+    pub token_program0: Interface<'info, TokenInterface>,
+    /// Token program owning `input_mint1`, may differ from the first one
+    pub token_program1: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+
+/**
+ * Unshield two different mints to the same recipient with a single proof.
+ *
+ * |ext_amount0| of input_mint0 and |ext_amount1| of input_mint1 are paid to the recipient,
+ * each leg paying the withdrawal fee to the relayer in its own mint. Either leg may be WSOL,
+ * which is unwrapped and paid out as native SOL.
+ */
+pub fn handler(
+    ctx: Context<WithdrawDual>,
+    proof: Proof,
+    ext_data_minified: DualExtDataMinified,
+    encrypted_output: Vec<u8>,
+) -> Result<()> {
+    let tree_account_info = ctx.accounts.tree_account.to_account_info();
+    let mut tree_account_data = tree_account_info.try_borrow_mut_data()?;
+    let tree_account = &mut MerkleTreeData::from_bytes(&mut tree_account_data)?;
+    let global_config = &ctx.accounts.global_config;
+    require!(!global_config.is_paused(PAUSE_WITHDRAW), ErrorCode::InstructionPaused);
+
+    // Reconstruct full DualExtData from minified version and context accounts
+    let ext_data = DualExtData::from_minified(
+        &ctx.accounts.recipient.key(),
+        &ctx.accounts.fee_recipient_account0.key(),
+        &ctx.accounts.fee_recipient_account1.key(),
+        ext_data_minified,
+    );
+
+    // Check if proof.root is in the proof history of the tree it references
+    utils::check_known_root(
+        tree_account,
+        ctx.accounts.tree_account.key(),
+        ctx.accounts.root_history_account.as_ref(),
+        ctx.accounts.root_tree_account.as_ref(),
+        ctx.accounts.root_tree_history_account.as_ref(),
+        proof.root,
+        global_config.root_validity_slots,
+        ctx.accounts.verifying_key.tree_height,
+    )?;
+
+    // Check if the ext_data hashes to the same ext_data in the proof
+    let calculated_ext_data_hash = utils::calculate_dual_ext_data_hash(
+        &ext_data,
+        &encrypted_output,
+        ctx.accounts.input_mint0.key(),
+        ctx.accounts.input_mint1.key(),
+    )?;
+    require!(
+        Fr::from_le_bytes_mod_order(&calculated_ext_data_hash) == Fr::from_be_bytes_mod_order(&proof.ext_data_hash),
+        ErrorCode::ExtDataHashMismatch
+    );
+
+    require!(ext_data.ext_amount0 < 0 && ext_data.ext_amount1 < 0, ErrorCode::InvalidExtAmount);
+    require!(
+        utils::check_public_amount(ext_data.ext_amount0, ext_data.fee0, proof.public_amount0),
+        ErrorCode::InvalidPublicAmountData
+    );
+    require!(
+        utils::check_public_amount(ext_data.ext_amount1, ext_data.fee1, proof.public_amount1),
+        ErrorCode::InvalidPublicAmountData
+    );
+
+    // Each leg pays the withdrawal fee in its own mint, reimbursing the rent of the recipient's
+    // token account first when the leg creates it
+    let creates_recipient_account0 = creates_recipient_account(
+        &ctx.accounts.input_mint0,
+        ctx.accounts.recipient_token_account0.as_deref(),
+    );
+    let creates_recipient_account1 = creates_recipient_account(
+        &ctx.accounts.input_mint1,
+        ctx.accounts.recipient_token_account1.as_deref(),
+    );
+    for (mint_config, ext_amount, fee, creates_account) in [
+        (&ctx.accounts.mint_config0, ext_data.ext_amount0, ext_data.fee0, creates_recipient_account0),
+        (&ctx.accounts.mint_config1, ext_data.ext_amount1, ext_data.fee1, creates_recipient_account1),
+    ] {
+        let withdrawal_fee = utils::withdrawal_fee_after_account_creation(mint_config, fee, creates_account)?;
+        utils::validate_fee(
+            ext_amount,
+            withdrawal_fee,
+            global_config.deposit_fee_rate,
+            global_config.withdrawal_fee_rate,
+            global_config.fee_error_margin,
+        )?;
+    }
+
+    // Verify the proof with both mint addresses
+    require!(
+        verify_proof(
+            proof.clone(),
+            ctx.accounts.verifying_key.groth16_verifying_key(),
+            ctx.accounts.input_mint0.key(),
+            ctx.accounts.input_mint1.key()
+        ),
+        ErrorCode::InvalidProof
+    );
+
+    // Mark both inputs as spent, this fails if either nullifier was used before
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard0,
+        &ctx.accounts.legacy_nullifier0,
+        proof.input_nullifiers[0],
+        &ctx.accounts.relayer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    NullifierSet::spend(
+        &ctx.accounts.nullifier_shard1,
+        &ctx.accounts.legacy_nullifier1,
+        proof.input_nullifiers[1],
+        &ctx.accounts.relayer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let withdrawal_amount0 = ext_data.ext_amount0.checked_neg()
+        .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+    let withdrawal_amount1 = ext_data.ext_amount1.checked_neg()
+        .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+
+    // Everything leaving the reserves counts against the mints' outflow limits
+    let slot = Clock::get()?.slot;
+    utils::record_outflow(
+        &mut ctx.accounts.mint_config0,
+        withdrawal_amount0.checked_add(ext_data.fee0).ok_or(ErrorCode::ArithmeticOverflow)?,
+        slot,
+        global_config.outflow_window_slots,
+    )?;
+    utils::record_outflow(
+        &mut ctx.accounts.mint_config1,
+        withdrawal_amount1.checked_add(ext_data.fee1).ok_or(ErrorCode::ArithmeticOverflow)?,
+        slot,
+        global_config.outflow_window_slots,
+    )?;
+
+    // Mints are distinct, so at most one leg is WSOL and uses the unwrap account
+    let wsol_unwrap_bump = [ctx.bumps.wsol_unwrap_account.unwrap_or_default()];
+    let wsol_unwrap_seeds: &[&[u8]] = &[b"wsol_unwrap", proof.input_nullifiers[0].as_ref(), &wsol_unwrap_bump];
+
+    pay_out_withdrawal(
+        WithdrawalLeg {
+            token_program: &ctx.accounts.token_program0,
+            mint: &ctx.accounts.input_mint0,
+            reserve_token_account: &ctx.accounts.reserve_token_account0,
+            associated_token_program: &ctx.accounts.associated_token_program,
+            recipient_token_account: ctx.accounts.recipient_token_account0.as_deref(),
+            fee_recipient_account: &ctx.accounts.fee_recipient_account0,
+            wsol_unwrap_account: ctx.accounts.wsol_unwrap_account.as_deref(),
+            wsol_unwrap_seeds,
+        },
+        global_config,
+        &ctx.accounts.recipient,
+        &ctx.accounts.relayer,
+        &ctx.accounts.system_program,
+        withdrawal_amount0,
+        ext_data.fee0,
+    )?;
+    pay_out_withdrawal(
+        WithdrawalLeg {
+            token_program: &ctx.accounts.token_program1,
+            mint: &ctx.accounts.input_mint1,
+            reserve_token_account: &ctx.accounts.reserve_token_account1,
+            associated_token_program: &ctx.accounts.associated_token_program,
+            recipient_token_account: ctx.accounts.recipient_token_account1.as_deref(),
+            fee_recipient_account: &ctx.accounts.fee_recipient_account1,
+            wsol_unwrap_account: ctx.accounts.wsol_unwrap_account.as_deref(),
+            wsol_unwrap_seeds,
+        },
+        global_config,
+        &ctx.accounts.recipient,
+        &ctx.accounts.relayer,
+        &ctx.accounts.system_program,
+        withdrawal_amount1,
+        ext_data.fee1,
+    )?;
+
+    let next_index_to_insert = tree_account.next_index;
+    MerkleTree::append_pair::<Poseidon>(proof.output_commitments, tree_account, Clock::get()?.slot)?;
+    RootHistory::record(tree_account, ctx.accounts.root_history_account.as_ref())?;
+
+    emit_cpi!(NullifiersSpent::new(TransactionKind::WithdrawDual, &proof)?);
+    emit_cpi!(CommitmentData::new(
+        TransactionKind::WithdrawDual,
+        tree_account,
+        next_index_to_insert,
+        &proof,
+        ctx.accounts.input_mint0.key(),
+        ctx.accounts.input_mint1.key(),
+        encrypted_output,
+    )?);
+
+    Ok(())
+}
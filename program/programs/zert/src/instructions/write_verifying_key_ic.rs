@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::VerifyingKeyStatus;
+use crate::ErrorCode;
+
+/**
+ * Write a chunk of IC points into a pending verifying key. Only the authority can call this.
+ *
+ * The IC points do not fit into a single transaction together with the rest of the key,
+ * so they are written in chunks starting at start_index.
+ */
+pub fn handler(
+    ctx: Context<crate::WriteVerifyingKeyIc>,
+    start_index: u8,
+    vk_ic: Vec<[u8; 64]>,
+) -> Result<()> {
+    let verifying_key = &mut ctx.accounts.verifying_key;
+    require!(verifying_key.status == VerifyingKeyStatus::Pending, ErrorCode::VerifyingKeyNotPending);
+
+    let start = start_index as usize;
+    let end = start.checked_add(vk_ic.len()).ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(!vk_ic.is_empty() && end <= verifying_key.vk_ic.len(), ErrorCode::InvalidVerifyingKeyIcRange);
+
+    verifying_key.vk_ic[start..end].copy_from_slice(&vk_ic);
+
+    msg!("Verifying key version {}: wrote IC points {}..{}", verifying_key.version, start, end);
+    Ok(())
+}
@@ -0,0 +1,242 @@
+// Layouts of the accounts of pools deployed before trees were indexed, and their conversion
+// to the current layouts. Only the migration instructions read these.
+use crate::merkle_tree::MerkleTreeData;
+use crate::{GlobalConfig, MerkleTreeAccount, ErrorCode};
+use anchor_lang::prelude::*;
+
+/// The global config of a legacy pool, stored under the same address and discriminator as GlobalConfig.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyGlobalConfig {
+    pub authority: Pubkey,
+    pub deposit_fee_rate: u16,
+    pub withdrawal_fee_rate: u16,
+    pub fee_error_margin: u16,
+    pub bump: u8,
+}
+
+impl LegacyGlobalConfig {
+    /// Size the legacy global config account was created with: discriminator + the Rust size of its fields.
+    pub const SPACE: usize = 8 + 40;
+
+    /// Reads a legacy global config from its account data, discriminator included.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() == Self::SPACE && data[..8] == *GlobalConfig::DISCRIMINATOR,
+            ErrorCode::InvalidLegacyAccount
+        );
+        Self::deserialize(&mut &data[8..])
+            .map_err(|_| error!(ErrorCode::InvalidLegacyAccount))
+    }
+
+    /// The global config in the current layout, keeping the legacy fee settings
+    /// and the defaults of a new pool for everything added since.
+    pub fn upgrade(&self) -> GlobalConfig {
+        let mut global_config = GlobalConfig::new(self.authority, self.bump);
+        global_config.deposit_fee_rate = self.deposit_fee_rate;
+        global_config.withdrawal_fee_rate = self.withdrawal_fee_rate;
+        global_config.fee_error_margin = self.fee_error_margin;
+        global_config
+    }
+}
+
+/// Height and root history size the legacy tree was created with.
+pub const LEGACY_MERKLE_TREE_HEIGHT: u8 = 26;
+pub const LEGACY_ROOT_HISTORY_SIZE: usize = 100;
+
+/// The single merkle tree of a legacy pool, stored at the [b"merkle_tree"] address
+/// under the same discriminator as MerkleTreeAccount.
+#[zero_copy]
+pub struct LegacyMerkleTreeAccount {
+    pub authority: Pubkey,
+    pub next_index: u64,
+    pub subtrees: [[u8; 32]; LEGACY_MERKLE_TREE_HEIGHT as usize],
+    pub root: [u8; 32],
+    pub root_history: [[u8; 32]; LEGACY_ROOT_HISTORY_SIZE],
+    pub root_index: u64,
+    pub max_deposit_amount: u64,
+    pub height: u8,
+    pub root_history_size: u8,
+    pub bump: u8,
+    pub _padding: [u8; 5],
+}
+
+impl LegacyMerkleTreeAccount {
+    pub const SPACE: usize = 8 + std::mem::size_of::<LegacyMerkleTreeAccount>();
+
+    /// Reads a legacy tree from its account data, discriminator included.
+    pub fn from_bytes(data: &[u8]) -> Result<&Self> {
+        require!(
+            data.len() == Self::SPACE && data[..8] == *MerkleTreeAccount::DISCRIMINATOR,
+            ErrorCode::InvalidLegacyAccount
+        );
+        bytemuck::try_from_bytes(&data[8..])
+            .map_err(|_| error!(ErrorCode::InvalidLegacyAccount))
+    }
+
+    /// Copies the leaves and roots of the legacy tree into `tree_account`, a tree of the same height
+    /// and root history size. The legacy tree has no root slots, its roots are recorded at `slot`
+    /// so that they get the full validity window from the migration on.
+    pub fn upgrade(&self, tree_account: &mut MerkleTreeData, slot: u64) -> Result<()> {
+        require!(
+            tree_account.height == self.height
+                && tree_account.height == LEGACY_MERKLE_TREE_HEIGHT
+                && tree_account.root_history_size as usize == LEGACY_ROOT_HISTORY_SIZE,
+            ErrorCode::InvalidLegacyAccount
+        );
+
+        tree_account.next_index = self.next_index;
+        tree_account.root = self.root;
+        tree_account.root_index = self.root_index;
+        tree_account.subtrees.copy_from_slice(&self.subtrees);
+        tree_account.root_history.copy_from_slice(&self.root_history);
+        tree_account.root_slots.fill(slot);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle_tree::MerkleTree;
+    use light_hasher::Poseidon;
+
+    // Account data backed by u64 words so that headers are aligned like in account data
+    fn tree_buffer(space: usize) -> Vec<u64> {
+        vec![0u64; space.div_ceil(8)]
+    }
+
+    fn new_tree(buffer: &mut [u64]) -> MerkleTreeData<'_> {
+        let data = bytemuck::cast_slice_mut::<u64, u8>(buffer);
+        {
+            let header: &mut MerkleTreeAccount =
+                bytemuck::from_bytes_mut(&mut data[8..MerkleTreeAccount::SUBTREES_OFFSET]);
+            header.height = LEGACY_MERKLE_TREE_HEIGHT;
+            header.root_history_size = LEGACY_ROOT_HISTORY_SIZE as u8;
+        }
+        let mut tree_account = MerkleTreeData::from_bytes(data).unwrap();
+        MerkleTree::initialize::<Poseidon>(&mut tree_account).unwrap();
+        tree_account
+    }
+
+    fn leaf(i: u8) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(&[i]).to_bytes()
+    }
+
+    // Builds the data of a legacy tree account in the state `tree_account` is in
+    fn legacy_tree_data(tree_account: &MerkleTreeData) -> Vec<u64> {
+        let mut buffer = tree_buffer(LegacyMerkleTreeAccount::SPACE);
+        let data = bytemuck::cast_slice_mut::<u64, u8>(&mut buffer);
+        data[..8].copy_from_slice(MerkleTreeAccount::DISCRIMINATOR);
+        let legacy: &mut LegacyMerkleTreeAccount = bytemuck::from_bytes_mut(&mut data[8..]);
+        legacy.authority = Pubkey::new_unique();
+        legacy.next_index = tree_account.next_index;
+        legacy.subtrees.copy_from_slice(tree_account.subtrees);
+        legacy.root = tree_account.root;
+        legacy.root_history.copy_from_slice(tree_account.root_history);
+        legacy.root_index = tree_account.root_index;
+        legacy.max_deposit_amount = 1_000_000_000_000;
+        legacy.height = LEGACY_MERKLE_TREE_HEIGHT;
+        legacy.root_history_size = LEGACY_ROOT_HISTORY_SIZE as u8;
+        buffer
+    }
+
+    fn legacy_global_config_data(legacy: &LegacyGlobalConfig) -> Vec<u8> {
+        let mut data = GlobalConfig::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(LegacyGlobalConfig::SPACE, 0);
+        data
+    }
+
+    #[test]
+    fn test_upgrade_legacy_global_config() {
+        let authority = Pubkey::new_unique();
+        let data = legacy_global_config_data(&LegacyGlobalConfig {
+            authority,
+            deposit_fee_rate: 10,
+            withdrawal_fee_rate: 30,
+            fee_error_margin: 400,
+            bump: 254,
+        });
+
+        // Reallocated and rewritten in place the way migrate_global_config does
+        let global_config = LegacyGlobalConfig::from_bytes(&data).unwrap().upgrade();
+        let mut data = data;
+        data.resize(8 + std::mem::size_of::<GlobalConfig>(), 0);
+        global_config.try_serialize(&mut &mut data[..]).unwrap();
+
+        let global_config = GlobalConfig::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(global_config.version, crate::state::GLOBAL_CONFIG_VERSION);
+        assert_eq!(global_config.authority, authority);
+        assert_eq!(global_config.deposit_fee_rate, 10);
+        assert_eq!(global_config.withdrawal_fee_rate, 30);
+        assert_eq!(global_config.fee_error_margin, 400);
+        assert_eq!(global_config.bump, 254);
+        assert_eq!(global_config.active_tree_id, 0);
+        assert_eq!(global_config.pending_authority, Pubkey::default());
+        assert_eq!(global_config.paused, 0);
+
+        // A migrated global config is not mistaken for a legacy one
+        assert!(LegacyGlobalConfig::from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_legacy_global_config_does_not_deserialize() {
+        let data = legacy_global_config_data(&LegacyGlobalConfig {
+            authority: Pubkey::new_unique(),
+            deposit_fee_rate: 0,
+            withdrawal_fee_rate: 25,
+            fee_error_margin: 500,
+            bump: 255,
+        });
+        assert!(GlobalConfig::try_deserialize(&mut &data[..]).is_err());
+    }
+
+    #[test]
+    fn test_legacy_layout_size() {
+        // discriminator, authority, next_index, 26 subtrees, root, 100 roots, root_index,
+        // max_deposit_amount, height, root_history_size, bump and padding
+        assert_eq!(LegacyMerkleTreeAccount::SPACE, 8 + 32 + 8 + 26 * 32 + 32 + 100 * 32 + 8 + 8 + 8);
+    }
+
+    #[test]
+    fn test_upgrade_legacy_tree() {
+        let space = MerkleTreeAccount::space(LEGACY_MERKLE_TREE_HEIGHT, LEGACY_ROOT_HISTORY_SIZE as u8);
+        let mut original_buffer = tree_buffer(space);
+        let mut original = new_tree(&mut original_buffer);
+        for i in 0..5u8 {
+            MerkleTree::append::<Poseidon>(leaf(i), &mut original, 0).unwrap();
+        }
+        let old_roots: Vec<_> = original.recent_roots().collect();
+
+        let legacy_buffer = legacy_tree_data(&original);
+        let legacy = LegacyMerkleTreeAccount::from_bytes(bytemuck::cast_slice(&legacy_buffer)).unwrap();
+        let mut migrated_buffer = tree_buffer(space);
+        let mut migrated = new_tree(&mut migrated_buffer);
+        legacy.upgrade(&mut migrated, 1_000).unwrap();
+
+        // Notes of the legacy tree can still be proven against its roots
+        assert_eq!(migrated.recent_roots().collect::<Vec<_>>(), old_roots);
+        for root in &old_roots {
+            MerkleTree::check_root(&migrated, migrated.root_history, migrated.root_slots, *root, 1_050, 50).unwrap();
+        }
+
+        // and new commitments extend the same tree
+        MerkleTree::append_pair::<Poseidon>([leaf(5), leaf(6)], &mut original, 0).unwrap();
+        MerkleTree::append_pair::<Poseidon>([leaf(5), leaf(6)], &mut migrated, 0).unwrap();
+        assert_eq!(migrated.root, original.root);
+        assert_eq!(migrated.next_index, 7);
+    }
+
+    #[test]
+    fn test_reject_non_legacy_tree() {
+        let space = MerkleTreeAccount::space(LEGACY_MERKLE_TREE_HEIGHT, LEGACY_ROOT_HISTORY_SIZE as u8);
+        let mut buffer = tree_buffer(space);
+        let data = bytemuck::cast_slice_mut::<u64, u8>(&mut buffer);
+        data[..8].copy_from_slice(MerkleTreeAccount::DISCRIMINATOR);
+        assert!(LegacyMerkleTreeAccount::from_bytes(data).is_err());
+
+        let mut legacy_buffer = tree_buffer(LegacyMerkleTreeAccount::SPACE);
+        let legacy_data = bytemuck::cast_slice_mut::<u64, u8>(&mut legacy_buffer);
+        assert!(LegacyMerkleTreeAccount::from_bytes(legacy_data).is_err());
+    }
+}
@@ -1,10 +1,17 @@
+// The IDL instructions #[program] generates at the crate root still call the deprecated AccountInfo::realloc
+#![allow(deprecated)]
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
 declare_id!("6Uok9UsjztPC9VJ3a8ZpawzKmgrD2VvMKQGb64FYjhnx");
 declare_program!(jupiter_aggregator);
 
 
 pub mod merkle_tree;
+pub mod nullifier_set;
+pub mod root_history;
+pub mod legacy;
+pub mod oracle;
 pub mod utils;
 pub mod groth16;
 pub mod errors;
@@ -16,6 +23,7 @@ pub use state::*;
 pub use types::*;
 pub use instructions::*;
 pub use errors::ErrorCode;
+pub use legacy::{LEGACY_MERKLE_TREE_HEIGHT, LEGACY_ROOT_HISTORY_SIZE};
 
 
 pub const ADMIN_PUBKEY: Option<Pubkey> = Some(pubkey!("DJZCAiVf4WTB84iziDjXXpW32EJinEwbyary79NMjWz7"));
@@ -24,25 +32,178 @@ pub const ADMIN_PUBKEY: Option<Pubkey> = Some(pubkey!("DJZCAiVf4WTB84iziDjXXpW32
 pub mod zkcash {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize::handler(ctx)
-    }
-
-    pub fn update_deposit_limit(ctx: Context<UpdateDepositLimit>, new_limit: u64) -> Result<()> {
-        instructions::update_deposit_limit::handler(ctx, new_limit)
+    pub fn initialize(ctx: Context<Initialize>, height: u8, root_history_size: u8) -> Result<()> {
+        instructions::initialize::handler(ctx, height, root_history_size)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_global_config(
         ctx: Context<UpdateGlobalConfig>, 
         deposit_fee_rate: Option<u16>,
         withdrawal_fee_rate: Option<u16>,
-        fee_error_margin: Option<u16>
+        fee_error_margin: Option<u16>,
+        swap_fee_rate: Option<u16>,
+        max_swap_surplus_rate: Option<u16>,
+        guardian: Option<Pubkey>,
+        outflow_window_slots: Option<u64>,
+        min_relayer_stake: Option<u64>,
+        oracle_program: Option<Pubkey>,
+        root_validity_slots: Option<u64>,
     ) -> Result<()> {
         instructions::update_global_config::handler(
             ctx, 
             deposit_fee_rate, 
             withdrawal_fee_rate, 
-            fee_error_margin
+            fee_error_margin,
+            swap_fee_rate,
+            max_swap_surplus_rate,
+            guardian,
+            outflow_window_slots,
+            min_relayer_stake,
+            oracle_program,
+            root_validity_slots,
+        )
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+        instructions::set_pause::handler(ctx, paused)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority::handler(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority::handler(ctx)
+    }
+
+    pub fn migrate_global_config(ctx: Context<MigrateGlobalConfig>) -> Result<()> {
+        instructions::migrate_global_config::handler(ctx)
+    }
+
+    pub fn migrate_legacy_tree(ctx: Context<MigrateLegacyTree>) -> Result<()> {
+        instructions::migrate_legacy_tree::handler(ctx)
+    }
+
+    pub fn rollover_tree(ctx: Context<RolloverTree>, height: u8, root_history_size: u8) -> Result<()> {
+        instructions::rollover_tree::handler(ctx, height, root_history_size)
+    }
+
+    pub fn retire_tree(ctx: Context<RetireTree>) -> Result<()> {
+        instructions::retire_tree::handler(ctx)
+    }
+
+    pub fn initialize_root_history(ctx: Context<InitializeRootHistory>) -> Result<()> {
+        instructions::initialize_root_history::handler(ctx)
+    }
+
+    pub fn initialize_nullifier_shard(ctx: Context<InitializeNullifierShard>, shard_id: u8) -> Result<()> {
+        instructions::initialize_nullifier_shard::handler(ctx, shard_id)
+    }
+
+    pub fn register_verifying_key(
+        ctx: Context<RegisterVerifyingKey>,
+        version: u32,
+        tree_height: u8,
+        vk_alpha_g1: [u8; 64],
+        vk_beta_g2: [u8; 128],
+        vk_gamma_g2: [u8; 128],
+        vk_delta_g2: [u8; 128],
+    ) -> Result<()> {
+        instructions::register_verifying_key::handler(
+            ctx,
+            version,
+            tree_height,
+            vk_alpha_g1,
+            vk_beta_g2,
+            vk_gamma_g2,
+            vk_delta_g2,
+        )
+    }
+
+    pub fn write_verifying_key_ic(
+        ctx: Context<WriteVerifyingKeyIc>,
+        start_index: u8,
+        vk_ic: Vec<[u8; 64]>,
+    ) -> Result<()> {
+        instructions::write_verifying_key_ic::handler(ctx, start_index, vk_ic)
+    }
+
+    pub fn set_verifying_key_status(
+        ctx: Context<SetVerifyingKeyStatus>,
+        status: VerifyingKeyStatus,
+    ) -> Result<()> {
+        instructions::set_verifying_key_status::handler(ctx, status)
+    }
+
+    pub fn register_relayer(ctx: Context<RegisterRelayer>, relayer: Pubkey) -> Result<()> {
+        instructions::register_relayer::handler(ctx, relayer)
+    }
+
+    pub fn set_relayer_status(ctx: Context<SetRelayerStatus>, active: bool) -> Result<()> {
+        instructions::set_relayer_status::handler(ctx, active)
+    }
+
+    pub fn stake_relayer(ctx: Context<StakeRelayer>, amount: u64) -> Result<()> {
+        instructions::stake_relayer::handler(ctx, amount)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake::handler(ctx, amount)
+    }
+
+    pub fn unstake_relayer(ctx: Context<UnstakeRelayer>) -> Result<()> {
+        instructions::unstake_relayer::handler(ctx)
+    }
+
+    pub fn slash_relayer(ctx: Context<SlashRelayer>, amount: u64) -> Result<()> {
+        instructions::slash_relayer::handler(ctx, amount)
+    }
+
+    pub fn add_swap_program(ctx: Context<AddSwapProgram>, program_id: Pubkey) -> Result<()> {
+        instructions::add_swap_program::handler(ctx, program_id)
+    }
+
+    pub fn remove_swap_program(ctx: Context<RemoveSwapProgram>, program_id: Pubkey) -> Result<()> {
+        instructions::remove_swap_program::handler(ctx, program_id)
+    }
+
+    pub fn register_mint(
+        ctx: Context<RegisterMint>,
+        max_deposit_amount: u64,
+        min_deposit_amount: u64,
+        min_transfer_fee: u64,
+        account_creation_fee: u64,
+    ) -> Result<()> {
+        instructions::register_mint::handler(
+            ctx,
+            max_deposit_amount,
+            min_deposit_amount,
+            min_transfer_fee,
+            account_creation_fee,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_mint_config(
+        ctx: Context<UpdateMintConfig>,
+        max_deposit_amount: Option<u64>,
+        min_deposit_amount: Option<u64>,
+        enabled: Option<bool>,
+        max_outflow_per_window: Option<u64>,
+        price_feed_id: Option<[u8; 32]>,
+        min_transfer_fee: Option<u64>,
+        account_creation_fee: Option<u64>,
+    ) -> Result<()> {
+        instructions::update_mint_config::handler(
+            ctx,
+            max_deposit_amount,
+            min_deposit_amount,
+            enabled,
+            max_outflow_per_window,
+            price_feed_id,
+            min_transfer_fee,
+            account_creation_fee,
         )
     }
 
@@ -60,6 +221,15 @@ pub mod zkcash {
         )
     }
 
+    pub fn deposit_dual(
+        ctx: Context<DepositDual>,
+        proof: Proof,
+        ext_data_minified: DualExtDataMinified,
+        encrypted_output: Vec<u8>,
+    ) -> Result<()> {
+        instructions::deposit_dual::handler(ctx, proof, ext_data_minified, encrypted_output)
+    }
+
     pub fn withdraw(
         ctx: Context<Withdraw>,
         proof: Proof,
@@ -69,6 +239,24 @@ pub mod zkcash {
         instructions::withdraw::handler(ctx, proof, ext_data_minified, encrypted_output)    
     }
 
+    pub fn withdraw_dual(
+        ctx: Context<WithdrawDual>,
+        proof: Proof,
+        ext_data_minified: DualExtDataMinified,
+        encrypted_output: Vec<u8>,
+    ) -> Result<()> {
+        instructions::withdraw_dual::handler(ctx, proof, ext_data_minified, encrypted_output)
+    }
+
+    pub fn shielded_transfer(
+        ctx: Context<ShieldedTransfer>,
+        proof: Proof,
+        ext_data_minified: ExtDataMinified,
+        encrypted_output: Vec<u8>,
+    ) -> Result<()> {
+        instructions::shielded_transfer::handler(ctx, proof, ext_data_minified, encrypted_output)
+    }
+
     pub fn swap(
         ctx: Context<Swap>,
         proof: Proof,
@@ -81,12 +269,13 @@ pub mod zkcash {
 }
 
 #[derive(Accounts)]
+#[instruction(height: u8, root_history_size: u8)]
 pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + std::mem::size_of::<MerkleTreeAccount>(),
-        seeds = [b"merkle_tree"],
+        space = MerkleTreeAccount::checked_space(height, root_history_size)?,
+        seeds = [b"merkle_tree", 0u64.to_le_bytes().as_ref()],
         bump
     )]
     pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
@@ -108,6 +297,15 @@ pub struct Initialize<'info> {
         bump
     )]
     pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VerifyingKeyAccount>(),
+        seeds = [b"verifying_key", 0u32.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -116,21 +314,160 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
-pub struct UpdateDepositLimit<'info> {
+pub struct UpdateGlobalConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    
+    /// The authority account that can update the global config
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGlobalConfig<'info> {
+    /// CHECK: a global config in the legacy layout, parsed by LegacyGlobalConfig and reallocated in place
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump,
+        owner = crate::ID
+    )]
+    pub global_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VerifyingKeyAccount>(),
+        seeds = [b"verifying_key", 0u32.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    /// The authority of the legacy global config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateLegacyTree<'info> {
+    /// CHECK: the merkle tree of a legacy pool, parsed by LegacyMerkleTreeAccount and closed once migrated
     #[account(
         mut,
         seeds = [b"merkle_tree"],
-        bump = tree_account.load()?.bump,
+        bump,
+        owner = crate::ID
+    )]
+    pub legacy_tree_account: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MerkleTreeAccount::space(LEGACY_MERKLE_TREE_HEIGHT, LEGACY_ROOT_HISTORY_SIZE as u8),
+        seeds = [b"merkle_tree", 0u64.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// The authority of the legacy tree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(height: u8, root_history_size: u8)]
+pub struct RolloverTree<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
+        bump = current_tree_account.load()?.bump
+    )]
+    pub current_tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// An active verifying key whose circuit proves membership in trees of the new height
+    #[account(
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump,
+        constraint = verifying_key.status == VerifyingKeyStatus::Active @ ErrorCode::VerifyingKeyNotActive,
+        constraint = verifying_key.tree_height == height @ ErrorCode::TreeHeightMismatch
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = MerkleTreeAccount::checked_space(height, root_history_size)?,
+        seeds = [b"merkle_tree", (global_config.active_tree_id + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// The authority, or anyone once the current tree is full
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RetireTree<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
         has_one = authority @ ErrorCode::Unauthorized
     )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"merkle_tree", tree_account.load()?.tree_id.to_le_bytes().as_ref()],
+        bump = tree_account.load()?.bump
+    )]
     pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
-    
-    /// The authority account that can update the deposit limit
+
+    /// The authority account that can retire trees
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateGlobalConfig<'info> {
+pub struct InitializeRootHistory<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"merkle_tree", global_config.active_tree_id.to_le_bytes().as_ref()],
+        bump = tree_account.load()?.bump
+    )]
+    pub tree_account: AccountLoader<'info, MerkleTreeAccount>,
+
+    /// Created beforehand by the client with RootHistory::space(capacity) bytes, owned by this program
+    #[account(zero)]
+    pub root_history_account: AccountLoader<'info, RootHistoryAccount>,
+
+    /// The authority account that can attach root histories
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
     #[account(
         mut,
         seeds = [b"global_config"],
@@ -138,7 +475,317 @@ pub struct UpdateGlobalConfig<'info> {
         has_one = authority @ ErrorCode::Unauthorized
     )]
     pub global_config: Account<'info, GlobalConfig>,
-    
-    /// The authority account that can update the global config
+
+    /// The current authority proposing its successor
     pub authority: Signer<'info>,
 }
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.pending_authority == new_authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The proposed authority, e.g. a multisig vault
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(version: u32)]
+pub struct RegisterVerifyingKey<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VerifyingKeyAccount>(),
+        seeds = [b"verifying_key", version.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    /// The authority account that can register verifying keys
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteVerifyingKeyIc<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    /// The authority account that can register verifying keys
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVerifyingKeyStatus<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"verifying_key", verifying_key.version.to_le_bytes().as_ref()],
+        bump = verifying_key.bump
+    )]
+    pub verifying_key: Box<Account<'info, VerifyingKeyAccount>>,
+
+    /// The authority account that can activate and retire verifying keys
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct RegisterRelayer<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<RelayerAccount>(),
+        seeds = [b"relayer", relayer.as_ref()],
+        bump
+    )]
+    pub relayer_account: Account<'info, RelayerAccount>,
+
+    /// The authority account that can register relayers
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRelayerStatus<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer", relayer_account.relayer.as_ref()],
+        bump = relayer_account.bump
+    )]
+    pub relayer_account: Account<'info, RelayerAccount>,
+
+    /// The authority account that can list and delist relayers
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeRelayer<'info> {
+    #[account(
+        mut,
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_account.bump
+    )]
+    pub relayer_account: Account<'info, RelayerAccount>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_account.bump
+    )]
+    pub relayer_account: Account<'info, RelayerAccount>,
+
+    pub relayer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeRelayer<'info> {
+    #[account(
+        mut,
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_account.bump
+    )]
+    pub relayer_account: Account<'info, RelayerAccount>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashRelayer<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer", relayer_account.relayer.as_ref()],
+        bump = relayer_account.bump
+    )]
+    pub relayer_account: Account<'info, RelayerAccount>,
+
+    /// The authority account that can slash relayers, it receives the slashed lamports
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddSwapProgram<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SwapProgramRegistry::SPACE,
+        seeds = [b"swap_program_registry"],
+        bump
+    )]
+    pub swap_program_registry: Account<'info, SwapProgramRegistry>,
+
+    /// The authority account that can manage swap programs
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveSwapProgram<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"swap_program_registry"],
+        bump = swap_program_registry.bump
+    )]
+    pub swap_program_registry: Account<'info, SwapProgramRegistry>,
+
+    /// The authority account that can manage swap programs
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMint<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<MintConfig>(),
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// The authority account that can register mints
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintConfig<'info> {
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint_config.mint.as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// The authority account that can update mint configs
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The authority, or the guardian when only adding pause flags
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(shard_id: u8)]
+pub struct InitializeNullifierShard<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<NullifierShard>(),
+        seeds = [b"nullifier_shard", shard_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nullifier_shard: AccountLoader<'info, NullifierShard>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -2,18 +2,75 @@
 use light_hasher::Hasher;
 use crate::{MerkleTreeAccount, ErrorCode};
 use anchor_lang::prelude::*;
+use std::ops::{Deref, DerefMut};
+
+/// A merkle tree account: its header followed by `height` subtrees, `root_history_size` roots
+/// and the slot each of these roots was recorded at.
+pub struct MerkleTreeData<'a> {
+    pub header: &'a mut MerkleTreeAccount,
+    pub subtrees: &'a mut [[u8; 32]],
+    pub root_history: &'a mut [[u8; 32]],
+    pub root_slots: &'a mut [u64],
+}
+
+impl<'a> MerkleTreeData<'a> {
+    /// Splits the data of a tree account, discriminator included, into its header, subtrees, root history and root slots.
+    pub fn from_bytes(data: &'a mut [u8]) -> Result<Self> {
+        let header_end = MerkleTreeAccount::SUBTREES_OFFSET;
+        require!(data.len() >= header_end, anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+
+        let (header, rest) = data[8..].split_at_mut(header_end - 8);
+        let header: &mut MerkleTreeAccount = bytemuck::from_bytes_mut(header);
+        let subtrees_len = header.height as usize * 32;
+        let root_history_len = header.root_history_size as usize * 32;
+        let root_slots_len = header.root_history_size as usize * 8;
+        require!(
+            rest.len() >= subtrees_len + root_history_len + root_slots_len,
+            anchor_lang::error::ErrorCode::AccountDidNotDeserialize
+        );
+
+        let (subtrees, rest) = rest.split_at_mut(subtrees_len);
+        let (root_history, rest) = rest.split_at_mut(root_history_len);
+        Ok(Self {
+            header,
+            subtrees: bytemuck::cast_slice_mut(subtrees),
+            root_history: bytemuck::cast_slice_mut(root_history),
+            root_slots: bytemuck::cast_slice_mut(&mut rest[..root_slots_len]),
+        })
+    }
+
+    /// Roots from the oldest to the most recent one, as stored in the tree's own root history.
+    pub fn recent_roots(&self) -> impl Iterator<Item = [u8; 32]> + '_ {
+        let root_history_size = self.root_history.len();
+        (1..=root_history_size)
+            .map(move |offset| self.root_history[(self.header.root_index as usize + offset) % root_history_size])
+            .filter(|root| *root != [0u8; 32])
+    }
+}
+
+impl Deref for MerkleTreeData<'_> {
+    type Target = MerkleTreeAccount;
+
+    fn deref(&self) -> &MerkleTreeAccount {
+        self.header
+    }
+}
+
+impl DerefMut for MerkleTreeData<'_> {
+    fn deref_mut(&mut self) -> &mut MerkleTreeAccount {
+        self.header
+    }
+}
 
 pub struct MerkleTree;
 
 impl MerkleTree {
-    pub fn initialize<H: Hasher>(tree_account: &mut MerkleTreeAccount) -> Result<()> {
+    pub fn initialize<H: Hasher>(tree_account: &mut MerkleTreeData) -> Result<()> {
         let height = tree_account.height as usize;
         
         // Initialize empty subtrees
         let zero_bytes = H::zero_bytes();
-        for i in 0..height {
-            tree_account.subtrees[i] = zero_bytes[i];
-        }
+        tree_account.subtrees.copy_from_slice(&zero_bytes[..height]);
 
         // Set initial root
         let initial_root = H::zero_bytes()[height];
@@ -25,10 +82,10 @@ impl MerkleTree {
 
     pub fn append<H: Hasher>(
         leaf: [u8; 32],
-        tree_account: &mut MerkleTreeAccount,
+        tree_account: &mut MerkleTreeData,
+        slot: u64,
     ) -> Result<Vec<[u8; 32]>> {
         let height = tree_account.height as usize;
-        let root_history_size = tree_account.root_history_size as usize;
         
         // Check if tree is full before appending
         // Maximum capacity is 2^height leaves
@@ -44,50 +101,139 @@ impl MerkleTree {
         let mut right;
         let mut proof: Vec<[u8; 32]> = vec![[0u8; 32]; height];
 
-        for i in 0..height {
+        for (i, sibling) in proof.iter_mut().enumerate() {
             let subtree = &mut tree_account.subtrees[i];
             let zero_byte = H::zero_bytes()[i];
             
-            if current_index % 2 == 0 {
+            if current_index & 1 == 0 {
                 left = current_level_hash;
                 right = zero_byte;
                 *subtree = current_level_hash;
-                proof[i] = right;
+                *sibling = right;
             } else {
                 left = *subtree;
                 right = current_level_hash;
-                proof[i] = left;
+                *sibling = left;
             }
             current_level_hash = H::hashv(&[&left, &right]).unwrap();
             current_index /= 2;
         }
         
-        tree_account.root = current_level_hash;
         tree_account.next_index = tree_account.next_index
             .checked_add(1)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Self::push_root(tree_account, current_level_hash, slot)?;
         
+        Ok(proof)
+    }
+
+    /// Appends the two output commitments of a transaction. Pairs are always inserted at an even index,
+    /// so they fill a whole level-1 node: the pair is hashed once, height - 1 levels are walked
+    /// and a single root is recorded for both leaves, at `slot`.
+    pub fn append_pair<H: Hasher>(
+        leaves: [[u8; 32]; 2],
+        tree_account: &mut MerkleTreeData,
+        slot: u64,
+    ) -> Result<()> {
+        // Trees filled by single appends may be misaligned, insert those leaf by leaf
+        if tree_account.next_index & 1 != 0 {
+            Self::append::<H>(leaves[0], tree_account, slot)?;
+            Self::append::<H>(leaves[1], tree_account, slot)?;
+            return Ok(());
+        }
+
+        let height = tree_account.height as usize;
+        let max_capacity = 1u64 << height; // 2^height
+        require!(
+            tree_account.next_index + 2 <= max_capacity,
+            ErrorCode::MerkleTreeFull
+        );
+
+        tree_account.subtrees[0] = leaves[0];
+        let mut current_index = (tree_account.next_index / 2) as usize;
+        let mut current_level_hash = H::hashv(&[&leaves[0], &leaves[1]]).unwrap();
+
+        for i in 1..height {
+            let subtree = &mut tree_account.subtrees[i];
+
+            if current_index & 1 == 0 {
+                *subtree = current_level_hash;
+                current_level_hash = H::hashv(&[&current_level_hash, &H::zero_bytes()[i]]).unwrap();
+            } else {
+                current_level_hash = H::hashv(&[subtree, &current_level_hash]).unwrap();
+            }
+            current_index /= 2;
+        }
+
+        tree_account.next_index += 2;
+        Self::push_root(tree_account, current_level_hash, slot)
+    }
+
+    fn push_root(tree_account: &mut MerkleTreeData, root: [u8; 32], slot: u64) -> Result<()> {
+        let root_history_size = tree_account.root_history_size as usize;
+
+        tree_account.root = root;
         let new_root_index = (tree_account.root_index as usize)
             .checked_add(1)
             .ok_or(ErrorCode::ArithmeticOverflow)? % root_history_size;
         tree_account.root_index = new_root_index as u64;
-        tree_account.root_history[new_root_index] = current_level_hash;
-        
-        Ok(proof)
+        tree_account.root_history[new_root_index] = root;
+        tree_account.root_slots[new_root_index] = slot;
+
+        Ok(())
     }
 
-    pub fn is_known_root(tree_account: &MerkleTreeAccount, root: [u8; 32]) -> bool {
-        if root == [0u8; 32] {
+    /// Checks that `root` is in the tree's own root history and has not expired at slot `now`.
+    /// Like in a RootHistoryAccount, the most recent root never expires and older ones stay valid
+    /// for `validity_slots` after the root that replaced them was recorded, 0 disabling the expiry.
+    pub fn check_root(
+        tree_account: &MerkleTreeAccount,
+        root_history: &[[u8; 32]],
+        root_slots: &[u64],
+        root: [u8; 32],
+        now: u64,
+        validity_slots: u64,
+    ) -> Result<()> {
+        let position = Self::root_position(tree_account, root_history, root).ok_or(ErrorCode::UnknownRoot)?;
+        require!(
+            !Self::is_expired(tree_account, root_slots, position, now, validity_slots),
+            ErrorCode::RootExpired
+        );
+        Ok(())
+    }
+
+    /// Whether the root at `position` of the tree's own root history expired at slot `now`, see check_root.
+    pub fn is_expired(
+        tree_account: &MerkleTreeAccount,
+        root_slots: &[u64],
+        position: usize,
+        now: u64,
+        validity_slots: u64,
+    ) -> bool {
+        if validity_slots == 0 || position == tree_account.root_index as usize {
             return false;
         }
+        let next = (position + 1) % tree_account.root_history_size as usize;
+        now.saturating_sub(root_slots[next]) > validity_slots
+    }
+
+    pub fn is_known_root(tree_account: &MerkleTreeAccount, root_history: &[[u8; 32]], root: [u8; 32]) -> bool {
+        Self::root_position(tree_account, root_history, root).is_some()
+    }
+
+    /// Position of `root` in the tree's own root history, searched from the most recent root.
+    pub fn root_position(tree_account: &MerkleTreeAccount, root_history: &[[u8; 32]], root: [u8; 32]) -> Option<usize> {
+        if root == [0u8; 32] {
+            return None;
+        }
         
         let root_history_size = tree_account.root_history_size as usize;
         let current_root_index = tree_account.root_index as usize;
         let mut i = current_root_index;
         
         loop {
-            if root == tree_account.root_history[i] {
-                return true;
+            if root == root_history[i] {
+                return Some(i);
             }
             
             if i == 0 {
@@ -101,6 +247,179 @@ impl MerkleTree {
             }
         }
         
-        false
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{MERKLE_TREE_HEIGHT, ROOT_HISTORY_SIZE};
+    use light_hasher::Poseidon;
+
+    // Backed by u64 words so that the header is aligned like in account data
+    struct TestTree(Vec<u64>);
+
+    impl TestTree {
+        fn new(height: u8, root_history_size: u8) -> Self {
+            let space = MerkleTreeAccount::space(height, root_history_size);
+            let mut tree = Self(vec![0u64; space.div_ceil(8)]);
+            {
+                let data = bytemuck::cast_slice_mut::<u64, u8>(&mut tree.0);
+                let header: &mut MerkleTreeAccount =
+                    bytemuck::from_bytes_mut(&mut data[8..MerkleTreeAccount::SUBTREES_OFFSET]);
+                header.height = height;
+                header.root_history_size = root_history_size;
+            }
+            MerkleTree::initialize::<Poseidon>(&mut tree.data()).unwrap();
+            tree
+        }
+
+        fn data(&mut self) -> MerkleTreeData<'_> {
+            MerkleTreeData::from_bytes(bytemuck::cast_slice_mut(&mut self.0)).unwrap()
+        }
+    }
+
+    fn new_tree() -> TestTree {
+        TestTree::new(MERKLE_TREE_HEIGHT, ROOT_HISTORY_SIZE as u8)
+    }
+
+    fn leaf(i: u8) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(&[i]).to_bytes()
+    }
+
+    #[test]
+    fn test_append_pair_matches_two_appends() {
+        let mut single_tree = new_tree();
+        let mut paired_tree = new_tree();
+        let (mut single, mut paired) = (single_tree.data(), paired_tree.data());
+
+        for i in 0..5u8 {
+            MerkleTree::append::<Poseidon>(leaf(2 * i), &mut single, 0).unwrap();
+            MerkleTree::append::<Poseidon>(leaf(2 * i + 1), &mut single, 0).unwrap();
+            MerkleTree::append_pair::<Poseidon>([leaf(2 * i), leaf(2 * i + 1)], &mut paired, 0).unwrap();
+
+            assert_eq!(paired.root, single.root);
+            assert_eq!(paired.next_index, single.next_index);
+            assert_eq!(paired.subtrees, single.subtrees);
+        }
+    }
+
+    #[test]
+    fn test_append_pair_records_one_root() {
+        let mut tree = new_tree();
+        let mut tree_account = tree.data();
+        MerkleTree::append_pair::<Poseidon>([leaf(1), leaf(2)], &mut tree_account, 0).unwrap();
+
+        assert_eq!(tree_account.root_index, 1);
+        assert_eq!(tree_account.root_history[1], tree_account.root);
+        assert!(MerkleTree::is_known_root(&tree_account, tree_account.root_history, tree_account.root));
+    }
+
+    #[test]
+    fn test_append_pair_after_odd_index() {
+        let mut single_tree = new_tree();
+        let mut paired_tree = new_tree();
+        let (mut single, mut paired) = (single_tree.data(), paired_tree.data());
+        MerkleTree::append::<Poseidon>(leaf(9), &mut single, 0).unwrap();
+        MerkleTree::append::<Poseidon>(leaf(9), &mut paired, 0).unwrap();
+
+        MerkleTree::append::<Poseidon>(leaf(1), &mut single, 0).unwrap();
+        MerkleTree::append::<Poseidon>(leaf(2), &mut single, 0).unwrap();
+        MerkleTree::append_pair::<Poseidon>([leaf(1), leaf(2)], &mut paired, 0).unwrap();
+
+        assert_eq!(paired.root, single.root);
+        assert_eq!(paired.next_index, 3);
+    }
+
+    #[test]
+    fn test_small_tree_fills_up() {
+        let mut tree = TestTree::new(3, 4);
+        let mut tree_account = tree.data();
+        assert_eq!(tree_account.subtrees.len(), 3);
+        assert_eq!(tree_account.root_history.len(), 4);
+
+        for i in 0..4u8 {
+            MerkleTree::append_pair::<Poseidon>([leaf(2 * i), leaf(2 * i + 1)], &mut tree_account, 0).unwrap();
+        }
+        assert!(tree_account.is_full());
+        assert!(MerkleTree::append_pair::<Poseidon>([leaf(8), leaf(9)], &mut tree_account, 0).is_err());
+
+        let hash = |left: [u8; 32], right: [u8; 32]| Poseidon::hashv(&[&left, &right]).unwrap();
+        let level1: Vec<_> = (0..4u8).map(|i| hash(leaf(2 * i), leaf(2 * i + 1))).collect();
+        let expected_root = hash(hash(level1[0], level1[1]), hash(level1[2], level1[3]));
+        assert_eq!(tree_account.root, expected_root);
+    }
+
+    #[test]
+    fn test_small_root_history_wraps_around() {
+        let mut tree = TestTree::new(MERKLE_TREE_HEIGHT, 3);
+        let mut tree_account = tree.data();
+
+        let mut roots = vec![tree_account.root];
+        for i in 0..4u8 {
+            MerkleTree::append_pair::<Poseidon>([leaf(2 * i), leaf(2 * i + 1)], &mut tree_account, 0).unwrap();
+            roots.push(tree_account.root);
+        }
+
+        for (i, root) in roots.iter().enumerate() {
+            let known = MerkleTree::is_known_root(&tree_account, tree_account.root_history, *root);
+            assert_eq!(known, i >= roots.len() - 3);
+        }
+        assert_eq!(tree_account.recent_roots().collect::<Vec<_>>(), roots[2..]);
+    }
+
+    #[test]
+    fn test_root_expires_after_being_replaced() {
+        let mut tree = TestTree::new(MERKLE_TREE_HEIGHT, 4);
+        let mut tree_account = tree.data();
+
+        let mut roots = vec![];
+        for (i, slot) in [1_000u64, 1_040, 1_100].into_iter().enumerate() {
+            let i = i as u8;
+            MerkleTree::append_pair::<Poseidon>([leaf(2 * i), leaf(2 * i + 1)], &mut tree_account, slot).unwrap();
+            roots.push(tree_account.root);
+        }
+        let check = |tree_account: &MerkleTreeData, root: [u8; 32], now: u64, validity_slots: u64| {
+            MerkleTree::check_root(tree_account, tree_account.root_history, tree_account.root_slots, root, now, validity_slots)
+        };
+
+        // Replaced at slot 1_040, then at slot 1_100
+        assert!(check(&tree_account, roots[0], 1_090, 50).is_ok());
+        assert!(format!("{:?}", check(&tree_account, roots[0], 1_091, 50)).contains("RootExpired"));
+        assert!(check(&tree_account, roots[1], 1_150, 50).is_ok());
+        assert!(format!("{:?}", check(&tree_account, roots[1], 1_151, 50)).contains("RootExpired"));
+
+        // The most recent root never expires and a validity of 0 disables the expiry
+        assert!(check(&tree_account, roots[2], u64::MAX, 50).is_ok());
+        assert!(check(&tree_account, roots[0], u64::MAX, 0).is_ok());
+        assert!(format!("{:?}", check(&tree_account, leaf(0), 1_000, 50)).contains("UnknownRoot"));
+    }
+
+    #[test]
+    fn test_root_expiry_across_wrap_around() {
+        let mut tree = TestTree::new(MERKLE_TREE_HEIGHT, 3);
+        let mut tree_account = tree.data();
+
+        let mut roots = vec![];
+        for i in 0..4u8 {
+            MerkleTree::append_pair::<Poseidon>([leaf(2 * i), leaf(2 * i + 1)], &mut tree_account, 100 * i as u64).unwrap();
+            roots.push(tree_account.root);
+        }
+
+        // roots[1] sits at the last position, its successor roots[2] at position 0 was recorded at slot 200
+        let position = MerkleTree::root_position(&tree_account, tree_account.root_history, roots[1]).unwrap();
+        assert_eq!(position, 2);
+        assert!(!MerkleTree::is_expired(&tree_account, tree_account.root_slots, position, 210, 10));
+        assert!(MerkleTree::is_expired(&tree_account, tree_account.root_slots, position, 211, 10));
+        assert!(MerkleTree::root_position(&tree_account, tree_account.root_history, roots[0]).is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_short_account() {
+        let mut tree = TestTree::new(MERKLE_TREE_HEIGHT, 10);
+        let data = bytemuck::cast_slice_mut::<u64, u8>(&mut tree.0);
+        let len = MerkleTreeAccount::space(MERKLE_TREE_HEIGHT, 9);
+        assert!(MerkleTreeData::from_bytes(&mut data[..len]).is_err());
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,283 @@
+// Nullifiers are stored in 256 shard accounts, selected by the last byte of the nullifier.
+// Each shard keeps its nullifiers sorted right after the header, so membership is a binary search
+// and spending only pays rent for 32 more bytes instead of a whole account per nullifier.
+//
+// A shard is capped by the 10 MiB account size limit at MAX_NULLIFIERS_PER_SHARD = 327,679 nullifiers,
+// about 84 million across the 256 shards, and check_shard rejects inserts into a full shard. Inserting
+// moves the entries after the insertion point, at worst the whole 10 MiB. On SBF the move is a sol_memmove
+// syscall, charged one CU per 250 bytes: about 42,000 CU for a full shard and 84,000 CU for the two inputs
+// of a transaction, on top of the proof verification. This bound follows from the syscall pricing and was
+// not measured on a cluster; test_insert_into_near_full_shard runs the worst case off-chain.
+use crate::{NullifierShard, ErrorCode};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+use anchor_lang::system_program;
+
+pub const NULLIFIER_SIZE: usize = 32;
+
+pub struct NullifierSet;
+
+impl NullifierSet {
+    /// Offset of the first nullifier in a shard account: discriminator + header.
+    pub const ENTRIES_OFFSET: usize = 8 + std::mem::size_of::<NullifierShard>();
+
+    /// Most nullifiers a shard can hold within the account size limit.
+    pub const MAX_NULLIFIERS_PER_SHARD: usize =
+        (MAX_PERMITTED_DATA_LENGTH as usize - Self::ENTRIES_OFFSET) / NULLIFIER_SIZE;
+
+    /// Id of the shard holding `nullifier`. Nullifiers are big-endian field elements,
+    /// so the last byte is uniformly distributed while the first one is not.
+    pub fn shard_id(nullifier: &[u8; 32]) -> u8 {
+        nullifier[31]
+    }
+
+    /// Account size of a shard holding `count` nullifiers.
+    pub fn space(count: usize) -> usize {
+        Self::ENTRIES_OFFSET + count * NULLIFIER_SIZE
+    }
+
+    /// Marks `nullifier` as spent, failing with NullifierAlreadyUsed if it was spent before,
+    /// either in `shard` or as a PDA of the previous one-account-per-nullifier scheme.
+    pub fn spend<'info>(
+        shard: &AccountLoader<'info, NullifierShard>,
+        legacy_nullifier: &AccountInfo<'info>,
+        nullifier: [u8; 32],
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        Self::check_legacy_nullifier(legacy_nullifier)?;
+        let count = Self::check_shard(&*shard.load()?, &nullifier)?;
+
+        let shard_info = shard.to_account_info();
+        let position = {
+            let data = shard_info.try_borrow_data()?;
+            match Self::find(&data[Self::ENTRIES_OFFSET..Self::space(count)], &nullifier) {
+                Ok(_) => return err!(ErrorCode::NullifierAlreadyUsed),
+                Err(position) => position,
+            }
+        };
+
+        // Grow the shard by one entry, the payer covers the additional rent
+        let new_len = Self::space(count + 1);
+        let rent_due = Self::rent_due(&Rent::get()?, new_len, shard_info.lamports());
+        if rent_due > 0 {
+            let transfer_ctx = CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: shard_info.clone(),
+                },
+            );
+            system_program::transfer(transfer_ctx, rent_due)?;
+        }
+        shard_info.resize(new_len)?;
+
+        Self::insert(&mut shard_info.try_borrow_mut_data()?[Self::ENTRIES_OFFSET..], position, &nullifier);
+
+        let mut header = shard.load_mut()?;
+        header.count = header.count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// Fails with NullifierAlreadyUsed if the nullifier was spent under the previous scheme,
+    /// which created an account at its `[b"nullifier", nullifier]` PDA.
+    pub fn check_legacy_nullifier(legacy_nullifier: &AccountInfo) -> Result<()> {
+        require!(legacy_nullifier.data_is_empty(), ErrorCode::NullifierAlreadyUsed);
+        Ok(())
+    }
+
+    /// Checks that `nullifier` belongs in `shard` and that the shard has room for it.
+    /// Returns the number of nullifiers already stored.
+    pub fn check_shard(shard: &NullifierShard, nullifier: &[u8; 32]) -> Result<usize> {
+        require!(shard.shard_id == Self::shard_id(nullifier), ErrorCode::InvalidNullifierShard);
+        let count = shard.count as usize;
+        require!(count < Self::MAX_NULLIFIERS_PER_SHARD, ErrorCode::NullifierShardFull);
+        Ok(count)
+    }
+
+    /// Lamports to add to a shard holding `lamports` for it to stay rent exempt at `new_len` bytes.
+    pub fn rent_due(rent: &Rent, new_len: usize, lamports: u64) -> u64 {
+        rent.minimum_balance(new_len).saturating_sub(lamports)
+    }
+
+    /// Inserts `nullifier` at `position` in packed 32-byte `entries` whose last slot is free,
+    /// shifting the entries after it by one slot.
+    pub fn insert(entries: &mut [u8], position: usize, nullifier: &[u8; 32]) {
+        let insert_at = position * NULLIFIER_SIZE;
+        let entries_end = entries.len() - NULLIFIER_SIZE;
+        entries.copy_within(insert_at..entries_end, insert_at + NULLIFIER_SIZE);
+        entries[insert_at..insert_at + NULLIFIER_SIZE].copy_from_slice(nullifier);
+    }
+
+    /// Binary search of `nullifier` in sorted, packed 32-byte `entries`.
+    /// Returns Ok(index) if present, Err(insertion index) otherwise.
+    pub fn find(entries: &[u8], nullifier: &[u8; 32]) -> std::result::Result<usize, usize> {
+        let mut low = 0;
+        let mut high = entries.len() / NULLIFIER_SIZE;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let entry = &entries[mid * NULLIFIER_SIZE..(mid + 1) * NULLIFIER_SIZE];
+            match entry.cmp(&nullifier[..]) {
+                std::cmp::Ordering::Equal => return Ok(mid),
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+            }
+        }
+
+        Err(low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nullifier(first: u8, last: u8) -> [u8; 32] {
+        let mut nullifier = [0u8; 32];
+        nullifier[0] = first;
+        nullifier[31] = last;
+        nullifier
+    }
+
+    fn pack(nullifiers: &[[u8; 32]]) -> Vec<u8> {
+        nullifiers.iter().flatten().copied().collect()
+    }
+
+    #[test]
+    fn test_find_empty_shard() {
+        assert_eq!(NullifierSet::find(&[], &nullifier(1, 7)), Err(0));
+    }
+
+    #[test]
+    fn test_find_existing_nullifier() {
+        let entries = pack(&[nullifier(1, 7), nullifier(2, 7), nullifier(5, 7)]);
+        assert_eq!(NullifierSet::find(&entries, &nullifier(1, 7)), Ok(0));
+        assert_eq!(NullifierSet::find(&entries, &nullifier(2, 7)), Ok(1));
+        assert_eq!(NullifierSet::find(&entries, &nullifier(5, 7)), Ok(2));
+    }
+
+    #[test]
+    fn test_find_insertion_position() {
+        let entries = pack(&[nullifier(1, 7), nullifier(2, 7), nullifier(5, 7)]);
+        assert_eq!(NullifierSet::find(&entries, &nullifier(0, 7)), Err(0));
+        assert_eq!(NullifierSet::find(&entries, &nullifier(3, 7)), Err(2));
+        assert_eq!(NullifierSet::find(&entries, &nullifier(6, 7)), Err(3));
+    }
+
+    fn shard(shard_id: u8, count: u32) -> NullifierShard {
+        NullifierShard { count, shard_id, bump: 255, _padding: [0; 2] }
+    }
+
+    #[test]
+    fn test_insert_keeps_entries_sorted() {
+        let mut entries: Vec<u8> = Vec::new();
+        for first in [5u8, 1, 9, 3, 7, 0, 8] {
+            let position = NullifierSet::find(&entries, &nullifier(first, 7)).unwrap_err();
+            entries.resize(entries.len() + NULLIFIER_SIZE, 0);
+            NullifierSet::insert(&mut entries, position, &nullifier(first, 7));
+        }
+
+        let expected: Vec<_> = [0u8, 1, 3, 5, 7, 8, 9].iter().map(|first| nullifier(*first, 7)).collect();
+        assert_eq!(entries, pack(&expected));
+        assert_eq!(NullifierSet::find(&entries, &nullifier(7, 7)), Ok(4));
+    }
+
+    #[test]
+    fn test_insert_at_end() {
+        let mut entries = pack(&[nullifier(1, 7), nullifier(2, 7), [0u8; 32]]);
+        NullifierSet::insert(&mut entries, 2, &nullifier(3, 7));
+        assert_eq!(entries, pack(&[nullifier(1, 7), nullifier(2, 7), nullifier(3, 7)]));
+    }
+
+    #[test]
+    fn test_insert_into_near_full_shard() {
+        // Entry i has i in its bytes 1..5, so that the entries are sorted and distinct
+        let count = NullifierSet::MAX_NULLIFIERS_PER_SHARD - 1;
+        let mut entries = vec![0u8; NullifierSet::MAX_NULLIFIERS_PER_SHARD * NULLIFIER_SIZE];
+        for (i, entry) in entries.chunks_exact_mut(NULLIFIER_SIZE).take(count).enumerate() {
+            entry[0] = 1;
+            entry[1..5].copy_from_slice(&(i as u32).to_be_bytes());
+            entry[31] = 7;
+        }
+        assert!(NullifierSet::check_shard(&shard(7, count as u32), &nullifier(0, 7)).is_ok());
+
+        // The smallest nullifier moves every stored entry
+        let first = nullifier(0, 7);
+        let position = NullifierSet::find(&entries[..count * NULLIFIER_SIZE], &first).unwrap_err();
+        assert_eq!(position, 0);
+        let last = entries[(count - 1) * NULLIFIER_SIZE..count * NULLIFIER_SIZE].to_vec();
+        NullifierSet::insert(&mut entries, position, &first);
+
+        assert_eq!(&entries[..NULLIFIER_SIZE], &first);
+        assert_eq!(&entries[count * NULLIFIER_SIZE..], &last[..]);
+        assert_eq!(NullifierSet::find(&entries, &first), Ok(0));
+        assert_eq!(NullifierSet::find(&entries, &last.try_into().unwrap()), Ok(count));
+        assert_eq!(NullifierSet::space(count + 1), NullifierSet::ENTRIES_OFFSET + entries.len());
+    }
+
+    #[test]
+    fn test_space_grows_by_one_entry() {
+        assert_eq!(NullifierSet::space(0), NullifierSet::ENTRIES_OFFSET);
+        assert_eq!(NullifierSet::space(4) - NullifierSet::space(3), NULLIFIER_SIZE);
+        assert!(NullifierSet::space(NullifierSet::MAX_NULLIFIERS_PER_SHARD) <= MAX_PERMITTED_DATA_LENGTH as usize);
+        assert!(NullifierSet::space(NullifierSet::MAX_NULLIFIERS_PER_SHARD + 1) > MAX_PERMITTED_DATA_LENGTH as usize);
+        assert_eq!(NullifierSet::MAX_NULLIFIERS_PER_SHARD, 327_679);
+    }
+
+    #[test]
+    fn test_rent_due_tops_up_to_new_size() {
+        let rent = Rent::default();
+        let lamports = rent.minimum_balance(NullifierSet::space(3));
+        let rent_due = NullifierSet::rent_due(&rent, NullifierSet::space(4), lamports);
+        assert_eq!(rent_due, rent.minimum_balance(NullifierSet::space(4)) - lamports);
+        assert_eq!(rent_due, NULLIFIER_SIZE as u64 * rent.lamports_per_byte_year * rent.exemption_threshold as u64);
+
+        // Nothing is due when the shard already holds enough lamports
+        assert_eq!(NullifierSet::rent_due(&rent, NullifierSet::space(4), lamports + rent_due), 0);
+        assert_eq!(NullifierSet::rent_due(&rent, NullifierSet::space(4), u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_check_shard_rejects_wrong_shard() {
+        assert_eq!(NullifierSet::check_shard(&shard(7, 3), &nullifier(1, 7)).unwrap(), 3);
+        assert_eq!(
+            NullifierSet::check_shard(&shard(8, 3), &nullifier(1, 7)).unwrap_err(),
+            error!(ErrorCode::InvalidNullifierShard)
+        );
+    }
+
+    #[test]
+    fn test_check_shard_rejects_full_shard() {
+        let full = shard(7, NullifierSet::MAX_NULLIFIERS_PER_SHARD as u32);
+        assert_eq!(
+            NullifierSet::check_shard(&full, &nullifier(1, 7)).unwrap_err(),
+            error!(ErrorCode::NullifierShardFull)
+        );
+    }
+
+    #[test]
+    fn test_check_legacy_nullifier() {
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let (mut lamports, mut spent_lamports) = (0u64, 1_000_000u64);
+        let (mut no_data, mut spent_data) = (vec![], vec![0u8; 9]);
+
+        let unspent = AccountInfo::new(&key, false, false, &mut lamports, &mut no_data, &owner, false, 0);
+        assert!(NullifierSet::check_legacy_nullifier(&unspent).is_ok());
+
+        let spent = AccountInfo::new(&key, false, false, &mut spent_lamports, &mut spent_data, &owner, false, 0);
+        assert_eq!(
+            NullifierSet::check_legacy_nullifier(&spent).unwrap_err(),
+            error!(ErrorCode::NullifierAlreadyUsed)
+        );
+    }
+
+    #[test]
+    fn test_shard_id_uses_last_byte() {
+        assert_eq!(NullifierSet::shard_id(&nullifier(0x30, 0xab)), 0xab);
+    }
+}
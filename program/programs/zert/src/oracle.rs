@@ -0,0 +1,169 @@
+// Price checks for swaps against Pyth pull-oracle accounts (PriceUpdateV2).
+// The accounts are parsed by hand so that no Pyth SDK is needed, and their owner is taken
+// from GlobalConfig::oracle_program so that a mock oracle can be used on localnet.
+use crate::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Price updates older than this are rejected.
+pub const MAX_PRICE_AGE_SECONDS: i64 = 60;
+
+// PriceUpdateV2 layout: discriminator, write_authority, verification_level, price_message, posted_slot
+const VERIFICATION_LEVEL_OFFSET: usize = 8 + 32;
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8 + 8 + 8 + 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceUpdate {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl PriceUpdate {
+    /// Parses a fully verified PriceUpdateV2 account.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let discriminator = &hash(b"account:PriceUpdateV2").to_bytes()[..8];
+        require!(
+            data.len() > VERIFICATION_LEVEL_OFFSET && data[..8] == *discriminator,
+            ErrorCode::InvalidPriceUpdate
+        );
+        // Partially verified updates carry an extra num_signatures byte and are not trusted
+        require!(
+            data[VERIFICATION_LEVEL_OFFSET] == VERIFICATION_LEVEL_FULL,
+            ErrorCode::InvalidPriceUpdate
+        );
+
+        let message_offset = VERIFICATION_LEVEL_OFFSET + 1;
+        let message = data
+            .get(message_offset..message_offset + PRICE_MESSAGE_LEN)
+            .ok_or(ErrorCode::InvalidPriceUpdate)?;
+
+        let mut feed_id = [0u8; 32];
+        feed_id.copy_from_slice(&message[..32]);
+        Ok(Self {
+            feed_id,
+            price: i64::from_le_bytes(message[32..40].try_into().unwrap()),
+            exponent: i32::from_le_bytes(message[48..52].try_into().unwrap()),
+            publish_time: i64::from_le_bytes(message[52..60].try_into().unwrap()),
+        })
+    }
+
+    /// Loads the price of `feed_id` from `account`, which must be owned by `oracle_program`
+    /// and published at most MAX_PRICE_AGE_SECONDS before `now`.
+    pub fn load(account: &AccountInfo, oracle_program: &Pubkey, feed_id: &[u8; 32], now: i64) -> Result<Self> {
+        require!(account.owner == oracle_program, ErrorCode::InvalidPriceUpdate);
+
+        let update = Self::parse(&account.try_borrow_data()?)?;
+        require!(update.feed_id == *feed_id, ErrorCode::InvalidPriceUpdate);
+        require!(update.price > 0, ErrorCode::InvalidPriceUpdate);
+        require!(
+            now.saturating_sub(update.publish_time) <= MAX_PRICE_AGE_SECONDS,
+            ErrorCode::StalePriceUpdate
+        );
+        Ok(update)
+    }
+}
+
+/// Output amount, in raw units of the output mint, that `amount_in` raw units of the input mint
+/// are worth at the oracle prices.
+pub fn expected_amount_out(
+    amount_in: u64,
+    price_in: &PriceUpdate,
+    decimals_in: u8,
+    price_out: &PriceUpdate,
+    decimals_out: u8,
+) -> Result<u64> {
+    require!(price_in.price > 0 && price_out.price > 0, ErrorCode::InvalidPriceUpdate);
+
+    // amount_out = amount_in * price_in * 10^(expo_in - expo_out + decimals_out - decimals_in) / price_out
+    let scale = price_in.exponent as i64 - price_out.exponent as i64 + decimals_out as i64 - decimals_in as i64;
+    let pow10 = |exp: i64| -> Result<u128> {
+        let exp = u32::try_from(exp).map_err(|_| ErrorCode::ArithmeticOverflow)?;
+        Ok(10u128.checked_pow(exp).ok_or(ErrorCode::ArithmeticOverflow)?)
+    };
+
+    let mut numerator = (amount_in as u128) * (price_in.price as u128);
+    let mut denominator = price_out.price as u128;
+    if scale >= 0 {
+        numerator = numerator.checked_mul(pow10(scale)?).ok_or(ErrorCode::ArithmeticOverflow)?;
+    } else {
+        denominator = denominator.checked_mul(pow10(-scale)?).ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    Ok(u64::try_from(numerator / denominator).map_err(|_| ErrorCode::ArithmeticOverflow)?)
+}
+
+/// Requires `min_amount_out` to be at most `max_surplus_rate` basis points below `expected_amount_out`,
+/// so that a relayer cannot quote a loose minimum and capture the difference as surplus.
+pub fn check_min_amount_out(min_amount_out: u64, expected_amount_out: u64, max_surplus_rate: u16) -> Result<()> {
+    let floor = (expected_amount_out as u128)
+        .checked_mul(10000u128.saturating_sub(max_surplus_rate as u128))
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        / 10000;
+    require!(min_amount_out as u128 >= floor, ErrorCode::SwapMinAmountBelowOracle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(price: i64, exponent: i32) -> PriceUpdate {
+        PriceUpdate { feed_id: [0u8; 32], price, exponent, publish_time: 0 }
+    }
+
+    fn price_update_data(verification_level: &[u8], update: &PriceUpdate) -> Vec<u8> {
+        let mut data = hash(b"account:PriceUpdateV2").to_bytes()[..8].to_vec();
+        data.extend_from_slice(&[0u8; 32]);
+        data.extend_from_slice(verification_level);
+        data.extend_from_slice(&update.feed_id);
+        data.extend_from_slice(&update.price.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes()); // conf
+        data.extend_from_slice(&update.exponent.to_le_bytes());
+        data.extend_from_slice(&update.publish_time.to_le_bytes());
+        data.extend_from_slice(&[0u8; 24]); // prev_publish_time, ema_price, ema_conf
+        data.extend_from_slice(&0u64.to_le_bytes()); // posted_slot
+        data
+    }
+
+    #[test]
+    fn test_parse_fully_verified_update() {
+        let update = PriceUpdate { feed_id: [7u8; 32], price: 15_000_000_000, exponent: -8, publish_time: 1_700_000_000 };
+        assert_eq!(PriceUpdate::parse(&price_update_data(&[1], &update)).unwrap(), update);
+    }
+
+    #[test]
+    fn test_parse_rejects_partially_verified_update() {
+        let update = price(1, 0);
+        assert!(PriceUpdate::parse(&price_update_data(&[0, 3], &update)).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_discriminator() {
+        let mut data = price_update_data(&[1], &price(1, 0));
+        data[0] ^= 1;
+        assert!(PriceUpdate::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_expected_amount_out() {
+        // 2 SOL (9 decimals) at $150 into USDC (6 decimals) at $1 = 300 USDC
+        let sol = price(15_000_000_000, -8);
+        let usdc = price(100_000_000, -8);
+        assert_eq!(expected_amount_out(2_000_000_000, &sol, 9, &usdc, 6).unwrap(), 300_000_000);
+        // and back
+        assert_eq!(expected_amount_out(300_000_000, &usdc, 6, &sol, 9).unwrap(), 2_000_000_000);
+    }
+
+    #[test]
+    fn test_check_min_amount_out() {
+        // 1% below 300 USDC is the lowest acceptable minimum
+        assert!(check_min_amount_out(297_000_000, 300_000_000, 100).is_ok());
+        assert!(check_min_amount_out(296_999_999, 300_000_000, 100).is_err());
+        assert!(check_min_amount_out(0, 300_000_000, 10000).is_ok());
+    }
+}
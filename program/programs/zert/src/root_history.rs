@@ -0,0 +1,405 @@
+// Roots of a merkle tree can additionally be recorded in a RootHistoryAccount, a ring buffer of thousands
+// of (root, slot) entries followed by an open-addressing hash index over them, so proofs stay valid for
+// far more insertions than the tree account keeps and lookups do not scan the whole history.
+// The account is too large to be allocated through a CPI, so clients create it with the system program.
+use crate::{MerkleTreeAccount, RootHistoryAccount, ErrorCode};
+use anchor_lang::prelude::*;
+
+pub const ROOT_ENTRY_SIZE: usize = 32 + 8; // root, slot it was recorded at
+pub const INDEX_ENTRY_SIZE: usize = 4;     // position + 1 of an entry, 0 when the bucket is empty
+
+pub struct RootHistory;
+
+impl RootHistory {
+    /// Offset of the first entry in a root history account: discriminator + header.
+    pub const ENTRIES_OFFSET: usize = 8 + std::mem::size_of::<RootHistoryAccount>();
+
+    /// Account size needed to keep `capacity` roots. The index has two buckets per entry.
+    pub fn space(capacity: usize) -> usize {
+        Self::ENTRIES_OFFSET + capacity * (ROOT_ENTRY_SIZE + 2 * INDEX_ENTRY_SIZE)
+    }
+
+    /// Number of roots an account of `data_len` bytes can keep.
+    pub fn capacity(data_len: usize) -> usize {
+        data_len.saturating_sub(Self::ENTRIES_OFFSET) / (ROOT_ENTRY_SIZE + 2 * INDEX_ENTRY_SIZE)
+    }
+
+    /// Returns the root history attached to `tree_account`, if any, failing when it was not passed
+    /// or another account was passed in its place.
+    pub fn attached<'a, 'info>(
+        tree_account: &MerkleTreeAccount,
+        root_history_account: Option<&'a AccountLoader<'info, RootHistoryAccount>>,
+    ) -> Result<Option<&'a AccountLoader<'info, RootHistoryAccount>>> {
+        if tree_account.root_history_account == Pubkey::default() {
+            return Ok(None);
+        }
+
+        let root_history_account = root_history_account.ok_or(ErrorCode::MissingRootHistoryAccount)?;
+        require_keys_eq!(
+            root_history_account.key(),
+            tree_account.root_history_account,
+            ErrorCode::InvalidRootHistoryAccount
+        );
+        Ok(Some(root_history_account))
+    }
+
+    /// Checks that `root` is in the history and has not expired at slot `now`, see is_expired.
+    pub fn check_root(
+        root_history_account: &AccountLoader<RootHistoryAccount>,
+        root: &[u8; 32],
+        now: u64,
+        validity_slots: u64,
+    ) -> Result<()> {
+        let (capacity, head) = {
+            let header = root_history_account.load()?;
+            (header.capacity as usize, header.head as usize)
+        };
+
+        let info = root_history_account.to_account_info();
+        let data = info.try_borrow_data()?;
+        let (entries, index) = data[Self::ENTRIES_OFFSET..].split_at(capacity * ROOT_ENTRY_SIZE);
+        let position = Self::find(entries, index, root).ok_or(ErrorCode::UnknownRoot)?;
+        require!(
+            !Self::is_expired(entries, head, position, now, validity_slots),
+            ErrorCode::RootExpired
+        );
+        Ok(())
+    }
+
+    /// Records the current root of `tree_account` in its root history, if it has one.
+    pub fn record(
+        tree_account: &MerkleTreeAccount,
+        root_history_account: Option<&AccountLoader<RootHistoryAccount>>,
+    ) -> Result<()> {
+        if let Some(root_history_account) = Self::attached(tree_account, root_history_account)? {
+            Self::push(root_history_account, &tree_account.root, Clock::get()?.slot)?;
+        }
+        Ok(())
+    }
+
+    /// Records `root`, created at `slot`, evicting the oldest root once the history is full.
+    pub fn push(root_history_account: &AccountLoader<RootHistoryAccount>, root: &[u8; 32], slot: u64) -> Result<()> {
+        let (capacity, head, count) = {
+            let header = root_history_account.load()?;
+            (header.capacity as usize, header.head as usize, header.count as usize)
+        };
+
+        {
+            let info = root_history_account.to_account_info();
+            let mut data = info.try_borrow_mut_data()?;
+            let (entries, index) = data[Self::ENTRIES_OFFSET..].split_at_mut(capacity * ROOT_ENTRY_SIZE);
+            Self::insert(entries, index, head, count == capacity, root, slot);
+        }
+
+        let mut header = root_history_account.load_mut()?;
+        header.head = ((head + 1) % capacity) as u32;
+        header.count = (count + 1).min(capacity) as u32;
+        Ok(())
+    }
+
+    /// Position of `root` in packed `entries`, looked up through `index`.
+    pub fn find(entries: &[u8], index: &[u8], root: &[u8; 32]) -> Option<usize> {
+        let buckets = index.len() / INDEX_ENTRY_SIZE;
+        let mut bucket = Self::bucket(root, buckets);
+
+        loop {
+            let position = Self::index_get(index, bucket)?;
+            if Self::root_at(entries, position) == root {
+                return Some(position);
+            }
+            bucket = (bucket + 1) % buckets;
+        }
+    }
+
+    /// Writes (`root`, `slot`) at position `head` of `entries` and adds it to `index`.
+    /// When `full`, the entry at `head` is the oldest one and is removed from the index first.
+    pub fn insert(entries: &mut [u8], index: &mut [u8], head: usize, full: bool, root: &[u8; 32], slot: u64) {
+        if full {
+            Self::remove(entries, index, head);
+        }
+
+        let entry = &mut entries[head * ROOT_ENTRY_SIZE..(head + 1) * ROOT_ENTRY_SIZE];
+        entry[..32].copy_from_slice(root);
+        entry[32..].copy_from_slice(&slot.to_le_bytes());
+
+        // The index has twice as many buckets as entries, so a free bucket is always found
+        let buckets = index.len() / INDEX_ENTRY_SIZE;
+        let mut bucket = Self::bucket(root, buckets);
+        while Self::index_get(index, bucket).is_some() {
+            bucket = (bucket + 1) % buckets;
+        }
+        Self::index_set(index, bucket, Some(head));
+    }
+
+    /// Fills empty `entries` with `roots`, oldest first, all recorded at `slot`, and returns the resulting
+    /// head and count. Seeding as many roots as the capacity wraps the head back to the first position.
+    pub fn seed(
+        entries: &mut [u8],
+        index: &mut [u8],
+        roots: impl IntoIterator<Item = [u8; 32]>,
+        slot: u64,
+    ) -> (usize, usize) {
+        let capacity = entries.len() / ROOT_ENTRY_SIZE;
+        let mut count = 0;
+        for root in roots {
+            Self::insert(entries, index, count, false, &root, slot);
+            count += 1;
+        }
+        (count % capacity, count)
+    }
+
+    /// Whether the root at `position` expired at slot `now`. The most recent root never expires, older ones
+    /// stay valid for `validity_slots` after the root that replaced them was recorded, so a proof always has
+    /// the full window however long its root was current. A `validity_slots` of 0 disables the expiry.
+    pub fn is_expired(entries: &[u8], head: usize, position: usize, now: u64, validity_slots: u64) -> bool {
+        let capacity = entries.len() / ROOT_ENTRY_SIZE;
+        let next = (position + 1) % capacity;
+        if validity_slots == 0 || next == head {
+            return false;
+        }
+        now.saturating_sub(Self::slot_at(entries, next)) > validity_slots
+    }
+
+    /// Slot the entry at `position` was recorded at.
+    pub fn slot_at(entries: &[u8], position: usize) -> u64 {
+        let offset = position * ROOT_ENTRY_SIZE + 32;
+        u64::from_le_bytes(entries[offset..offset + 8].try_into().unwrap())
+    }
+
+    /// Removes the entry at `position` from `index`, shifting back the entries probed past it
+    /// so that lookups never stop at the freed bucket too early.
+    fn remove(entries: &[u8], index: &mut [u8], position: usize) {
+        let buckets = index.len() / INDEX_ENTRY_SIZE;
+        let mut free = Self::bucket(Self::root_at(entries, position), buckets);
+        while Self::index_get(index, free) != Some(position) {
+            free = (free + 1) % buckets;
+        }
+
+        let mut bucket = free;
+        loop {
+            bucket = (bucket + 1) % buckets;
+            let Some(moved) = Self::index_get(index, bucket) else {
+                break;
+            };
+
+            // Entries whose home bucket lies cyclically in (free, bucket] are still reachable
+            let home = Self::bucket(Self::root_at(entries, moved), buckets);
+            let reachable = if free <= bucket {
+                free < home && home <= bucket
+            } else {
+                free < home || home <= bucket
+            };
+            if !reachable {
+                Self::index_set(index, free, Some(moved));
+                free = bucket;
+            }
+        }
+        Self::index_set(index, free, None);
+    }
+
+    /// Home bucket of `root`. Roots are big-endian field elements, so the last bytes are uniformly distributed.
+    fn bucket(root: &[u8], buckets: usize) -> usize {
+        (u64::from_le_bytes(root[24..32].try_into().unwrap()) % buckets as u64) as usize
+    }
+
+    fn root_at(entries: &[u8], position: usize) -> &[u8] {
+        &entries[position * ROOT_ENTRY_SIZE..position * ROOT_ENTRY_SIZE + 32]
+    }
+
+    fn index_get(index: &[u8], bucket: usize) -> Option<usize> {
+        let offset = bucket * INDEX_ENTRY_SIZE;
+        let value = u32::from_le_bytes(index[offset..offset + INDEX_ENTRY_SIZE].try_into().unwrap());
+        (value as usize).checked_sub(1)
+    }
+
+    fn index_set(index: &mut [u8], bucket: usize, position: Option<usize>) {
+        let offset = bucket * INDEX_ENTRY_SIZE;
+        let value = position.map_or(0, |position| position as u32 + 1);
+        index[offset..offset + INDEX_ENTRY_SIZE].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct History {
+        entries: Vec<u8>,
+        index: Vec<u8>,
+        capacity: usize,
+        head: usize,
+        count: usize,
+    }
+
+    impl History {
+        fn new(capacity: usize) -> Self {
+            Self {
+                entries: vec![0u8; capacity * ROOT_ENTRY_SIZE],
+                index: vec![0u8; 2 * capacity * INDEX_ENTRY_SIZE],
+                capacity,
+                head: 0,
+                count: 0,
+            }
+        }
+
+        fn push(&mut self, root: &[u8; 32], slot: u64) {
+            let full = self.count == self.capacity;
+            RootHistory::insert(&mut self.entries, &mut self.index, self.head, full, root, slot);
+            self.head = (self.head + 1) % self.capacity;
+            self.count = (self.count + 1).min(self.capacity);
+        }
+
+        fn seeded(capacity: usize, roots: impl IntoIterator<Item = [u8; 32]>, slot: u64) -> Self {
+            let mut history = Self::new(capacity);
+            (history.head, history.count) = RootHistory::seed(&mut history.entries, &mut history.index, roots, slot);
+            history
+        }
+
+        fn slot(&self, root: &[u8; 32]) -> Option<u64> {
+            RootHistory::find(&self.entries, &self.index, root)
+                .map(|position| RootHistory::slot_at(&self.entries, position))
+        }
+
+        fn is_expired(&self, root: &[u8; 32], now: u64, validity_slots: u64) -> bool {
+            let position = RootHistory::find(&self.entries, &self.index, root).unwrap();
+            RootHistory::is_expired(&self.entries, self.head, position, now, validity_slots)
+        }
+    }
+
+    // Roots sharing `home` share their bucket, to exercise collisions
+    fn root(id: u16, home: u64) -> [u8; 32] {
+        let mut root = [0u8; 32];
+        root[..2].copy_from_slice(&id.to_le_bytes());
+        root[24..].copy_from_slice(&home.to_le_bytes());
+        root
+    }
+
+    #[test]
+    fn test_space_and_capacity() {
+        assert_eq!(RootHistory::capacity(RootHistory::space(4096)), 4096);
+        assert_eq!(RootHistory::capacity(RootHistory::space(4096) - 1), 4095);
+        assert_eq!(RootHistory::capacity(0), 0);
+    }
+
+    #[test]
+    fn test_find_empty_history() {
+        let history = History::new(4);
+        assert_eq!(history.slot(&root(1, 1)), None);
+    }
+
+    #[test]
+    fn test_find_records_slot() {
+        let mut history = History::new(4);
+        history.push(&root(1, 1), 10);
+        history.push(&root(2, 1), 11);
+        history.push(&root(3, 2), 12);
+
+        assert_eq!(history.slot(&root(1, 1)), Some(10));
+        assert_eq!(history.slot(&root(2, 1)), Some(11));
+        assert_eq!(history.slot(&root(3, 2)), Some(12));
+        assert_eq!(history.slot(&root(4, 1)), None);
+    }
+
+    #[test]
+    fn test_oldest_root_is_evicted() {
+        let mut history = History::new(4);
+        for i in 0..6u16 {
+            history.push(&root(i, i as u64), i as u64);
+        }
+
+        assert_eq!(history.slot(&root(0, 0)), None);
+        assert_eq!(history.slot(&root(1, 1)), None);
+        for i in 2..6u16 {
+            assert_eq!(history.slot(&root(i, i as u64)), Some(i as u64));
+        }
+    }
+
+    #[test]
+    fn test_matches_ring_buffer_with_collisions() {
+        let capacity = 5;
+        let mut history = History::new(capacity);
+
+        for i in 0..300u16 {
+            // Few distinct home buckets, including the last one so probes wrap around
+            history.push(&root(i, (i as u64 * 7) % 3 + 7), i as u64);
+
+            for j in 0..=i {
+                let expected = (i - j < capacity as u16).then_some(j as u64);
+                assert_eq!(history.slot(&root(j, (j as u64 * 7) % 3 + 7)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_latest_root_never_expires() {
+        let mut history = History::new(4);
+        history.push(&root(1, 1), 100);
+
+        assert!(!history.is_expired(&root(1, 1), 1_000_000, 50));
+    }
+
+    #[test]
+    fn test_root_expires_after_being_replaced() {
+        let mut history = History::new(4);
+        history.push(&root(1, 1), 100);
+        history.push(&root(2, 2), 1_000);
+        history.push(&root(3, 3), 1_010);
+
+        // The window of a root starts when the next one is recorded
+        assert!(!history.is_expired(&root(1, 1), 1_050, 50));
+        assert!(history.is_expired(&root(1, 1), 1_051, 50));
+        assert!(!history.is_expired(&root(2, 2), 1_060, 50));
+        assert!(history.is_expired(&root(2, 2), 1_061, 50));
+        assert!(!history.is_expired(&root(3, 3), 1_000_000, 50));
+    }
+
+    #[test]
+    fn test_expiry_across_wrap_around() {
+        let mut history = History::new(4);
+        for i in 0..6u16 {
+            history.push(&root(i, i as u64), 100 * i as u64);
+        }
+
+        // Position 3 holds root 3 and is followed by root 4 at position 0
+        assert!(!history.is_expired(&root(3, 3), 410, 10));
+        assert!(history.is_expired(&root(3, 3), 411, 10));
+        assert!(!history.is_expired(&root(5, 5), 1_000_000, 10));
+    }
+
+    #[test]
+    fn test_seed_partial_history() {
+        let mut history = History::seeded(4, (0..2u16).map(|i| root(i, i as u64)), 50);
+        assert_eq!((history.head, history.count), (2, 2));
+
+        history.push(&root(2, 2), 60);
+        assert_eq!(history.slot(&root(0, 0)), Some(50));
+        assert_eq!(history.slot(&root(2, 2)), Some(60));
+    }
+
+    #[test]
+    fn test_seed_full_history_then_push() {
+        // A tree whose in-account history is full, migrated into an account of exactly that capacity
+        let mut history = History::seeded(4, (0..4u16).map(|i| root(i, i as u64)), 50);
+        assert_eq!((history.head, history.count), (0, 4));
+        assert!(history.is_expired(&root(0, 0), 1_000, 10));
+        assert!(!history.is_expired(&root(3, 3), 1_000, 10));
+
+        history.push(&root(4, 4), 60);
+        assert_eq!(history.slot(&root(0, 0)), None);
+        for i in 1..5u16 {
+            assert!(history.slot(&root(i, i as u64)).is_some());
+        }
+        assert!(!history.is_expired(&root(3, 3), 70, 10));
+        assert!(history.is_expired(&root(3, 3), 71, 10));
+        assert!(!history.is_expired(&root(4, 4), 1_000_000, 10));
+    }
+
+    #[test]
+    fn test_zero_validity_disables_expiry() {
+        let mut history = History::new(4);
+        history.push(&root(1, 1), 100);
+        history.push(&root(2, 2), 200);
+
+        assert!(!history.is_expired(&root(1, 1), u64::MAX, 0));
+    }
+}
@@ -1,9 +1,38 @@
 use anchor_lang::prelude::*;
+use crate::groth16::Groth16Verifyingkey;
+use crate::oracle::PYTH_RECEIVER_PROGRAM_ID;
+use crate::utils::VERIFYING_KEY;
 
 // Constants
-pub const MERKLE_TREE_HEIGHT: u8 = 26;
+pub const MERKLE_TREE_HEIGHT: u8 = 26; // depth of the genesis circuit, and the default tree height
 pub const ROOT_HISTORY_SIZE: usize = 100;
 
+// Deepest tree the hasher provides zero bytes for
+pub const MAX_MERKLE_TREE_HEIGHT: u8 = 32;
+
+// Largest account `init` can create, accounts created through a CPI grow by at most this many bytes
+pub const MAX_INIT_ACCOUNT_SPACE: usize = anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+
+// Number of public inputs of the transaction circuit
+pub const NR_PUBLIC_INPUTS: usize = 10;
+
+// Maximum number of programs swaps can be routed through
+pub const MAX_SWAP_PROGRAMS: usize = 16;
+
+// Layout versions of the migratable accounts, legacy accounts predate versioning
+pub const GLOBAL_CONFIG_VERSION: u8 = 1;
+pub const MERKLE_TREE_VERSION: u8 = 1;
+
+// Slots between a relayer's unstake request and the withdrawal of the lamports, ~1 day at 400ms slots
+pub const RELAYER_UNSTAKE_COOLDOWN_SLOTS: u64 = 216_000;
+
+// Flags of GlobalConfig::paused, each one disables an instruction
+pub const PAUSE_DEPOSIT: u8 = 1 << 0;
+pub const PAUSE_WITHDRAW: u8 = 1 << 1;
+pub const PAUSE_SWAP: u8 = 1 << 2;
+pub const PAUSE_TRANSFER: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSIT | PAUSE_WITHDRAW | PAUSE_SWAP | PAUSE_TRANSFER;
+
 #[account]
 pub struct TreeTokenAccount {
     pub authority: Pubkey,
@@ -12,33 +41,221 @@ pub struct TreeTokenAccount {
 
 #[account]
 pub struct GlobalConfig {
+    pub version: u8,              // GLOBAL_CONFIG_VERSION, first so that future migrations can read it
     pub authority: Pubkey,
+    pub pending_authority: Pubkey, // proposed authority, Pubkey::default() when none
     pub deposit_fee_rate: u16,    // basis points (0-10000, where 10000 = 100%)
     pub withdrawal_fee_rate: u16, // basis points (0-10000, where 10000 = 100%)
+    pub swap_fee_rate: u16,       // basis points of the swapped input amount, paid upfront in the input token
+    pub max_swap_surplus_rate: u16, // basis points of min_amount_out the relayer may capture in the output token
     pub fee_error_margin: u16,    // basis points (0-10000, where 10000 = 100%)
+    pub active_tree_id: u64,      // id of the merkle tree new commitments are appended to
+    pub guardian: Pubkey,         // can pause but not unpause, Pubkey::default() when unset
+    pub paused: u8,               // bitmask of PAUSE_* flags
+    pub outflow_window_slots: u64, // length of the per-mint outflow rate limit window
+    pub min_relayer_stake: u64,    // lamports a relayer must have staked to submit transactions
+    pub oracle_program: Pubkey,    // owner of the price update accounts checked by swap
+    pub root_validity_slots: u64,  // slots a replaced root stays valid in the root history, 0 = until evicted
+    pub bump: u8,
+}
+
+impl GlobalConfig {
+    /// A global config with the default settings of a new pool.
+    pub fn new(authority: Pubkey, bump: u8) -> Self {
+        Self {
+            version: GLOBAL_CONFIG_VERSION,
+            authority,
+            pending_authority: Pubkey::default(),
+            deposit_fee_rate: 0, // 0% - Free deposits
+            withdrawal_fee_rate: 25, // 0.25% (25 basis points)
+            swap_fee_rate: 25, // 0.25% (25 basis points)
+            max_swap_surplus_rate: 100, // 1% (100 basis points)
+            fee_error_margin: 500, // 5% (500 basis points)
+            active_tree_id: 0,
+            guardian: Pubkey::default(),
+            paused: 0,
+            outflow_window_slots: 9_000, // ~1 hour at 400ms slots
+            min_relayer_stake: 0,
+            oracle_program: PYTH_RECEIVER_PROGRAM_ID,
+            root_validity_slots: 9_000, // ~1 hour at 400ms slots
+            bump,
+        }
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+}
+
+#[account]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    pub max_deposit_amount: u64, // raw token units
+    pub min_deposit_amount: u64, // raw token units
+    pub enabled: bool,           // deposits and swaps into this mint are accepted
+    pub max_outflow_per_window: u64, // raw token units leaving the reserve per rolling window, 0 = unlimited
+    pub window_start_slot: u64,      // first slot of the current outflow window
+    pub current_window_outflow: u64,
+    pub previous_window_outflow: u64,
+    pub price_feed_id: [u8; 32],     // Pyth feed id of the mint's USD price, zeroes when none
+    pub min_transfer_fee: u64,       // raw token units a shielded transfer must pay its relayer
+    pub account_creation_fee: u64,   // raw token units a withdrawal creating the recipient's token account adds to its fee
+    pub bump: u8,
+}
+
+#[account]
+pub struct SwapProgramRegistry {
+    pub programs: Vec<Pubkey>, // aggregator and AMM adapter programs swap may invoke, at most MAX_SWAP_PROGRAMS
+    pub bump: u8,
+}
+
+impl SwapProgramRegistry {
+    pub const SPACE: usize = 8 + 4 + 32 * MAX_SWAP_PROGRAMS + 1;
+
+    pub fn contains(&self, program_id: &Pubkey) -> bool {
+        self.programs.contains(program_id)
+    }
+}
+
+#[account]
+pub struct RelayerAccount {
+    pub relayer: Pubkey,
+    pub active: bool, // cleared by the authority to delist a misbehaving relayer
+    pub stake: u64,   // lamports held by this account on top of its rent
+    pub pending_unstake: u64,         // lamports requested for withdrawal, no longer counted in stake but still slashable
+    pub unstake_requested_slot: u64,  // slot of the last unstake request, the cooldown starts from it
     pub bump: u8,
 }
 
+impl RelayerAccount {
+    pub fn is_eligible(&self, min_stake: u64) -> bool {
+        self.active && self.stake >= min_stake
+    }
+}
+
 #[account]
 pub struct NullifierAccount {
     /// This account's existence indicates that the nullifier has been used.
     /// No fields needed other than bump for PDA verification.
+    /// Legacy: new nullifiers are stored in NullifierShard accounts.
     pub bump: u8,
 }
 
+#[account(zero_copy)]
+pub struct NullifierShard {
+    pub count: u32,   // number of sorted nullifiers stored after this header
+    pub shard_id: u8, // last byte of every nullifier stored in this shard
+    pub bump: u8,
+    // The pub _padding: [u8; 2] is needed because of the #[account(zero_copy)] attribute.
+    pub _padding: [u8; 2],
+}
+
 #[account(zero_copy)]
 pub struct MerkleTreeAccount {
-    pub authority: Pubkey,
+    pub root_history_account: Pubkey, // RootHistoryAccount recording this tree's roots, Pubkey::default() when none
     pub next_index: u64,
-    pub subtrees: [[u8; 32]; MERKLE_TREE_HEIGHT as usize],
+    pub tree_id: u64,
     pub root: [u8; 32],
-    pub root_history: [[u8; 32]; ROOT_HISTORY_SIZE],
     pub root_index: u64,
-    pub max_deposit_amount: u64,
     pub height: u8,
     pub root_history_size: u8,
     pub bump: u8,
-    // The pub _padding: [u8; 5] is needed because of the #[account(zero_copy)] attribute.
-    pub _padding: [u8; 5],
+    pub retired: u8, // 1 once the admin stops accepting proofs against this tree's roots
+    pub version: u8, // MERKLE_TREE_VERSION
+    // The pub _padding: [u8; 3] is needed because of the #[account(zero_copy)] attribute.
+    pub _padding: [u8; 3],
+    // Followed by `height` subtrees, `root_history_size` roots and the slot each root was recorded at, see MerkleTreeData
 }
 
+impl MerkleTreeAccount {
+    /// Offset of the first subtree in a tree account: discriminator + header.
+    pub const SUBTREES_OFFSET: usize = 8 + std::mem::size_of::<MerkleTreeAccount>();
+
+    /// Account size of a tree of `height` keeping `root_history_size` roots and their slots.
+    pub fn space(height: u8, root_history_size: u8) -> usize {
+        Self::SUBTREES_OFFSET + (height as usize + root_history_size as usize) * 32 + root_history_size as usize * 8
+    }
+
+    /// Account size of a tree, failing when the tree cannot be created, see utils::check_tree_shape.
+    /// Used as the `space` of `init` so that the shape is checked before the account is created.
+    pub fn checked_space(height: u8, root_history_size: u8) -> Result<usize> {
+        crate::utils::check_tree_shape(height, root_history_size)?;
+        Ok(Self::space(height, root_history_size))
+    }
+
+    /// Roots kept in the tree's own root history, `data` being the whole account data.
+    pub fn root_history<'a>(&self, data: &'a [u8]) -> &'a [[u8; 32]] {
+        let start = Self::SUBTREES_OFFSET + self.height as usize * 32;
+        bytemuck::cast_slice(&data[start..start + self.root_history_size as usize * 32])
+    }
+
+    /// Slots the roots of the tree's own root history were recorded at, `data` being the whole account data.
+    pub fn root_slots<'a>(&self, data: &'a [u8]) -> &'a [u64] {
+        let start = Self::SUBTREES_OFFSET + (self.height as usize + self.root_history_size as usize) * 32;
+        bytemuck::cast_slice(&data[start..start + self.root_history_size as usize * 8])
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.next_index >= 1u64 << self.height
+    }
+}
+
+#[account(zero_copy)]
+pub struct RootHistoryAccount {
+    pub tree: Pubkey,  // merkle tree whose roots are recorded
+    pub capacity: u32, // number of roots kept, the entries and their index follow this header
+    pub head: u32,     // position the next root is written to
+    pub count: u32,    // number of roots stored, at most capacity
+    // The pub _padding: [u8; 4] is needed because of the #[account(zero_copy)] attribute.
+    pub _padding: [u8; 4],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VerifyingKeyStatus {
+    Pending, // registered, vk_ic may still be written
+    Active,  // proofs are verified against this key
+    Retired, // proofs against this key are rejected
+}
+
+#[account]
+pub struct VerifyingKeyAccount {
+    pub version: u32,
+    pub status: VerifyingKeyStatus,
+    pub tree_height: u8, // depth of the merkle proofs checked by the circuit, must match the tree height
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamma_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: [[u8; 64]; NR_PUBLIC_INPUTS + 1],
+    pub bump: u8,
+}
+
+impl VerifyingKeyAccount {
+    /// Verifying key version 0, the genesis circuit.
+    pub fn genesis(bump: u8) -> Self {
+        let mut vk_ic = [[0u8; 64]; NR_PUBLIC_INPUTS + 1];
+        vk_ic.copy_from_slice(VERIFYING_KEY.vk_ic);
+        Self {
+            version: 0,
+            status: VerifyingKeyStatus::Active,
+            tree_height: MERKLE_TREE_HEIGHT,
+            vk_alpha_g1: VERIFYING_KEY.vk_alpha_g1,
+            vk_beta_g2: VERIFYING_KEY.vk_beta_g2,
+            vk_gamma_g2: VERIFYING_KEY.vk_gamma_g2,
+            vk_delta_g2: VERIFYING_KEY.vk_delta_g2,
+            vk_ic,
+            bump,
+        }
+    }
+
+    pub fn groth16_verifying_key(&self) -> Groth16Verifyingkey<'_> {
+        Groth16Verifyingkey {
+            nr_pubinputs: NR_PUBLIC_INPUTS,
+            vk_alpha_g1: self.vk_alpha_g1,
+            vk_beta_g2: self.vk_beta_g2,
+            vk_gamma_g2: self.vk_gamma_g2,
+            vk_delta_g2: self.vk_delta_g2,
+            vk_ic: &self.vk_ic,
+        }
+    }
+}
@@ -1,13 +1,84 @@
 use anchor_lang::prelude::*;
+use crate::state::MerkleTreeAccount;
+
+// Version of the event layouts below, bumped on every schema change. Events without it predate versioning.
+pub const EVENT_VERSION: u8 = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransactionKind {
+    Deposit,
+    Withdraw,
+    Swap,
+    Transfer,
+    DepositDual,
+    WithdrawDual,
+}
 
 #[event]
 pub struct CommitmentData {
-    pub index: u64,
+    pub version: u8,
+    pub kind: TransactionKind,
+    pub tree_id: u64,
+    pub index: u64,            // leaf index of commitment0, commitment1 is at index + 1
+    pub root: [u8; 32],        // tree root after both commitments were inserted
+    pub root_index: u64,       // position of root in the tree's root history
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub input_nullifiers: [[u8; 32]; 2],
+    pub slot: u64,
     pub commitment0: [u8; 32],
     pub commitment1: [u8; 32],
     pub encrypted_output: Vec<u8>,
 }
 
+#[event]
+pub struct NullifiersSpent {
+    pub version: u8,
+    pub kind: TransactionKind,
+    pub nullifiers: [[u8; 32]; 2],
+    pub slot: u64,
+}
+
+impl CommitmentData {
+    /// Event for the output commitments of `proof`, called right after they were appended to `tree_account` at `index`.
+    pub fn new(
+        kind: TransactionKind,
+        tree_account: &MerkleTreeAccount,
+        index: u64,
+        proof: &Proof,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        encrypted_output: Vec<u8>,
+    ) -> Result<Self> {
+        Ok(Self {
+            version: EVENT_VERSION,
+            kind,
+            tree_id: tree_account.tree_id,
+            index,
+            root: tree_account.root,
+            root_index: tree_account.root_index,
+            mint_a,
+            mint_b,
+            input_nullifiers: proof.input_nullifiers,
+            slot: Clock::get()?.slot,
+            commitment0: proof.output_commitments[0],
+            commitment1: proof.output_commitments[1],
+            encrypted_output,
+        })
+    }
+}
+
+impl NullifiersSpent {
+    pub fn new(kind: TransactionKind, proof: &Proof) -> Result<Self> {
+        Ok(Self {
+            version: EVENT_VERSION,
+            kind,
+            nullifiers: proof.input_nullifiers,
+            slot: Clock::get()?.slot,
+        })
+    }
+}
+
 // all public inputs needs to be in big endian format
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Proof {
@@ -38,6 +109,27 @@ pub struct ExtDataMinified {
     pub fee: u64,
 }
 
+// Dual-token transactions move ext_amount0 of mint A and ext_amount1 of mint B,
+// each leg paying its own fee in its own mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DualExtDataMinified {
+    pub ext_amount0: i64,
+    pub ext_amount1: i64,
+    pub fee0: u64,
+    pub fee1: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DualExtData {
+    pub recipient: Pubkey,
+    pub ext_amount0: i64,
+    pub ext_amount1: i64,
+    pub fee0: u64,
+    pub fee1: u64,
+    pub fee_recipient0: Pubkey,
+    pub fee_recipient1: Pubkey,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct SwapExtDataMinified {
     pub ext_amount: i64,
@@ -54,7 +146,7 @@ pub struct SwapExtData { // TODO ADD JUP DATA
 }
 
 impl ExtData {
-    pub fn from_minified<'info>(
+    pub fn from_minified(
         recipient: &Pubkey,
         fee_recipient: &Pubkey,
         minified: ExtDataMinified,
@@ -68,8 +160,27 @@ impl ExtData {
     }
 }
 
+impl DualExtData {
+    pub fn from_minified(
+        recipient: &Pubkey,
+        fee_recipient0: &Pubkey,
+        fee_recipient1: &Pubkey,
+        minified: DualExtDataMinified,
+    ) -> Self {
+        Self {
+            recipient: *recipient,
+            ext_amount0: minified.ext_amount0,
+            ext_amount1: minified.ext_amount1,
+            fee0: minified.fee0,
+            fee1: minified.fee1,
+            fee_recipient0: *fee_recipient0,
+            fee_recipient1: *fee_recipient1,
+        }
+    }
+}
+
 impl SwapExtData {
-    pub fn from_minified<'info>(
+    pub fn from_minified(
         fee_recipient: &Pubkey, 
         minified: SwapExtDataMinified,
     ) -> Self {
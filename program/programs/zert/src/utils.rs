@@ -1,4 +1,7 @@
-use crate::Proof;
+use crate::{DualExtData, Proof};
+use crate::merkle_tree::{MerkleTree, MerkleTreeData};
+use crate::root_history::RootHistory;
+use crate::state::{MerkleTreeAccount, MintConfig, RelayerAccount, RootHistoryAccount, MAX_INIT_ACCOUNT_SPACE, MAX_MERKLE_TREE_HEIGHT, RELAYER_UNSTAKE_COOLDOWN_SLOTS};
 use crate::groth16::{Groth16Verifier, Groth16Verifyingkey};
 use crate::ErrorCode;
 use ark_bn254;
@@ -7,10 +10,17 @@ use std::ops::Neg;
 use ark_bn254::Fr;
 use ark_ff::PrimeField;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_lang::solana_program::hash::hash;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::TokenAccount;
 
 type G1 = ark_bn254::g1::G1Affine;
 
+/// Verifying key of the first circuit release, registered as version 0 by initialize.
+/// Later circuits are registered on chain through register_verifying_key.
 pub const VERIFYING_KEY: Groth16Verifyingkey = Groth16Verifyingkey {
 	nr_pubinputs: 10,
 
@@ -117,8 +127,9 @@ pub fn check_public_amount(ext_amount: i64, fee: u64, public_amount_bytes: [u8;
         Fr::from(abs_ext_amount as u64)
     };
 
-    // return false if the deposit amount is barely enough to cover the fee
-    if ext_amount >= 0 && ext_amount_fr <= fee_fr {
+    // return false if the deposit amount is barely enough to cover the fee.
+    // A zero ext_amount is a private transfer whose fee is paid from the shielded value.
+    if ext_amount > 0 && ext_amount_fr <= fee_fr {
         return false;
     }
 
@@ -221,7 +232,7 @@ pub fn validate_fee(
     Ok(())
 }
 
-pub fn verify_proof(proof: Proof, verifying_key: Groth16Verifyingkey, mint_address_a: Pubkey, mint_address_b: Pubkey) -> bool {
+pub fn verify_proof(proof: Proof, verifying_key: Groth16Verifyingkey<'_>, mint_address_a: Pubkey, mint_address_b: Pubkey) -> bool {
     let mut public_inputs_vec: [[u8; 32]; 10] = [[0u8; 32]; 10];
     public_inputs_vec[0] = proof.root;
     public_inputs_vec[1] = proof.public_amount0;
@@ -363,6 +374,499 @@ pub fn calculate_swap_ext_data_hash(
     Ok(calculated_ext_data_hash)
 }
 
+/**
+ * Calculate dual-token ExtData hash with encrypted outputs included
+ * This matches the client-side calculation for hash verification
+ */
+pub fn calculate_dual_ext_data_hash(
+    ext_data: &DualExtData,
+    encrypted_output: &[u8],
+    mint_address_a: Pubkey,
+    mint_address_b: Pubkey,
+) -> Result<[u8; 32]> {
+    #[derive(AnchorSerialize)]
+    struct CompleteDualExtData {
+        pub recipient: Pubkey,
+        pub ext_amount0: i64,
+        pub ext_amount1: i64,
+        pub encrypted_output: Vec<u8>,
+        pub fee0: u64,
+        pub fee1: u64,
+        pub fee_recipient0: Pubkey,
+        pub fee_recipient1: Pubkey,
+        pub mint_address_a: Pubkey,
+        pub mint_address_b: Pubkey,
+    }
+
+    let complete_dual_ext_data = CompleteDualExtData {
+        recipient: ext_data.recipient,
+        ext_amount0: ext_data.ext_amount0,
+        ext_amount1: ext_data.ext_amount1,
+        encrypted_output: encrypted_output.to_vec(),
+        fee0: ext_data.fee0,
+        fee1: ext_data.fee1,
+        fee_recipient0: ext_data.fee_recipient0,
+        fee_recipient1: ext_data.fee_recipient1,
+        mint_address_a,
+        mint_address_b,
+    };
+
+    let mut serialized_ext_data = Vec::new();
+    complete_dual_ext_data.serialize(&mut serialized_ext_data)?;
+    let calculated_ext_data_hash = hash(&serialized_ext_data).to_bytes();
+
+    Ok(calculated_ext_data_hash)
+}
+
+/**
+ * Checks that the proof root is known to the tree the proof was generated against.
+ *
+ * Proofs reference the active tree by default. After a rollover, notes living in an older tree
+ * are spent by passing that tree as `root_tree_account`, which is accepted as long as it is not retired.
+ * Once a root history account is attached to a tree, its roots are looked up there, otherwise in the
+ * tree's own root history. Either way, roots expire `root_validity_slots` after being replaced.
+ * Trees can have different heights, so the circuit of the proof must match the height of the tree the root belongs to.
+ *
+ * @param active_tree The active tree, already loaded by the caller
+ * @param active_tree_key Address of the active tree
+ * @param root_history_account Root history of the active tree, required once one is attached
+ * @param root_tree_account Optional tree the proof root belongs to
+ * @param root_tree_history_account Root history of `root_tree_account`, required once one is attached to it
+ * @param root The root used by the proof
+ * @param root_validity_slots GlobalConfig::root_validity_slots
+ * @param verifying_key_height Depth of the circuit of the verifying key the proof is checked against
+ * @return Ok(()) if the root is known, Err(ErrorCode) otherwise
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn check_known_root(
+    active_tree: &MerkleTreeData,
+    active_tree_key: Pubkey,
+    root_history_account: Option<&AccountLoader<RootHistoryAccount>>,
+    root_tree_account: Option<&AccountLoader<MerkleTreeAccount>>,
+    root_tree_history_account: Option<&AccountLoader<RootHistoryAccount>>,
+    root: [u8; 32],
+    root_validity_slots: u64,
+    verifying_key_height: u8,
+) -> Result<()> {
+    let now = Clock::get()?.slot;
+    match root_tree_account {
+        Some(root_tree_account) if root_tree_account.key() != active_tree_key => {
+            let root_tree = root_tree_account.load()?;
+            require!(root_tree.retired == 0, ErrorCode::TreeRetired);
+            require!(root_tree.height == verifying_key_height, ErrorCode::TreeHeightMismatch);
+            match RootHistory::attached(&root_tree, root_tree_history_account)? {
+                Some(root_tree_history_account) => {
+                    RootHistory::check_root(root_tree_history_account, &root, now, root_validity_slots)?;
+                }
+                None => {
+                    let root_tree_info = root_tree_account.to_account_info();
+                    let root_tree_data = root_tree_info.try_borrow_data()?;
+                    MerkleTree::check_root(
+                        &root_tree,
+                        root_tree.root_history(&root_tree_data),
+                        root_tree.root_slots(&root_tree_data),
+                        root,
+                        now,
+                        root_validity_slots,
+                    )?;
+                }
+            }
+        }
+        _ => {
+            require!(active_tree.height == verifying_key_height, ErrorCode::TreeHeightMismatch);
+            match RootHistory::attached(active_tree, root_history_account)? {
+                Some(root_history_account) => {
+                    RootHistory::check_root(root_history_account, &root, now, root_validity_slots)?;
+                }
+                None => {
+                    MerkleTree::check_root(
+                        active_tree,
+                        active_tree.root_history,
+                        active_tree.root_slots,
+                        root,
+                        now,
+                        root_validity_slots,
+                    )?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Reads the config of a mint from its mint config PDA.
+ *
+ * @param mint_config The mint config PDA of the mint, empty when the mint was never registered
+ * @return The mint config, Err(ErrorCode::UnsupportedMintAddress) if the mint is not registered
+ */
+pub fn load_mint_config(mint_config: &AccountInfo) -> Result<MintConfig> {
+    require!(!mint_config.data_is_empty(), ErrorCode::UnsupportedMintAddress);
+    require_keys_eq!(*mint_config.owner, crate::ID, anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+    MintConfig::try_deserialize(&mut &mint_config.try_borrow_data()?[..])
+}
+
+/**
+ * Checks that a deposit of `amount` into `mint_config`'s mint is allowed.
+ *
+ * @param mint_config The config of the deposited mint
+ * @param amount The amount entering the pool, in raw token units
+ * @return Ok(()) if the mint is enabled and the amount is within its limits
+ */
+pub fn check_deposit_limits(mint_config: &MintConfig, amount: u64) -> Result<()> {
+    require!(mint_config.enabled, ErrorCode::MintDisabled);
+    require!(amount >= mint_config.min_deposit_amount, ErrorCode::DepositBelowMinimum);
+    require!(amount <= mint_config.max_deposit_amount, ErrorCode::DepositLimitExceeded);
+    Ok(())
+}
+
+/**
+ * Checks that a shielded transfer in `mint_config`'s mint is allowed.
+ *
+ * A transfer moves no tokens in or out of the pool, its only outflow is the relayer fee paid from
+ * the shielded inputs. With no public amount to apply a fee rate to, the fee is checked against
+ * the flat minimum configured for the mint.
+ *
+ * @param mint_config The config of the transferred mint
+ * @param ext_amount The external amount, must be zero
+ * @param fee The fee paid to the relayer
+ * @return Ok(()) if the mint is enabled, ext_amount is zero and the fee is at least min_transfer_fee
+ */
+pub fn check_transfer(mint_config: &MintConfig, ext_amount: i64, fee: u64) -> Result<()> {
+    require!(mint_config.enabled, ErrorCode::MintDisabled);
+    require!(ext_amount == 0, ErrorCode::InvalidExtAmount);
+    require!(fee >= mint_config.min_transfer_fee, ErrorCode::TransferFeeBelowMinimum);
+    Ok(())
+}
+
+/**
+ * Takes the reimbursement of the recipient's token account out of a withdrawal fee.
+ *
+ * A withdrawal to a recipient without a token account of the mint creates its associated token
+ * account, with the relayer paying the rent in lamports. The fee is paid in the withdrawn token,
+ * which the pool can't price in lamports, so the mint's account_creation_fee, set by the authority
+ * in raw token units, stands in for the rent: the fee must include it on top of the withdrawal fee.
+ *
+ * @param mint_config The config of the withdrawn mint
+ * @param fee The fee paid to the relayer
+ * @param creates_account Whether the withdrawal creates the recipient's token account
+ * @return The part of the fee that must satisfy the withdrawal fee rate
+ */
+pub fn withdrawal_fee_after_account_creation(
+    mint_config: &MintConfig,
+    fee: u64,
+    creates_account: bool,
+) -> Result<u64> {
+    if !creates_account {
+        return Ok(fee);
+    }
+    Ok(fee.checked_sub(mint_config.account_creation_fee)
+        .ok_or(ErrorCode::AccountCreationFeeNotCovered)?)
+}
+
+/**
+ * Checks the public amounts of a swap before they are matched against the proof.
+ *
+ * check_public_amount accepts a zero amount for shielded transfers, so a swap has to reject
+ * a zero minimum output itself: it would let the swap credit nothing to the output mint.
+ *
+ * @param ext_amount The amount leaving the input reserve, must be negative
+ * @param ext_min_amount_out The minimum amount credited to the output reserve, must be positive
+ * @return Ok(()) if both amounts are valid, Err(ErrorCode::InvalidExtAmount) otherwise
+ */
+pub fn check_swap_amounts(ext_amount: i64, ext_min_amount_out: i64) -> Result<()> {
+    require!(ext_amount < 0, ErrorCode::InvalidExtAmount);
+    require!(ext_min_amount_out > 0, ErrorCode::InvalidExtAmount);
+    Ok(())
+}
+
+/**
+ * Checks that `relayer_account` may withdraw the lamports it requested to unstake.
+ *
+ * Listed and delisted relayers wait alike, so that the authority has the time to slash a relayer
+ * before its stake leaves the pool.
+ *
+ * @param relayer_account The relayer unstaking
+ * @param current_slot The current slot
+ * @return Ok(()) if lamports are pending and were requested at least RELAYER_UNSTAKE_COOLDOWN_SLOTS ago
+ */
+pub fn check_unstake(relayer_account: &RelayerAccount, current_slot: u64) -> Result<()> {
+    require!(relayer_account.pending_unstake > 0, ErrorCode::NoPendingUnstake);
+    let unlock_slot = relayer_account.unstake_requested_slot.saturating_add(RELAYER_UNSTAKE_COOLDOWN_SLOTS);
+    require!(current_slot >= unlock_slot, ErrorCode::RelayerUnstakeCooldown);
+    Ok(())
+}
+
+/**
+ * Takes `amount` lamports out of the stake of `relayer_account`, starting with the pending unstake.
+ *
+ * @param relayer_account The relayer being slashed
+ * @param amount Lamports to slash
+ * @return Ok(()) if the relayer has at least `amount` lamports staked or pending withdrawal
+ */
+pub fn slash_stake(relayer_account: &mut RelayerAccount, amount: u64) -> Result<()> {
+    let from_pending = amount.min(relayer_account.pending_unstake);
+    let from_stake = amount - from_pending;
+    require!(from_stake <= relayer_account.stake, ErrorCode::InsufficientRelayerStake);
+
+    relayer_account.pending_unstake -= from_pending;
+    relayer_account.stake -= from_stake;
+    Ok(())
+}
+
+/**
+ * Checks the shape of a new merkle tree.
+ *
+ * Tree accounts are created by `init` through a CPI, which can allocate at most MAX_INIT_ACCOUNT_SPACE bytes,
+ * so the subtrees and the root history must fit in that, e.g. at most 232 roots for a tree of height 26.
+ *
+ * @param height Height of the tree
+ * @param root_history_size Number of roots the tree keeps
+ * @return Ok(()) if the tree can be created, Err(ErrorCode) otherwise
+ */
+pub fn check_tree_shape(height: u8, root_history_size: u8) -> Result<()> {
+    require!((1..=MAX_MERKLE_TREE_HEIGHT).contains(&height), ErrorCode::InvalidTreeHeight);
+    require!(root_history_size > 0, ErrorCode::InvalidRootHistorySize);
+    require!(
+        MerkleTreeAccount::space(height, root_history_size) <= MAX_INIT_ACCOUNT_SPACE,
+        ErrorCode::TreeAccountTooLarge
+    );
+    Ok(())
+}
+
+/**
+ * Checks the shape of the first tree of a pool against the only verifying key it starts with.
+ *
+ * Trees of other heights are added later by rolling over to them once a verifying key of their height is active.
+ *
+ * @param height Height of the first tree
+ * @param root_history_size Number of roots the first tree keeps
+ * @param verifying_key_height Depth of the circuit of the genesis verifying key
+ * @return Ok(()) if proofs can be verified against the tree, Err(ErrorCode) otherwise
+ */
+pub fn check_initial_tree(height: u8, root_history_size: u8, verifying_key_height: u8) -> Result<()> {
+    check_tree_shape(height, root_history_size)?;
+    require!(height == verifying_key_height, ErrorCode::TreeHeightMismatch);
+    Ok(())
+}
+
+/**
+ * Records `amount` leaving the reserve of `mint_config`'s mint and enforces its outflow limit.
+ *
+ * Uses a sliding window approximated from two fixed windows: the outflow of the previous window
+ * is weighted by the share of it still covered by the rolling window ending at `current_slot`.
+ *
+ * @param mint_config The config of the mint leaving the pool, updated in place
+ * @param amount The amount leaving the reserve, in raw token units
+ * @param current_slot The current slot
+ * @param window_slots Length of the rolling window in slots, 0 disables the limit
+ * @return Ok(()) if the outflow stays within max_outflow_per_window, Err(ErrorCode) otherwise
+ */
+pub fn record_outflow(
+    mint_config: &mut MintConfig,
+    amount: u64,
+    current_slot: u64,
+    window_slots: u64,
+) -> Result<()> {
+    if mint_config.max_outflow_per_window == 0 || window_slots == 0 {
+        return Ok(());
+    }
+
+    let window_start = current_slot - current_slot % window_slots;
+    if window_start != mint_config.window_start_slot {
+        // Only the window right before the current one still overlaps the rolling window
+        let windows_elapsed = window_start.checked_sub(mint_config.window_start_slot);
+        mint_config.previous_window_outflow = if windows_elapsed == Some(window_slots) {
+            mint_config.current_window_outflow
+        } else {
+            0
+        };
+        mint_config.current_window_outflow = 0;
+        mint_config.window_start_slot = window_start;
+    }
+
+    let previous_window_weight = window_slots - (current_slot - window_start);
+    let weighted_previous_outflow = (mint_config.previous_window_outflow as u128)
+        .checked_mul(previous_window_weight as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(window_slots as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    let current_window_outflow = mint_config.current_window_outflow
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let rolling_outflow = weighted_previous_outflow
+        .checked_add(current_window_outflow as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    require!(
+        rolling_outflow <= mint_config.max_outflow_per_window as u128,
+        ErrorCode::OutflowLimitExceeded
+    );
+    mint_config.current_window_outflow = current_window_outflow;
+
+    Ok(())
+}
+
+/**
+ * Checks that an existing token account can receive a withdrawal for `recipient`. Any token account
+ * of the mint owned by the recipient is accepted, not only its associated token account.
+ *
+ * @param token_account The token account passed by the relayer
+ * @param recipient The recipient committed to in the proof
+ * @param mint The withdrawn mint
+ * @return Ok(()) if the account holds `mint` and is owned by `recipient`
+ */
+pub fn check_recipient_token_account(token_account: &TokenAccount, recipient: &Pubkey, mint: &Pubkey) -> Result<()> {
+    require!(
+        token_account.owner == *recipient && token_account.mint == *mint,
+        ErrorCode::InvalidRecipientTokenAccount
+    );
+    Ok(())
+}
+
+/**
+ * Checks that a swap route debited exactly `swap_amount` from the input reserve.
+ *
+ * The shielded input value is swap_amount + fee, the fee being paid separately after the route.
+ * A route pulling more would spend reserve tokens backing other notes.
+ *
+ * @param balance_before The input reserve balance before the route
+ * @param balance_after The input reserve balance after the route
+ * @param swap_amount |ext_amount|, the input amount committed to in the proof
+ * @return Ok(()) if the reserve decreased by exactly swap_amount, Err(SwapInputMismatch) otherwise
+ */
+pub fn check_swap_input(balance_before: u64, balance_after: u64, swap_amount: u64) -> Result<()> {
+    let input_debit = balance_before.checked_sub(balance_after)
+        .ok_or(ErrorCode::SwapInputMismatch)?;
+    require!(input_debit == swap_amount, ErrorCode::SwapInputMismatch);
+    Ok(())
+}
+
+/**
+ * Validates the slippage surplus of a swap, i.e. the output received above ext_min_amount_out,
+ * which is captured by the relayer in output tokens.
+ *
+ * @param surplus The output amount received above min_amount_out
+ * @param min_amount_out The minimum output amount committed to in the proof
+ * @param max_surplus_rate Maximum surplus relative to min_amount_out (in basis points, 0-10000)
+ * @return Ok(()) if surplus <= min_amount_out * max_surplus_rate / 10000, Err(ErrorCode) otherwise
+ */
+pub fn validate_swap_surplus(surplus: u64, min_amount_out: u64, max_surplus_rate: u16) -> Result<()> {
+    let max_surplus = (min_amount_out as u128)
+        .checked_mul(max_surplus_rate as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    require!(surplus as u128 <= max_surplus, ErrorCode::SwapSurplusExceeded);
+    Ok(())
+}
+
+/**
+ * Creates a PDA account owned by `owner`, with `payer` covering its rent.
+ *
+ * Unlike a plain create_account this also works when lamports were sent to the address beforehand,
+ * so nobody can block the address by funding it.
+ *
+ * @param payer The account paying the rent, must be a signer
+ * @param new_account The PDA to create
+ * @param system_program The system program
+ * @param space Size of the account data
+ * @param owner Program that will own the account
+ * @param signer_seeds Seeds of `new_account`, including the bump
+ * @return Ok(()) once the account is allocated, assigned and rent exempt
+ */
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    new_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current_lamports = new_account.lamports();
+
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: new_account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            space as u64,
+            owner,
+        );
+    }
+
+    let top_up = rent.saturating_sub(current_lamports);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: new_account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate { account_to_allocate: new_account.clone() },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign { account_to_assign: new_account.clone() },
+            &[signer_seeds],
+        ),
+        owner,
+    )
+}
+
+/**
+ * Calculates how many tokens have to be sent so that the destination is credited exactly `net_amount`.
+ *
+ * Legacy SPL mints and Token-2022 mints without the transfer-fee extension credit the full amount,
+ * for mints with a transfer fee the fee of the current epoch is added on top.
+ *
+ * @param mint The mint account of the transferred token
+ * @param net_amount The amount the destination has to receive
+ * @return The gross amount to pass to transfer_checked
+ */
+pub fn calculate_gross_transfer_amount(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(net_amount);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_with_extensions = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let transfer_fee_config = match mint_with_extensions.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config,
+        Err(_) => return Ok(net_amount),
+    };
+
+    let epoch = Clock::get()?.epoch;
+    let gross_amount = transfer_fee_config
+        .get_epoch_fee(epoch)
+        .calculate_pre_fee_amount(net_amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(gross_amount)
+}
+
 pub fn change_endianness(bytes: &[u8]) -> Vec<u8> {
     let mut vec = Vec::new();
     for b in bytes.chunks(32) {
@@ -376,6 +880,7 @@ pub fn change_endianness(bytes: &[u8]) -> Vec<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::MERKLE_TREE_HEIGHT;
 
     #[test]
     fn test_validate_fee_deposit_exact_minimum() {
@@ -557,4 +1062,321 @@ mod tests {
         // We don't need to check the specific error type since overflow protection
         // may result in different error conditions depending on implementation
     }
-}
\ No newline at end of file
+
+    fn mint_config(min_deposit_amount: u64, max_deposit_amount: u64, enabled: bool) -> MintConfig {
+        MintConfig {
+            mint: Pubkey::new_unique(),
+            max_deposit_amount,
+            min_deposit_amount,
+            enabled,
+            max_outflow_per_window: 0,
+            window_start_slot: 0,
+            current_window_outflow: 0,
+            previous_window_outflow: 0,
+            price_feed_id: [0u8; 32],
+            min_transfer_fee: 0,
+            account_creation_fee: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_load_mint_config_of_unregistered_mint() {
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let mut lamports = 0u64;
+        let mut data = vec![];
+        let unregistered = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let result = load_mint_config(&unregistered).map(|_| ());
+        assert!(format!("{:?}", result).contains("UnsupportedMintAddress"));
+    }
+
+    #[test]
+    fn test_load_mint_config_of_registered_mint() {
+        let config = mint_config(1_000_000, 1_000_000_000_000, true);
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let mut lamports = 1_000_000u64;
+        let mut data = vec![];
+        config.try_serialize(&mut data).unwrap();
+        let registered = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let loaded = load_mint_config(&registered).unwrap();
+        assert_eq!(loaded.mint, config.mint);
+        assert_eq!(loaded.min_deposit_amount, 1_000_000);
+        assert_eq!(loaded.max_deposit_amount, 1_000_000_000_000);
+    }
+
+    #[test]
+    fn test_check_deposit_limits_within_bounds() {
+        // USDC-like mint: 1 USDC minimum, 1,000,000 USDC maximum (6 decimals)
+        let config = mint_config(1_000_000, 1_000_000_000_000, true);
+        assert!(check_deposit_limits(&config, 1_000_000).is_ok());
+        assert!(check_deposit_limits(&config, 1_000_000_000_000).is_ok());
+    }
+
+    #[test]
+    fn test_check_deposit_limits_out_of_bounds() {
+        let config = mint_config(1_000_000, 1_000_000_000_000, true);
+        let below = check_deposit_limits(&config, 999_999);
+        assert!(format!("{:?}", below).contains("DepositBelowMinimum"));
+        let above = check_deposit_limits(&config, 1_000_000_000_001);
+        assert!(format!("{:?}", above).contains("DepositLimitExceeded"));
+    }
+
+    #[test]
+    fn test_check_deposit_limits_disabled_mint() {
+        let config = mint_config(0, u64::MAX, false);
+        let result = check_deposit_limits(&config, 1);
+        assert!(format!("{:?}", result).contains("MintDisabled"));
+    }
+
+    #[test]
+    fn test_check_transfer_with_fee() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.min_transfer_fee = 5_000;
+        assert!(check_transfer(&config, 0, 5_000).is_ok());
+        assert!(check_transfer(&config, 0, 10_000).is_ok());
+
+        let withdrawal = check_transfer(&config, -1_000, 5_000);
+        assert!(format!("{:?}", withdrawal).contains("InvalidExtAmount"));
+
+        let mut disabled_config = mint_config(0, u64::MAX, false);
+        disabled_config.min_transfer_fee = 5_000;
+        let disabled = check_transfer(&disabled_config, 0, 5_000);
+        assert!(format!("{:?}", disabled).contains("MintDisabled"));
+    }
+
+    #[test]
+    fn test_check_transfer_fee_below_minimum() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.min_transfer_fee = 5_000;
+
+        let free = check_transfer(&config, 0, 0);
+        assert!(format!("{:?}", free).contains("TransferFeeBelowMinimum"));
+        let below = check_transfer(&config, 0, 4_999);
+        assert!(format!("{:?}", below).contains("TransferFeeBelowMinimum"));
+    }
+
+    #[test]
+    fn test_withdrawal_fee_to_existing_token_account() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.account_creation_fee = 2_000;
+
+        // Nothing is created, the whole fee counts towards the withdrawal fee rate
+        assert_eq!(withdrawal_fee_after_account_creation(&config, 0, false).unwrap(), 0);
+        assert_eq!(withdrawal_fee_after_account_creation(&config, 5_000, false).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn test_withdrawal_fee_creating_token_account() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.account_creation_fee = 2_000;
+
+        assert_eq!(withdrawal_fee_after_account_creation(&config, 7_000, true).unwrap(), 5_000);
+        assert_eq!(withdrawal_fee_after_account_creation(&config, 2_000, true).unwrap(), 0);
+        let uncovered = withdrawal_fee_after_account_creation(&config, 1_999, true);
+        assert!(format!("{:?}", uncovered).contains("AccountCreationFeeNotCovered"));
+
+        // A withdrawal paying exactly the rate fee only passes validate_fee without the account
+        let rate_fee = 5_000; // 0.25% of 2_000_000
+        assert!(validate_fee(-2_000_000, rate_fee, 0, 25, 0).is_ok());
+        let net = withdrawal_fee_after_account_creation(&config, rate_fee, true).unwrap();
+        assert!(validate_fee(-2_000_000, net, 0, 25, 0).is_err());
+    }
+
+    #[test]
+    fn test_check_swap_amounts_rejects_zero_min_amount_out() {
+        assert!(check_swap_amounts(-1_000, 990).is_ok());
+
+        // check_public_amount alone accepts a zero amount with a zero public amount
+        assert!(check_public_amount(0, 0, [0u8; 32]));
+        let zero_out = check_swap_amounts(-1_000, 0);
+        assert!(format!("{:?}", zero_out).contains("InvalidExtAmount"));
+        let negative_out = check_swap_amounts(-1_000, -1);
+        assert!(format!("{:?}", negative_out).contains("InvalidExtAmount"));
+        let deposit = check_swap_amounts(1_000, 990);
+        assert!(format!("{:?}", deposit).contains("InvalidExtAmount"));
+    }
+
+    fn relayer_account(stake: u64, pending_unstake: u64, unstake_requested_slot: u64) -> RelayerAccount {
+        RelayerAccount {
+            relayer: Pubkey::new_unique(),
+            active: true,
+            stake,
+            pending_unstake,
+            unstake_requested_slot,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_check_unstake_after_cooldown() {
+        let nothing_pending = check_unstake(&relayer_account(1_000_000_000, 0, 0), u64::MAX);
+        assert!(format!("{:?}", nothing_pending).contains("NoPendingUnstake"));
+
+        // Active relayers wait as long as delisted ones
+        let mut relayer_account = relayer_account(0, 1_000_000_000, 1_000);
+        for active in [true, false] {
+            relayer_account.active = active;
+            let cooling_down = check_unstake(&relayer_account, 1_000 + RELAYER_UNSTAKE_COOLDOWN_SLOTS - 1);
+            assert!(format!("{:?}", cooling_down).contains("RelayerUnstakeCooldown"));
+            assert!(check_unstake(&relayer_account, 1_000 + RELAYER_UNSTAKE_COOLDOWN_SLOTS).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_slash_stake_takes_pending_unstake_first() {
+        let mut relayer_account = relayer_account(1_000, 300, 50);
+        slash_stake(&mut relayer_account, 200).unwrap();
+        assert_eq!((relayer_account.stake, relayer_account.pending_unstake), (1_000, 100));
+
+        slash_stake(&mut relayer_account, 600).unwrap();
+        assert_eq!((relayer_account.stake, relayer_account.pending_unstake), (500, 0));
+
+        let too_much = slash_stake(&mut relayer_account, 501);
+        assert!(format!("{:?}", too_much).contains("InsufficientRelayerStake"));
+        assert_eq!((relayer_account.stake, relayer_account.pending_unstake), (500, 0));
+
+        slash_stake(&mut relayer_account, 500).unwrap();
+        assert_eq!((relayer_account.stake, relayer_account.pending_unstake), (0, 0));
+    }
+
+    #[test]
+    fn test_check_initial_tree() {
+        assert!(check_initial_tree(MERKLE_TREE_HEIGHT, 100, MERKLE_TREE_HEIGHT).is_ok());
+        assert!(check_initial_tree(MERKLE_TREE_HEIGHT, 1, MERKLE_TREE_HEIGHT).is_ok());
+
+        let other_height = check_initial_tree(20, 100, MERKLE_TREE_HEIGHT);
+        assert!(format!("{:?}", other_height).contains("TreeHeightMismatch"));
+        let no_roots = check_initial_tree(MERKLE_TREE_HEIGHT, 0, MERKLE_TREE_HEIGHT);
+        assert!(format!("{:?}", no_roots).contains("InvalidRootHistorySize"));
+        let too_large = check_initial_tree(MERKLE_TREE_HEIGHT, 255, MERKLE_TREE_HEIGHT);
+        assert!(format!("{:?}", too_large).contains("TreeAccountTooLarge"));
+    }
+
+    #[test]
+    fn test_check_tree_shape() {
+        assert!(check_tree_shape(20, 100).is_ok());
+        assert!(check_tree_shape(MERKLE_TREE_HEIGHT, 232).is_ok());
+
+        let too_large = check_tree_shape(MERKLE_TREE_HEIGHT, 233);
+        assert!(format!("{:?}", too_large).contains("TreeAccountTooLarge"));
+        let no_levels = check_tree_shape(0, 100);
+        assert!(format!("{:?}", no_levels).contains("InvalidTreeHeight"));
+        let too_deep = check_tree_shape(MAX_MERKLE_TREE_HEIGHT + 1, 100);
+        assert!(format!("{:?}", too_deep).contains("InvalidTreeHeight"));
+    }
+
+    #[test]
+    fn test_record_outflow_unlimited() {
+        let mut config = mint_config(0, u64::MAX, true);
+        assert!(record_outflow(&mut config, u64::MAX, 1_000, 100).is_ok());
+        assert_eq!(config.current_window_outflow, 0);
+    }
+
+    #[test]
+    fn test_record_outflow_within_window() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.max_outflow_per_window = 1_000;
+        assert!(record_outflow(&mut config, 600, 1_010, 100).is_ok());
+        assert!(record_outflow(&mut config, 400, 1_050, 100).is_ok());
+        let result = record_outflow(&mut config, 1, 1_099, 100);
+        assert!(format!("{:?}", result).contains("OutflowLimitExceeded"));
+        assert_eq!(config.current_window_outflow, 1_000);
+    }
+
+    #[test]
+    fn test_record_outflow_previous_window_decays() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.max_outflow_per_window = 1_000;
+        assert!(record_outflow(&mut config, 1_000, 1_000, 100).is_ok());
+        // 25 slots into the next window, 75% of the previous outflow still counts
+        let result = record_outflow(&mut config, 251, 1_125, 100);
+        assert!(format!("{:?}", result).contains("OutflowLimitExceeded"));
+        assert!(record_outflow(&mut config, 250, 1_125, 100).is_ok());
+        assert_eq!(config.previous_window_outflow, 1_000);
+    }
+
+    #[test]
+    fn test_record_outflow_resets_after_idle_windows() {
+        let mut config = mint_config(0, u64::MAX, true);
+        config.max_outflow_per_window = 1_000;
+        assert!(record_outflow(&mut config, 1_000, 1_000, 100).is_ok());
+        assert!(record_outflow(&mut config, 1_000, 1_250, 100).is_ok());
+        assert_eq!(config.previous_window_outflow, 0);
+        assert_eq!(config.window_start_slot, 1_200);
+    }
+
+    fn token_account(owner: Pubkey, mint: Pubkey) -> TokenAccount {
+        use anchor_spl::token_2022::spl_token_2022::state::{Account, AccountState};
+        use anchor_lang::solana_program::program_pack::Pack;
+
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(Account { mint, owner, amount: 0, state: AccountState::Initialized, ..Account::default() }, &mut data).unwrap();
+        TokenAccount::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn test_check_recipient_token_account_accepts_non_associated_account() {
+        let (recipient, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // Owner and mint are all that matter, wherever the account lives
+        assert!(check_recipient_token_account(&token_account(recipient, mint), &recipient, &mint).is_ok());
+
+        let other_owner = check_recipient_token_account(&token_account(Pubkey::new_unique(), mint), &recipient, &mint);
+        assert!(format!("{:?}", other_owner).contains("InvalidRecipientTokenAccount"));
+        let other_mint = check_recipient_token_account(&token_account(recipient, Pubkey::new_unique()), &recipient, &mint);
+        assert!(format!("{:?}", other_mint).contains("InvalidRecipientTokenAccount"));
+    }
+
+    #[test]
+    fn test_check_swap_input_exact_debit() {
+        assert!(check_swap_input(10_000, 9_000, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_check_swap_input_route_over_pulls() {
+        // The route took 1_001 from the reserve while the proof only covers 1_000
+        let over_pull = check_swap_input(10_000, 8_999, 1_000);
+        assert!(format!("{:?}", over_pull).contains("SwapInputMismatch"));
+
+        let under_pull = check_swap_input(10_000, 9_001, 1_000);
+        assert!(format!("{:?}", under_pull).contains("SwapInputMismatch"));
+
+        let refilled = check_swap_input(10_000, 10_500, 1_000);
+        assert!(format!("{:?}", refilled).contains("SwapInputMismatch"));
+    }
+
+    #[test]
+    fn test_validate_swap_surplus() {
+        // 1% of 100_000 = 1_000
+        assert!(validate_swap_surplus(0, 100_000, 100).is_ok());
+        assert!(validate_swap_surplus(1_000, 100_000, 100).is_ok());
+        assert!(validate_swap_surplus(1_001, 100_000, 100).is_err());
+        assert!(validate_swap_surplus(1, 100_000, 0).is_err());
+        assert!(validate_swap_surplus(u64::MAX, u64::MAX, 10000).is_ok());
+    }
+
+    #[test]
+    fn test_check_public_amount_private_transfer() {
+        use ark_ff::BigInteger;
+
+        // ext_amount == 0: public_amount = -fee
+        let fee = 1_000u64;
+        let mut public_amount = [0u8; 32];
+        public_amount.copy_from_slice(&(-Fr::from(fee)).into_bigint().to_bytes_be());
+        assert!(check_public_amount(0, fee, public_amount));
+        assert!(check_public_amount(0, 0, [0u8; 32]));
+        assert!(!check_public_amount(0, fee, [0u8; 32]));
+    }
+
+    #[test]
+    fn test_check_public_amount_deposit_must_exceed_fee() {
+        use ark_ff::BigInteger;
+
+        let mut public_amount = [0u8; 32];
+        public_amount.copy_from_slice(&Fr::from(0u64).into_bigint().to_bytes_be());
+        assert!(!check_public_amount(1_000, 1_000, public_amount));
+    }
+}
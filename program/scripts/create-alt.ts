@@ -34,9 +34,9 @@ function getProtocolAddresses(
         programId
     );
 
-    // Derive tree accounts
+    // Derive tree accounts, the first tree has id 0
     const [treeAccount] = PublicKey.findProgramAddressSync(
-        [Buffer.from("merkle_tree")],
+        [Buffer.from("merkle_tree"), Buffer.alloc(8)],
         programId
     );
 
@@ -10,6 +10,10 @@ import { Zkcash } from "../target/types/zkcash";
 import fs from "fs";
 import path from "path";
 
+// Height of the first tree, must match the genesis verifying key, and the number of roots it keeps
+const TREE_HEIGHT = 26;
+const ROOT_HISTORY_SIZE = 100;
+
 async function initialize() {
   try {
     // Get cluster from command line args or use localnet
@@ -46,7 +50,7 @@ async function initialize() {
 
     // Derive PDAs
     const [treeAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from("merkle_tree")],
+      [Buffer.from("merkle_tree"), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
       program.programId
     );
     const [treeTokenAccount] = PublicKey.findProgramAddressSync(
@@ -57,11 +61,16 @@ async function initialize() {
       [Buffer.from("global_config")],
       program.programId
     );
+    const [verifyingKey] = PublicKey.findProgramAddressSync(
+      [Buffer.from("verifying_key"), Buffer.alloc(4)], // version 0, the genesis circuit
+      program.programId
+    );
 
     console.log("\n📍 PDAs:");
     console.log("  Tree Account:", treeAccount.toString());
     console.log("  Tree Token Account:", treeTokenAccount.toString());
     console.log("  Global Config:", globalConfig.toString());
+    console.log("  Verifying Key:", verifyingKey.toString());
 
     // Check wallet balance
     const balance = await connection.getBalance(walletKeypair.publicKey);
@@ -87,11 +96,12 @@ async function initialize() {
     // Initialize
     console.log("\n⚙️  Sending initialize transaction...");
     const tx = await program.methods
-      .initialize()
+      .initialize(TREE_HEIGHT, ROOT_HISTORY_SIZE)
       .accountsStrict({
         treeAccount,
         treeTokenAccount,
         globalConfig: globalConfig,
+        verifyingKey,
         authority: walletKeypair.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -108,14 +118,17 @@ async function initialize() {
     console.log("  Deposit Fee Rate:", globalConfigAccount.depositFeeRate, "basis points (0%)");
     console.log("  Withdrawal Fee Rate:", globalConfigAccount.withdrawalFeeRate, "basis points (0.25%)");
     console.log("  Fee Error Margin:", globalConfigAccount.feeErrorMargin, "basis points (5%)");
+    console.log("  Active Tree Id:", globalConfigAccount.activeTreeId.toString());
+    console.log("  Root Validity:", globalConfigAccount.rootValiditySlots.toString(), "slots");
 
     const treeAccountData = await program.account.merkleTreeAccount.fetch(treeAccount);
     console.log("\n🌳 Merkle Tree:");
+    console.log("  Tree Id:", treeAccountData.treeId.toString());
     console.log("  Height:", treeAccountData.height);
-    console.log("  Max Deposit Amount:", treeAccountData.maxDepositAmount.toString(), "lamports");
     console.log("  Root History Size:", treeAccountData.rootHistorySize);
 
     console.log("\n✨ Setup complete!");
+    console.log("💡 Register mints with register_mint and relayers with register_relayer before accepting deposits");
   } catch (error) {
     console.error("\n❌ Error:", error);
     process.exit(1);
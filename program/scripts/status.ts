@@ -40,10 +40,6 @@ async function checkStatus() {
     const program = anchor.workspace.Zkcash as Program<Zkcash>;
 
     // Derive PDAs
-    const [treeAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from("merkle_tree")],
-      program.programId
-    );
     const [globalConfig] = PublicKey.findProgramAddressSync(
       [Buffer.from("global_config")],
       program.programId
@@ -55,8 +51,8 @@ async function checkStatus() {
 
     // Check if initialized
     try {
-      const treeAccountInfo = await connection.getAccountInfo(treeAccount);
-      if (!treeAccountInfo) {
+      const globalConfigInfo = await connection.getAccountInfo(globalConfig);
+      if (!globalConfigInfo) {
         console.log("\n❌ Contract NOT initialized");
         console.log("\n💡 Run: yarn cli:setup to initialize");
         process.exit(1);
@@ -64,8 +60,12 @@ async function checkStatus() {
 
       console.log("\n✅ Contract Status: INITIALIZED");
 
-      // Fetch config
+      // Fetch config and the active tree
       const globalConfigAccount = await program.account.globalConfig.fetch(globalConfig);
+      const [treeAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from("merkle_tree"), globalConfigAccount.activeTreeId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
       const treeAccountData = await program.account.merkleTreeAccount.fetch(treeAccount);
 
       console.log("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -78,7 +78,7 @@ async function checkStatus() {
       console.log("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
       console.log("📍 PDAs");
       console.log("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-      console.log("  Tree Account:", treeAccount.toString());
+      console.log("  Active Tree Account:", treeAccount.toString());
       console.log("  Global Config:", globalConfig.toString());
 
       console.log("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -88,15 +88,22 @@ async function checkStatus() {
       console.log("  Deposit Fee:", globalConfigAccount.depositFeeRate / 100, "%");
       console.log("  Withdrawal Fee:", globalConfigAccount.withdrawalFeeRate / 100, "%");
       console.log("  Fee Error Margin:", globalConfigAccount.feeErrorMargin / 100, "%");
+      console.log("  Swap Fee:", globalConfigAccount.swapFeeRate / 100, "%");
+      console.log("  Paused Flags:", globalConfigAccount.paused);
+      console.log("  Min Relayer Stake:", (globalConfigAccount.minRelayerStake.toNumber() / 1e9).toFixed(4), "SOL");
+      console.log("  Root Validity:", globalConfigAccount.rootValiditySlots.toString(), "slots");
 
       console.log("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
       console.log("🌳 Merkle Tree");
       console.log("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+      console.log("  Tree Id:", treeAccountData.treeId.toString());
       console.log("  Height:", treeAccountData.height);
-      console.log("  Next Index:", treeAccountData.nextIndex);
-      console.log("  Root Index:", treeAccountData.rootIndex);
-      console.log("  Max Deposit:", (treeAccountData.maxDepositAmount.toNumber() / 1e9).toFixed(2), "SOL");
+      console.log("  Next Index:", treeAccountData.nextIndex.toString());
+      console.log("  Root Index:", treeAccountData.rootIndex.toString());
       console.log("  Root History Size:", treeAccountData.rootHistorySize);
+      console.log("  Root History Account:", treeAccountData.rootHistoryAccount.equals(PublicKey.default)
+        ? "none"
+        : treeAccountData.rootHistoryAccount.toString());
 
       // Load test mints if available
       const mintsPath = path.resolve(__dirname, "../test-mints.json");
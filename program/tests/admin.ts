@@ -0,0 +1,787 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { ProgramTestContext, BanksClient, startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { expect } from "chai";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createAssociatedTokenAccountInstruction, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { sha256 } from "@ethersproject/sha2";
+import { Zkcash } from "../target/types/zkcash";
+import {
+  buildDepositInstruction,
+  buildMissingNullifierShardInstructions,
+  executeAddSwapProgram,
+  executeInitialize,
+  executeRegisterMint,
+  executeRegisterRelayer,
+  executeUpdateGlobalConfig,
+  buildInitializeNullifierShardInstruction,
+  findTransactionAccounts,
+  sendBankrunTransaction,
+  JUPITER_PROGRAM_ID
+} from "./instructions";
+import {
+  findGlobalConfigPDA,
+  findLegacyMerkleTreePDA,
+  findMerkleTreePDA,
+  findMintConfigPDA,
+  findNullifierShardPDA,
+  findRelayerPDA,
+  findSwapProgramRegistryPDA,
+  findVerifyingKeyPDA
+} from "./lib/derive";
+import { createMint } from "./lib/token";
+import { DEFAULT_HEIGHT, ROOT_HISTORY_SIZE, ZERO_BYTES } from "./lib/constants";
+
+const PROGRAM_ID = new PublicKey("6Uok9UsjztPC9VJ3a8ZpawzKmgrD2VvMKQGb64FYjhnx");
+
+// Mirrors the constants of programs/zert/src/state.rs and root_history.rs
+const PAUSE_DEPOSIT = 1 << 0;
+const PAUSE_WITHDRAW = 1 << 1;
+const RELAYER_UNSTAKE_COOLDOWN_SLOTS = 216_000;
+const ROOT_HISTORY_ENTRIES_OFFSET = 8 + 48;
+const ROOT_HISTORY_BYTES_PER_ROOT = 40 + 2 * 4;
+
+/**
+ * Expect a transaction to fail with the given program error
+ * @param promise - Pending transaction
+ * @param code - Name of the expected ErrorCode variant
+ */
+async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (error) {
+    // Bankrun transactions only carry the error name in their logs
+    const logs: string[] = (error as any).logs ?? (error as any).meta?.logMessages ?? [];
+    expect([String(error), ...logs].join("\n")).to.include(code);
+    return;
+  }
+  expect.fail(`Expected the transaction to fail with ${code}`);
+}
+
+/**
+ * Anchor account discriminator, used to write accounts in the legacy layouts
+ * @param name - Account struct name
+ * @returns First 8 bytes of sha256("account:<name>")
+ */
+function accountDiscriminator(name: string): Buffer {
+  return Buffer.from(sha256(Buffer.from(`account:${name}`)).slice(2, 18), "hex");
+}
+
+async function startProgram() {
+  const context = await startAnchor("", [{ name: "zkcash", programId: PROGRAM_ID }], []);
+  const provider = new BankrunProvider(context);
+  anchor.setProvider(provider);
+  // Not anchor.workspace.Zkcash, which stays bound to the provider of the first context
+  const program = new Program<Zkcash>(anchor.workspace.Zkcash.idl, provider);
+  return { context, program };
+}
+
+/**
+ * Give lamports to a keypair that signs and pays in the tests
+ */
+function fundAccount(context: ProgramTestContext, account: PublicKey, lamports: number = 10 * LAMPORTS_PER_SOL) {
+  context.setAccount(account, {
+    lamports,
+    data: Buffer.alloc(0),
+    owner: SystemProgram.programId,
+    executable: false,
+  });
+}
+
+/**
+ * Warp one slot ahead, so that a retried transaction gets a new blockhash and is not deduplicated
+ */
+async function advanceSlot(context: ProgramTestContext, slots: number = 1) {
+  const clock = await context.banksClient.getClock();
+  context.warpToSlot(clock.slot + BigInt(slots));
+}
+
+describe("admin", () => {
+  let context: ProgramTestContext;
+  let program: Program<Zkcash>;
+  let banksClient: BanksClient;
+  let admin: Keypair;
+  let globalConfig: PublicKey;
+
+  before(async () => {
+    ({ context, program } = await startProgram());
+    banksClient = context.banksClient;
+    admin = context.payer;
+    [globalConfig] = findGlobalConfigPDA(program.programId);
+  });
+
+  it("Rejects a first tree the genesis verifying key cannot prove", async () => {
+    await expectError(executeInitialize(program, [admin], [], DEFAULT_HEIGHT - 6, ROOT_HISTORY_SIZE), "TreeHeightMismatch");
+    // A height 26 tree keeping 255 roots exceeds the 10240 bytes an account created by the program can have
+    await expectError(executeInitialize(program, [admin], [], DEFAULT_HEIGHT, 255), "TreeAccountTooLarge");
+  });
+
+  it("Initialize", async () => {
+    await executeInitialize(program, [admin], [], DEFAULT_HEIGHT, ROOT_HISTORY_SIZE);
+
+    const config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.authority.equals(admin.publicKey)).to.be.true;
+    expect(config.activeTreeId.toNumber()).to.equal(0);
+    expect(config.paused).to.equal(0);
+
+    const [treeAccount] = findMerkleTreePDA(program.programId, 0);
+    const tree = await program.account.merkleTreeAccount.fetch(treeAccount);
+    expect(tree.height).to.equal(DEFAULT_HEIGHT);
+    expect(tree.rootHistorySize).to.equal(ROOT_HISTORY_SIZE);
+    expect(tree.rootHistoryAccount.equals(PublicKey.default)).to.be.true;
+
+    const [verifyingKey] = findVerifyingKeyPDA(program.programId, 0);
+    const genesisKey = await program.account.verifyingKeyAccount.fetch(verifyingKey);
+    expect(genesisKey.status).to.deep.equal({ active: {} });
+    expect(genesisKey.treeHeight).to.equal(DEFAULT_HEIGHT);
+  });
+
+  it("Proposes and accepts a new authority", async () => {
+    const newAuthority = Keypair.generate();
+    const impostor = Keypair.generate();
+
+    await program.methods
+      .proposeAuthority(newAuthority.publicKey)
+      .accountsStrict({ globalConfig, authority: admin.publicKey })
+      .rpc();
+
+    await expectError(
+      program.methods
+        .acceptAuthority()
+        .accountsStrict({ globalConfig, newAuthority: impostor.publicKey })
+        .signers([impostor])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await program.methods
+      .acceptAuthority()
+      .accountsStrict({ globalConfig, newAuthority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc();
+
+    let config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.authority.equals(newAuthority.publicKey)).to.be.true;
+    expect(config.pendingAuthority.equals(PublicKey.default)).to.be.true;
+
+    // Hand the authority back to the admin for the following tests
+    await program.methods
+      .proposeAuthority(admin.publicKey)
+      .accountsStrict({ globalConfig, authority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc();
+    await program.methods
+      .acceptAuthority()
+      .accountsStrict({ globalConfig, newAuthority: admin.publicKey })
+      .rpc();
+
+    config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.authority.equals(admin.publicKey)).to.be.true;
+  });
+
+  it("Lets the guardian pause but not unpause", async () => {
+    const guardian = Keypair.generate();
+    await executeUpdateGlobalConfig(program, [admin], { guardian: guardian.publicKey });
+
+    await program.methods
+      .setPause(PAUSE_DEPOSIT)
+      .accountsStrict({ globalConfig, signer: guardian.publicKey })
+      .signers([guardian])
+      .rpc();
+    expect((await program.account.globalConfig.fetch(globalConfig)).paused).to.equal(PAUSE_DEPOSIT);
+
+    await expectError(
+      program.methods
+        .setPause(PAUSE_WITHDRAW)
+        .accountsStrict({ globalConfig, signer: guardian.publicKey })
+        .signers([guardian])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await expectError(
+      program.methods
+        .setPause(1 << 7)
+        .accountsStrict({ globalConfig, signer: admin.publicKey })
+        .rpc(),
+      "InvalidPauseFlags"
+    );
+
+    await program.methods
+      .setPause(0)
+      .accountsStrict({ globalConfig, signer: admin.publicKey })
+      .rpc();
+    expect((await program.account.globalConfig.fetch(globalConfig)).paused).to.equal(0);
+  });
+
+  it("Updates the global config", async () => {
+    await executeUpdateGlobalConfig(program, [admin], {
+      withdrawalFeeRate: 35,
+      minRelayerStake: new BN(LAMPORTS_PER_SOL),
+      rootValiditySlots: new BN(1_000),
+    });
+
+    const config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.withdrawalFeeRate).to.equal(35);
+    expect(config.minRelayerStake.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect(config.rootValiditySlots.toNumber()).to.equal(1_000);
+
+    const stranger = Keypair.generate();
+    await expectError(executeUpdateGlobalConfig(program, [stranger], { withdrawalFeeRate: 0 }), "Unauthorized");
+  });
+
+  it("Registers and updates a mint", async () => {
+    const mint = await createMint({
+      banksClient,
+      payer: admin,
+      admin,
+      lastBlockhash: context.lastBlockhash,
+      decimals: 6,
+    });
+    const [mintConfig] = findMintConfigPDA(program.programId, mint);
+
+    await expectError(executeRegisterMint(program, [admin], mint, new BN(100), new BN(1_000)), "InvalidDepositLimits");
+
+    await executeRegisterMint(program, [admin], mint, new BN(1_000_000), new BN(10), new BN(5), new BN(3));
+    let config = await program.account.mintConfig.fetch(mintConfig);
+    expect(config.mint.equals(mint)).to.be.true;
+    expect(config.enabled).to.be.true;
+    expect(config.maxDepositAmount.toNumber()).to.equal(1_000_000);
+    expect(config.minDepositAmount.toNumber()).to.equal(10);
+    expect(config.minTransferFee.toNumber()).to.equal(5);
+    expect(config.accountCreationFee.toNumber()).to.equal(3);
+
+    await program.methods
+      .updateMintConfig(null, null, false, new BN(500_000), null, new BN(20), new BN(7))
+      .accountsStrict({ globalConfig, mintConfig, authority: admin.publicKey })
+      .rpc();
+    config = await program.account.mintConfig.fetch(mintConfig);
+    expect(config.enabled).to.be.false;
+    expect(config.maxOutflowPerWindow.toNumber()).to.equal(500_000);
+    expect(config.minTransferFee.toNumber()).to.equal(20);
+    expect(config.accountCreationFee.toNumber()).to.equal(7);
+    expect(config.maxDepositAmount.toNumber()).to.equal(1_000_000);
+  });
+
+  it("Rejects a deposit of an unregistered mint", async () => {
+    const mint = await createMint({
+      banksClient,
+      payer: admin,
+      admin,
+      lastBlockhash: context.lastBlockhash,
+      decimals: 6,
+    });
+    await sendBankrunTransaction(
+      banksClient,
+      [
+        createAssociatedTokenAccountInstruction(
+          admin.publicKey,
+          getAssociatedTokenAddressSync(mint, globalConfig, true),
+          globalConfig,
+          mint
+        ),
+        createAssociatedTokenAccountInstruction(
+          admin.publicKey,
+          getAssociatedTokenAddressSync(mint, admin.publicKey),
+          admin.publicKey,
+          mint
+        ),
+      ],
+      admin,
+      []
+    );
+
+    // The mint config is checked before anything else, so the proof does not need to be valid
+    const nullifier = (lastByte: number) => [...new Array(31).fill(0), lastByte];
+    const proof = {
+      proofA: new Array(64).fill(0),
+      proofB: new Array(128).fill(0),
+      proofC: new Array(64).fill(0),
+      root: new Array(32).fill(0),
+      publicAmount0: new Array(32).fill(0),
+      publicAmount1: new Array(32).fill(0),
+      extDataHash: new Array(32).fill(0),
+      inputNullifiers: [nullifier(201), nullifier(202)],
+      outputCommitments: [new Array(32).fill(0), new Array(32).fill(0)],
+    };
+    const extData = {
+      recipient: getAssociatedTokenAddressSync(mint, globalConfig, true),
+      extAmount: new BN(1_000),
+      encryptedOutput: Buffer.from("unregisteredMintOutput"),
+      fee: new BN(0),
+      feeRecipient: admin.publicKey,
+      mintAddressA: mint,
+      mintAddressB: mint,
+    };
+
+    const ixs = await buildDepositInstruction(program, proof, extData, admin.publicKey, mint);
+    await expectError(sendBankrunTransaction(banksClient, ixs, admin, []), "UnsupportedMintAddress");
+  });
+
+  it("Registers, stakes, slashes and unstakes a relayer", async () => {
+    const relayer = Keypair.generate();
+    fundAccount(context, relayer.publicKey);
+    const [relayerAccount] = findRelayerPDA(program.programId, relayer.publicKey);
+
+    await executeRegisterRelayer(program, [admin], relayer.publicKey);
+    let account = await program.account.relayerAccount.fetch(relayerAccount);
+    expect(account.active).to.be.true;
+    expect(account.stake.toNumber()).to.equal(0);
+
+    await program.methods
+      .stakeRelayer(new BN(LAMPORTS_PER_SOL))
+      .accountsStrict({ relayerAccount, relayer: relayer.publicKey, systemProgram: SystemProgram.programId })
+      .signers([relayer])
+      .rpc();
+    account = await program.account.relayerAccount.fetch(relayerAccount);
+    expect(account.stake.toNumber()).to.equal(LAMPORTS_PER_SOL);
+
+    const requestUnstake = (amount: number) =>
+      program.methods
+        .requestUnstake(new BN(amount))
+        .accountsStrict({ relayerAccount, relayer: relayer.publicKey })
+        .signers([relayer])
+        .rpc();
+    const unstake = () =>
+      program.methods
+        .unstakeRelayer()
+        .accountsStrict({ relayerAccount, relayer: relayer.publicKey })
+        .signers([relayer])
+        .rpc();
+
+    await expectError(requestUnstake(2 * LAMPORTS_PER_SOL), "InsufficientRelayerStake");
+    await expectError(unstake(), "NoPendingUnstake");
+
+    // Even a listed relayer waits for the cooldown, the requested lamports stop counting as stake
+    await requestUnstake(LAMPORTS_PER_SOL / 2);
+    account = await program.account.relayerAccount.fetch(relayerAccount);
+    expect(account.stake.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+    expect(account.pendingUnstake.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+    const requestedSlot = account.unstakeRequestedSlot.toNumber();
+    await expectError(unstake(), "RelayerUnstakeCooldown");
+
+    // Only the authority slashes, pending lamports first
+    await expectError(
+      program.methods
+        .slashRelayer(new BN(1))
+        .accountsStrict({ globalConfig, relayerAccount, authority: relayer.publicKey })
+        .signers([relayer])
+        .rpc(),
+      "Unauthorized"
+    );
+    await program.methods
+      .setRelayerStatus(false)
+      .accountsStrict({ globalConfig, relayerAccount, authority: admin.publicKey })
+      .rpc();
+    const authorityBalance = await banksClient.getBalance(admin.publicKey);
+    await program.methods
+      .slashRelayer(new BN(LAMPORTS_PER_SOL / 4))
+      .accountsStrict({ globalConfig, relayerAccount, authority: admin.publicKey })
+      .rpc();
+    account = await program.account.relayerAccount.fetch(relayerAccount);
+    expect(account.active).to.be.false;
+    expect(account.stake.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+    expect(account.pendingUnstake.toNumber()).to.equal(LAMPORTS_PER_SOL / 4);
+    // The slashed lamports outweigh the transaction fee the authority paid
+    expect(Number(await banksClient.getBalance(admin.publicKey))).to.be.greaterThan(Number(authorityBalance));
+
+    context.warpToSlot(BigInt(requestedSlot + RELAYER_UNSTAKE_COOLDOWN_SLOTS));
+    const relayerBalance = await banksClient.getBalance(relayer.publicKey);
+    await unstake();
+    account = await program.account.relayerAccount.fetch(relayerAccount);
+    expect(account.stake.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+    expect(account.pendingUnstake.toNumber()).to.equal(0);
+    expect(Number(await banksClient.getBalance(relayer.publicKey))).to.be.greaterThan(Number(relayerBalance));
+  });
+
+  it("Registers a verifying key and writes its vk_ic", async () => {
+    const version = 1;
+    const [verifyingKey] = findVerifyingKeyPDA(program.programId, version);
+    const vkIc = Array.from({ length: 11 }, (_, i) => new Array(64).fill(i));
+
+    await expectError(
+      program.methods
+        .registerVerifyingKey(2, 0, new Array(64).fill(0), new Array(128).fill(0), new Array(128).fill(0), new Array(128).fill(0))
+        .accountsStrict({
+          globalConfig,
+          verifyingKey: findVerifyingKeyPDA(program.programId, 2)[0],
+          authority: admin.publicKey,
+          systemProgram: SystemProgram.programId
+        })
+        .rpc(),
+      "InvalidTreeHeight"
+    );
+
+    await program.methods
+      .registerVerifyingKey(version, DEFAULT_HEIGHT, new Array(64).fill(1), new Array(128).fill(2), new Array(128).fill(3), new Array(128).fill(4))
+      .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+    let key = await program.account.verifyingKeyAccount.fetch(verifyingKey);
+    expect(key.status).to.deep.equal({ pending: {} });
+
+    await expectError(
+      program.methods
+        .writeVerifyingKeyIc(10, vkIc.slice(0, 2))
+        .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey })
+        .rpc(),
+      "InvalidVerifyingKeyIcRange"
+    );
+
+    await program.methods
+      .writeVerifyingKeyIc(0, vkIc)
+      .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey })
+      .rpc();
+
+    await expectError(
+      program.methods
+        .setVerifyingKeyStatus({ pending: {} })
+        .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey })
+        .rpc(),
+      "InvalidVerifyingKeyStatus"
+    );
+
+    await program.methods
+      .setVerifyingKeyStatus({ active: {} })
+      .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey })
+      .rpc();
+    key = await program.account.verifyingKeyAccount.fetch(verifyingKey);
+    expect(key.status).to.deep.equal({ active: {} });
+    expect(key.vkIc[10]).to.deep.equal(vkIc[10]);
+
+    // An active key can no longer be written
+    await expectError(
+      program.methods
+        .writeVerifyingKeyIc(0, vkIc.slice(0, 1))
+        .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey })
+        .rpc(),
+      "VerifyingKeyNotPending"
+    );
+  });
+
+  it("Adds and removes swap programs", async () => {
+    const [swapProgramRegistry] = findSwapProgramRegistryPDA(program.programId);
+
+    await executeAddSwapProgram(program, [admin], JUPITER_PROGRAM_ID);
+    let registry = await program.account.swapProgramRegistry.fetch(swapProgramRegistry);
+    expect(registry.programs.map((id) => id.toBase58())).to.deep.equal([JUPITER_PROGRAM_ID.toBase58()]);
+
+    await advanceSlot(context);
+    await expectError(executeAddSwapProgram(program, [admin], JUPITER_PROGRAM_ID), "SwapProgramAlreadyRegistered");
+
+    await expectError(
+      program.methods
+        .removeSwapProgram(Keypair.generate().publicKey)
+        .accountsStrict({ globalConfig, swapProgramRegistry, authority: admin.publicKey })
+        .rpc(),
+      "UnknownSwapProgram"
+    );
+
+    await program.methods
+      .removeSwapProgram(JUPITER_PROGRAM_ID)
+      .accountsStrict({ globalConfig, swapProgramRegistry, authority: admin.publicKey })
+      .rpc();
+    registry = await program.account.swapProgramRegistry.fetch(swapProgramRegistry);
+    expect(registry.programs).to.be.empty;
+  });
+
+  it("Initializes a nullifier shard", async () => {
+    const shardId = 7;
+    const ix = await buildInitializeNullifierShardInstruction(program, admin.publicKey, shardId);
+    await sendBankrunTransaction(banksClient, ix, admin, []);
+
+    const [nullifierShard] = findNullifierShardPDA(program.programId, shardId);
+    const shard = await program.account.nullifierShard.fetch(nullifierShard);
+    expect(shard.shardId).to.equal(shardId);
+    expect(shard.count).to.equal(0);
+  });
+
+  it("Attaches a root history to the active tree", async () => {
+    const [treeAccount] = findMerkleTreePDA(program.programId, 0);
+    const rent = await banksClient.getRent();
+
+    const createRootHistory = async (capacity: number) => {
+      const rootHistory = Keypair.generate();
+      const space = ROOT_HISTORY_ENTRIES_OFFSET + capacity * ROOT_HISTORY_BYTES_PER_ROOT;
+      const createAccountIx = SystemProgram.createAccount({
+        fromPubkey: admin.publicKey,
+        newAccountPubkey: rootHistory.publicKey,
+        space,
+        lamports: Number(rent.minimumBalance(BigInt(space))),
+        programId: program.programId,
+      });
+      const tx = program.methods
+        .initializeRootHistory()
+        .accountsStrict({ globalConfig, treeAccount, rootHistoryAccount: rootHistory.publicKey, authority: admin.publicKey })
+        .preInstructions([createAccountIx])
+        .signers([rootHistory])
+        .rpc();
+      return { rootHistory: rootHistory.publicKey, tx };
+    };
+
+    // The history must keep at least as many roots as the tree itself
+    await expectError((await createRootHistory(ROOT_HISTORY_SIZE - 1)).tx, "InvalidRootHistoryCapacity");
+
+    const { rootHistory, tx } = await createRootHistory(1_000);
+    await tx;
+
+    const tree = await program.account.merkleTreeAccount.fetch(treeAccount);
+    expect(tree.rootHistoryAccount.equals(rootHistory)).to.be.true;
+    const history = await program.account.rootHistoryAccount.fetch(rootHistory);
+    expect(history.tree.equals(treeAccount)).to.be.true;
+    expect(history.capacity).to.equal(1_000);
+    expect(history.count).to.be.greaterThan(0);
+    expect(history.head).to.equal(history.count);
+
+    await expectError((await createRootHistory(1_000)).tx, "RootHistoryAlreadyAttached");
+  });
+
+  it("Rolls over and retires trees", async () => {
+    const [currentTree] = findMerkleTreePDA(program.programId, 0);
+    const [newTree] = findMerkleTreePDA(program.programId, 1);
+    const [genesisKey] = findVerifyingKeyPDA(program.programId, 0);
+
+    // Circuit of a shallower tree, e.g. for a test pool
+    const shallowHeight = 20;
+    const [shallowKey] = findVerifyingKeyPDA(program.programId, 3);
+    await program.methods
+      .registerVerifyingKey(3, shallowHeight, new Array(64).fill(1), new Array(128).fill(2), new Array(128).fill(3), new Array(128).fill(4))
+      .accountsStrict({ globalConfig, verifyingKey: shallowKey, authority: admin.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+    await program.methods
+      .writeVerifyingKeyIc(0, Array.from({ length: 11 }, (_, i) => new Array(64).fill(i)))
+      .accountsStrict({ globalConfig, verifyingKey: shallowKey, authority: admin.publicKey })
+      .rpc();
+    await program.methods
+      .setVerifyingKeyStatus({ active: {} })
+      .accountsStrict({ globalConfig, verifyingKey: shallowKey, authority: admin.publicKey })
+      .rpc();
+
+    await expectError(
+      program.methods
+        .retireTree()
+        .accountsStrict({ globalConfig, treeAccount: currentTree, authority: admin.publicKey })
+        .rpc(),
+      "CannotRetireActiveTree"
+    );
+
+    // Anyone may roll over a full tree, only the authority a tree with room left
+    const stranger = Keypair.generate();
+    fundAccount(context, stranger.publicKey);
+    await expectError(
+      program.methods
+        .rolloverTree(DEFAULT_HEIGHT, ROOT_HISTORY_SIZE)
+        .accountsStrict({
+          globalConfig,
+          currentTreeAccount: currentTree,
+          verifyingKey: genesisKey,
+          newTreeAccount: newTree,
+          payer: stranger.publicKey,
+          systemProgram: SystemProgram.programId
+        })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    // The new tree's height must be the one of the verifying key's circuit
+    await expectError(
+      program.methods
+        .rolloverTree(shallowHeight, 50)
+        .accountsStrict({
+          globalConfig,
+          currentTreeAccount: currentTree,
+          verifyingKey: genesisKey,
+          newTreeAccount: newTree,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId
+        })
+        .rpc(),
+      "TreeHeightMismatch"
+    );
+
+    await program.methods
+      .rolloverTree(shallowHeight, 50)
+      .accountsStrict({
+        globalConfig,
+        currentTreeAccount: currentTree,
+        verifyingKey: shallowKey,
+        newTreeAccount: newTree,
+        payer: admin.publicKey,
+        systemProgram: SystemProgram.programId
+      })
+      .rpc();
+
+    const config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.activeTreeId.toNumber()).to.equal(1);
+    const tree = await program.account.merkleTreeAccount.fetch(newTree);
+    expect(tree.treeId.toNumber()).to.equal(1);
+    expect(tree.nextIndex.toNumber()).to.equal(0);
+    expect(tree.height).to.equal(shallowHeight);
+    expect(tree.rootHistorySize).to.equal(50);
+    expect(tree.rootHistoryAccount.equals(PublicKey.default)).to.be.true;
+
+    // Notes of the old tree are spent against the root history attached to it
+    const mint = await createMint({
+      banksClient,
+      payer: admin,
+      admin,
+      lastBlockhash: context.lastBlockhash,
+      decimals: 6,
+    });
+    await executeRegisterMint(program, [admin], mint, new BN(1_000_000));
+    const reserveTokenAccount = getAssociatedTokenAddressSync(mint, globalConfig, true);
+    const userTokenAccount = getAssociatedTokenAddressSync(mint, admin.publicKey);
+    await sendBankrunTransaction(
+      banksClient,
+      [
+        createAssociatedTokenAccountInstruction(admin.publicKey, reserveTokenAccount, globalConfig, mint),
+        createAssociatedTokenAccountInstruction(admin.publicKey, userTokenAccount, admin.publicKey, mint),
+      ],
+      admin,
+      []
+    );
+
+    // The root is checked before the ext data and the proof, so neither needs to be valid
+    const oldTree = await program.account.merkleTreeAccount.fetch(currentTree);
+    const proof = {
+      proofA: new Array(64).fill(0),
+      proofB: new Array(128).fill(0),
+      proofC: new Array(64).fill(0),
+      root: oldTree.root,
+      publicAmount0: new Array(32).fill(0),
+      publicAmount1: new Array(32).fill(0),
+      extDataHash: new Array(32).fill(0),
+      inputNullifiers: [[...new Array(31).fill(0), 211], [...new Array(31).fill(0), 212]],
+      outputCommitments: [new Array(32).fill(0), new Array(32).fill(0)],
+    };
+    const accounts = await findTransactionAccounts(program, proof, 0, 0);
+    expect(accounts.rootTreeAccount?.equals(currentTree)).to.be.true;
+    expect(accounts.rootTreeHistoryAccount?.equals(oldTree.rootHistoryAccount)).to.be.true;
+    const depositToOldTree = async (rootTreeHistoryAccount: PublicKey | null) =>
+      program.methods
+        .deposit(proof, { extAmount: new BN(1_000), fee: new BN(0) }, Buffer.from("oldTreeOutput"))
+        .accountsStrict({
+          ...accounts,
+          rootTreeHistoryAccount,
+          inputMint: mint,
+          mintConfig: findMintConfigPDA(program.programId, mint)[0],
+          reserveTokenAccount,
+          feeRecipientAccount: admin.publicKey,
+          userTokenAccount,
+          user: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .preInstructions(await buildMissingNullifierShardInstructions(program, proof, admin.publicKey))
+        .rpc();
+
+    await expectError(depositToOldTree(null), "MissingRootHistoryAccount");
+    // The root is found, so the deposit only fails on the ext data hash
+    await expectError(depositToOldTree(accounts.rootTreeHistoryAccount), "ExtDataHashMismatch");
+
+    await program.methods
+      .retireTree()
+      .accountsStrict({ globalConfig, treeAccount: currentTree, authority: admin.publicKey })
+      .rpc();
+    expect((await program.account.merkleTreeAccount.fetch(currentTree)).retired).to.equal(1);
+  });
+});
+
+describe("legacy migration", () => {
+  let context: ProgramTestContext;
+  let program: Program<Zkcash>;
+  let admin: Keypair;
+
+  // Layout of LegacyMerkleTreeAccount in programs/zert/src/legacy.rs
+  const LEGACY_ROOT_HISTORY_SIZE = 100;
+  const LEGACY_TREE_SPACE = 8 + 32 + 8 + DEFAULT_HEIGHT * 32 + 32 + LEGACY_ROOT_HISTORY_SIZE * 32 + 8 + 8 + 8;
+
+  before(async () => {
+    ({ context, program } = await startProgram());
+    admin = context.payer;
+  });
+
+  it("Migrates a legacy global config", async () => {
+    const [globalConfig, bump] = findGlobalConfigPDA(program.programId);
+    const data = Buffer.alloc(8 + 40);
+    accountDiscriminator("GlobalConfig").copy(data, 0);
+    admin.publicKey.toBuffer().copy(data, 8);
+    data.writeUInt16LE(10, 40); // deposit_fee_rate
+    data.writeUInt16LE(35, 42); // withdrawal_fee_rate
+    data.writeUInt16LE(300, 44); // fee_error_margin
+    data.writeUInt8(bump, 46);
+    context.setAccount(globalConfig, { lamports: LAMPORTS_PER_SOL, data, owner: program.programId, executable: false });
+
+    const [verifyingKey] = findVerifyingKeyPDA(program.programId, 0);
+    await program.methods
+      .migrateGlobalConfig()
+      .accountsStrict({ globalConfig, verifyingKey, authority: admin.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    const config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.version).to.equal(1);
+    expect(config.authority.equals(admin.publicKey)).to.be.true;
+    expect(config.depositFeeRate).to.equal(10);
+    expect(config.withdrawalFeeRate).to.equal(35);
+    expect(config.feeErrorMargin).to.equal(300);
+    expect(config.activeTreeId.toNumber()).to.equal(0);
+
+    const genesisKey = await program.account.verifyingKeyAccount.fetch(verifyingKey);
+    expect(genesisKey.status).to.deep.equal({ active: {} });
+    expect(genesisKey.treeHeight).to.equal(DEFAULT_HEIGHT);
+  });
+
+  it("Migrates a legacy merkle tree to tree 0", async () => {
+    const [legacyTreeAccount, bump] = findLegacyMerkleTreePDA(program.programId);
+    const [treeAccount] = findMerkleTreePDA(program.programId, 0);
+    const root = Buffer.from(ZERO_BYTES[DEFAULT_HEIGHT]);
+
+    // An empty legacy tree: its subtrees are the zero bytes and its only root is the empty root
+    const data = Buffer.alloc(LEGACY_TREE_SPACE);
+    let offset = 0;
+    accountDiscriminator("MerkleTreeAccount").copy(data, offset);
+    offset += 8;
+    admin.publicKey.toBuffer().copy(data, offset);
+    offset += 32 + 8; // authority, next_index
+    for (let level = 0; level < DEFAULT_HEIGHT; level++) {
+      Buffer.from(ZERO_BYTES[level]).copy(data, offset + level * 32);
+    }
+    offset += DEFAULT_HEIGHT * 32;
+    root.copy(data, offset);
+    root.copy(data, offset + 32); // root_history[0]
+    offset += 32 + LEGACY_ROOT_HISTORY_SIZE * 32 + 8; // root, root_history, root_index
+    data.writeBigUInt64LE(BigInt(1_000_000), offset); // max_deposit_amount
+    offset += 8;
+    data.writeUInt8(DEFAULT_HEIGHT, offset);
+    data.writeUInt8(LEGACY_ROOT_HISTORY_SIZE, offset + 1);
+    data.writeUInt8(bump, offset + 2);
+    context.setAccount(legacyTreeAccount, { lamports: LAMPORTS_PER_SOL, data, owner: program.programId, executable: false });
+
+    const stranger = Keypair.generate();
+    fundAccount(context, stranger.publicKey);
+    await expectError(
+      program.methods
+        .migrateLegacyTree()
+        .accountsStrict({ legacyTreeAccount, treeAccount, authority: stranger.publicKey, systemProgram: SystemProgram.programId })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await program.methods
+      .migrateLegacyTree()
+      .accountsStrict({ legacyTreeAccount, treeAccount, authority: admin.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    const tree = await program.account.merkleTreeAccount.fetch(treeAccount);
+    expect(tree.treeId.toNumber()).to.equal(0);
+    expect(tree.nextIndex.toNumber()).to.equal(0);
+    expect(tree.height).to.equal(DEFAULT_HEIGHT);
+    expect(tree.rootHistorySize).to.equal(LEGACY_ROOT_HISTORY_SIZE);
+    expect(Buffer.from(tree.root).equals(root)).to.be.true;
+
+    // The legacy tree is closed so it can't be migrated twice
+    expect(await context.banksClient.getAccount(legacyTreeAccount)).to.be.null;
+  });
+});
@@ -27,7 +27,7 @@ import { Utxo } from "./lib/utxo";
 import { DEFAULT_HEIGHT, FIELD_SIZE, ROOT_HISTORY_SIZE, ZERO_BYTES, DEPOSIT_FEE_RATE, WITHDRAW_FEE_RATE } from "./lib/constants";
 import { getExtDataHash, getSwapExtDataHash, publicKeyToFieldElement } from "./lib/utils";
 import { parseProofToBytesArray, parseToBytesArray, prove } from "./lib/prover";
-import { findCommitmentPDAs, findGlobalConfigPDA, findNullifierPDAs, findTreeTokenAccountPDA } from "./lib/derive";
+import { findGlobalConfigPDA, findTreeTokenAccountPDA } from "./lib/derive";
 import path from "path";
 import { createMint } from "./lib/token";
 import { createGlobalTestALT, createVersionedTransactionWithALT, getTestProtocolAddresses } from "./lib/test_alt";
@@ -83,8 +83,7 @@ describe("bankrun", () => {
     const depositExtData: ExtData = {
       recipient: getAssociatedTokenAddressSync(mintAddressA, globalConfig, true),
       extAmount: new anchor.BN(depositAmount),
-      encryptedOutput1: Buffer.from("depositEncryptedOutput1"),
-      encryptedOutput2: Buffer.from("depositEncryptedOutput2"),
+      encryptedOutput: Buffer.from("depositEncryptedOutput"),
       fee: depositFee,
       feeRecipient: feeRecipient.publicKey,
       mintAddressA: mintAddressA,
@@ -185,15 +184,12 @@ describe("bankrun", () => {
 
     await createAtaBankrun(banksClient, admin, recipient.publicKey, mintAddressA); 
     const withdrawExtData: ExtData = {
-      recipient: getAssociatedTokenAddressSync(
-        mintAddressA,
-        recipient.publicKey
-      ),
+      recipient: recipient.publicKey,
       extAmount: new anchor.BN(-withdrawalAmount),
-      encryptedOutput1: Buffer.from("withdrawEncryptedOutput1"),
-      encryptedOutput2: Buffer.from("withdrawEncryptedOutput2"),
+      encryptedOutput: Buffer.from("withdrawEncryptedOutput"),
       fee: withdrawalFee,
-      feeRecipient: feeRecipient.publicKey,
+      // The withdrawal fee goes to a token account of the relayer submitting the transaction
+      feeRecipient: getAssociatedTokenAddressSync(mintAddressA, admin.publicKey),
       mintAddressA: mintAddressA,
       mintAddressB: mintAddressA,
     };
@@ -319,8 +315,7 @@ describe("bankrun", () => {
     const topUpExtData: ExtData = {
       recipient: getAssociatedTokenAddressSync(mintAddressA, globalConfig, true),
       extAmount: new anchor.BN(topUpAmount),
-      encryptedOutput1: Buffer.from("topUpEncryptedOutput1"),
-      encryptedOutput2: Buffer.from("topUpEncryptedOutput2"),
+      encryptedOutput: Buffer.from("topUpEncryptedOutput"),
       fee: topUpFee,
       feeRecipient: feeRecipient.publicKey,
       mintAddressA: mintAddressA,
@@ -476,8 +471,7 @@ describe("bankrun", () => {
   //     recipient: reserveTokenAccountOutput,
   //     extAmount: new anchor.BN(-swapAmountIn), // Pure swap, no deposit/withdrawal
   //     extMinAmountOut: new anchor.BN(swapMinAmountOut), // TODO
-  //     encryptedOutput1: Buffer.from("swapEncryptedOutput1"),
-  //     encryptedOutput2: Buffer.from("swapEncryptedOutput2"),
+  //     encryptedOutput: Buffer.from("swapEncryptedOutput"),
   //     fee: swapFee,
   //     feeRecipient: feeRecipient.publicKey,
   //     mintAddressA: mintAddressA,
@@ -1,16 +1,28 @@
 import * as anchor from "@coral-xyz/anchor";
-import { createAssociatedTokenAccountInstruction, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID, createTransferInstruction } from "@solana/spl-token";
+import { createAssociatedTokenAccountInstruction, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID, createTransferInstruction, NATIVE_MINT } from "@solana/spl-token";
 import { ComputeBudgetProgram, Keypair, LAMPORTS_PER_SOL, PublicKey, Transaction, TransactionInstruction } from "@solana/web3.js";
 import { Zkcash } from "../target/types/zkcash";
 import {
   findNullifierPDAs,
+  findNullifierShardPDA,
+  findNullifierShardPDAs,
   findMerkleTreePDA,
   findTreeTokenAccountPDA,
-  findGlobalConfigPDA
+  findGlobalConfigPDA,
+  findVerifyingKeyPDA,
+  findMintConfigPDA,
+  findRelayerPDA,
+  findSwapProgramRegistryPDA,
+  findWsolUnwrapPDA,
+  findEventAuthorityPDA
 } from "./lib/derive";
 import { BanksClient, ProgramTestContext } from "solana-bankrun";
 import { createMint } from "./lib/token";
-import { SwapData } from "./lib/types";
+import { DualExtData, SwapData } from "./lib/types";
+import { DEFAULT_HEIGHT, ROOT_HISTORY_SIZE } from "./lib/constants";
+
+// Jupiter program ID, registered as a swap program in the tests
+export const JUPITER_PROGRAM_ID = new PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 
 /**
  * Proof structure for ZK verification
@@ -33,8 +45,7 @@ export interface Proof {
 export interface ExtData {
   recipient: PublicKey;
   extAmount: anchor.BN;
-  encryptedOutput1: Buffer;
-  encryptedOutput2: Buffer;
+  encryptedOutput: Buffer;
   fee: anchor.BN;
   feeRecipient: PublicKey;
 }
@@ -56,6 +67,32 @@ export interface SwapExtDataMinified {
   fee: anchor.BN;
 }
 
+/**
+ * Minified dual-mint external data structure (for on-chain)
+ */
+export interface DualExtDataMinified {
+  extAmount0: anchor.BN;
+  extAmount1: anchor.BN;
+  fee0: anchor.BN;
+  fee1: anchor.BN;
+}
+
+/**
+ * Optional global config updates, fields left undefined are kept
+ */
+export interface GlobalConfigUpdate {
+  depositFeeRate?: number | null;
+  withdrawalFeeRate?: number | null;
+  feeErrorMargin?: number | null;
+  swapFeeRate?: number | null;
+  maxSwapSurplusRate?: number | null;
+  guardian?: PublicKey | null;
+  outflowWindowSlots?: anchor.BN | null;
+  minRelayerStake?: anchor.BN | null;
+  oracleProgram?: PublicKey | null;
+  rootValiditySlots?: anchor.BN | null;
+}
+
 /**
  * Helper function to create ExtDataMinified from ExtData
  * @param extData - Full ExtData object
@@ -71,7 +108,7 @@ export function createExtDataMinified(extData: ExtData): ExtDataMinified {
 /**
  * Helper function to create SwapExtDataMinified from SwapData
  * @param swapData - Full SwapData object
- * @returns Minified SwapData with extAmount, extMinAmountOut, fee, and feeRecipient
+ * @returns Minified SwapData with extAmount, extMinAmountOut and fee
  */
 export function createSwapExtDataMinified(swapData: SwapData): SwapExtDataMinified {
   return {
@@ -82,60 +119,143 @@ export function createSwapExtDataMinified(swapData: SwapData): SwapExtDataMinifi
 }
 
 /**
- * Build transact instruction
+ * Helper function to create DualExtDataMinified from DualExtData
+ * @param extData - Full DualExtData object
+ * @returns Minified DualExtData with both amounts and fees
+ */
+export function createDualExtDataMinified(extData: DualExtData): DualExtDataMinified {
+  return {
+    extAmount0: extData.extAmount0,
+    extAmount1: extData.extAmount1,
+    fee0: extData.fee0,
+    fee1: extData.fee1,
+  };
+}
+
+/**
+ * Derive the accounts shared by every transaction instruction
+ *
+ * Proofs are checked against the active tree unless they spend notes of an older tree, given by
+ * `rootTreeId`. Each tree's root history account is passed once one is attached to it.
+ * @param program - Anchor program instance
+ * @param proof - ZK proof data
+ * @param verifyingKeyVersion - Version of the circuit the proof was generated with
+ * @param rootTreeId - Id of the tree the proof root belongs to, the active tree when null
+ * @returns Tree, nullifier, config, verifying key and event accounts
+ */
+export async function findTransactionAccounts(
+  program: anchor.Program<Zkcash>,
+  proof: Proof,
+  verifyingKeyVersion: number = 0,
+  rootTreeId: number | null = null
+) {
+  const [globalConfig] = findGlobalConfigPDA(program.programId);
+  const { activeTreeId } = await program.account.globalConfig.fetch(globalConfig);
+  const [treeAccount] = findMerkleTreePDA(program.programId, activeTreeId);
+  const { rootHistoryAccount } = await program.account.merkleTreeAccount.fetch(treeAccount);
+  const [rootTreeAccount] = rootTreeId === null || activeTreeId.eqn(rootTreeId)
+    ? [null]
+    : findMerkleTreePDA(program.programId, rootTreeId);
+  const rootTreeHistoryAccount = rootTreeAccount
+    ? (await program.account.merkleTreeAccount.fetch(rootTreeAccount)).rootHistoryAccount
+    : PublicKey.default;
+  const shards = findNullifierShardPDAs(program, proof);
+  const legacyNullifiers = findNullifierPDAs(program, proof);
+  const [verifyingKey] = findVerifyingKeyPDA(program.programId, verifyingKeyVersion);
+  const [eventAuthority] = findEventAuthorityPDA(program.programId);
+
+  return {
+    treeAccount,
+    rootTreeAccount,
+    rootHistoryAccount: rootHistoryAccount.equals(PublicKey.default) ? null : rootHistoryAccount,
+    rootTreeHistoryAccount: rootTreeHistoryAccount.equals(PublicKey.default) ? null : rootTreeHistoryAccount,
+    nullifierShard0: shards.nullifierShard0PDA,
+    nullifierShard1: shards.nullifierShard1PDA,
+    legacyNullifier0: legacyNullifiers.nullifier0PDA,
+    legacyNullifier1: legacyNullifiers.nullifier1PDA,
+    globalConfig,
+    verifyingKey,
+    eventAuthority,
+    program: program.programId,
+  };
+}
+
+/**
+ * Build the instructions creating the nullifier shards of a proof that do not exist yet
+ * @param program - Anchor program instance
+ * @param proof - ZK proof data
+ * @param payer - Payer of the new shards
+ * @returns Transaction instructions, empty when both shards exist
+ */
+export async function buildMissingNullifierShardInstructions(
+  program: anchor.Program<Zkcash>,
+  proof: Proof,
+  payer: PublicKey
+): Promise<TransactionInstruction[]> {
+  const shardIds = [...new Set(proof.inputNullifiers.map((nullifier) => nullifier[31]))];
+  const ixs = [];
+
+  for (const shardId of shardIds) {
+    const [nullifierShard] = findNullifierShardPDA(program.programId, shardId);
+    if (await program.account.nullifierShard.fetchNullable(nullifierShard)) {
+      continue;
+    }
+    ixs.push(await buildInitializeNullifierShardInstruction(program, payer, shardId));
+  }
+
+  return ixs;
+}
+
+/**
+ * Build deposit instruction
  * @param program - Anchor program instance
  * @param proof - ZK proof data
  * @param extData - External data (recipient, amount, fee, etc.)
  * @param signer - Transaction signer public key
- * @returns Transaction instruction
+ * @param inputMint - Token mint address, WSOL deposits are wrapped from the signer's SOL
+ * @returns Transaction instructions, preceded by the creation of missing nullifier shards
  */
 export async function buildDepositInstruction(
   program: anchor.Program<Zkcash>,
   proof: Proof,
   extData: ExtData,
   signer: PublicKey,
-  inputMint: PublicKey
+  inputMint: PublicKey,
+  tokenProgram: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<TransactionInstruction[]> {
-  // Derive all necessary PDAs
-  const [treeAccount] = findMerkleTreePDA(program.programId);
-  const [treeTokenAccount] = findTreeTokenAccountPDA(program.programId);
-  const [globalConfig] = findGlobalConfigPDA(program.programId);
-  const nullifiers = findNullifierPDAs(program, proof);
+  const accounts = await findTransactionAccounts(program, proof);
+  const [mintConfig] = findMintConfigPDA(program.programId, inputMint);
 
   // Derive reserve token accounts
   const reserveTokenAccount = getAssociatedTokenAddressSync(
     inputMint,
-    globalConfig,
-    true
-  );
-  const userTokenAccount = getAssociatedTokenAddressSync(
-    inputMint,
-    signer,
-    true
+    accounts.globalConfig,
+    true,
+    tokenProgram
   );
+  const userTokenAccount = inputMint.equals(NATIVE_MINT)
+    ? null
+    : getAssociatedTokenAddressSync(inputMint, signer, true, tokenProgram);
 
-  const ixs = [];
+  const ixs = await buildMissingNullifierShardInstructions(program, proof, signer);
 
   // Build instruction
   const instruction = await program.methods
     .deposit(
       proof,
       createExtDataMinified(extData),
-      extData.encryptedOutput1,
-      extData.encryptedOutput2
+      extData.encryptedOutput
     )
     .accountsStrict({
-      treeAccount,
-      nullifier0: nullifiers.nullifier0PDA,
-      nullifier1: nullifiers.nullifier1PDA,
-      globalConfig,
+      ...accounts,
       inputMint: inputMint,
+      mintConfig,
       reserveTokenAccount: reserveTokenAccount,
       feeRecipientAccount: extData.feeRecipient,
       userTokenAccount: userTokenAccount,
       user: signer,
       systemProgram: anchor.web3.SystemProgram.programId,
-      tokenProgram: TOKEN_PROGRAM_ID
+      tokenProgram
     })
     .instruction();
 
@@ -148,60 +268,114 @@ export async function buildDepositInstruction(
  * Build withdraw instruction
  * @param program - Anchor program instance
  * @param proof - ZK proof data
- * @param extData - External data (recipient, amount, fee, etc.)
- * @param signer - Transaction signer public key
- * @param inputMint - Token mint address
- * @returns Transaction instruction
+ * @param extData - External data (recipient, amount, fee, etc.), the fee recipient is a token account of the relayer
+ * @param signer - Relayer public key, must be a registered relayer
+ * @param inputMint - Token mint address, WSOL is paid out as native SOL
+ * @returns Transaction instructions, preceded by the creation of missing nullifier shards
  */
 export async function buildWithdrawInstruction(
   program: anchor.Program<Zkcash>,
   proof: Proof,
   extData: ExtData,
   signer: PublicKey,
-  inputMint: PublicKey
+  inputMint: PublicKey,
+  tokenProgram: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<TransactionInstruction[]> {
-  // Derive all necessary PDAs
-  const [treeAccount] = findMerkleTreePDA(program.programId);
-  const [globalConfig] = findGlobalConfigPDA(program.programId);
-  const nullifiers = findNullifierPDAs(program, proof);
+  const accounts = await findTransactionAccounts(program, proof);
+  const [mintConfig] = findMintConfigPDA(program.programId, inputMint);
+  const [relayerAccount] = findRelayerPDA(program.programId, signer);
+  const isNative = inputMint.equals(NATIVE_MINT);
 
   // Derive reserve token account
   const reserveTokenAccount = getAssociatedTokenAddressSync(
     inputMint,
-    globalConfig,
-    true
+    accounts.globalConfig,
+    true,
+    tokenProgram
   );
 
-  // Derive recipient token account
-  const recipientTokenAccount = getAssociatedTokenAddressSync(
-    inputMint,
-    extData.recipient,
-    true
-  );
+  // Derive recipient token account, created by the program when it does not exist
+  const recipientTokenAccount = isNative
+    ? null
+    : getAssociatedTokenAddressSync(inputMint, extData.recipient, true, tokenProgram);
+  const wsolUnwrapAccount = isNative ? findWsolUnwrapPDA(program.programId, proof)[0] : null;
 
-  const ixs = [];
+  const ixs = await buildMissingNullifierShardInstructions(program, proof, signer);
 
   // Build instruction
   const instruction = await program.methods
     .withdraw(
       proof,
       createExtDataMinified(extData),
-      extData.encryptedOutput1,
-      extData.encryptedOutput2
+      extData.encryptedOutput
     )
     .accountsStrict({
-      treeAccount,
-      nullifier0: nullifiers.nullifier0PDA,
-      nullifier1: nullifiers.nullifier1PDA,
-      globalConfig,
+      ...accounts,
       inputMint: inputMint,
+      mintConfig,
       reserveTokenAccount: reserveTokenAccount,
+      recipient: extData.recipient,
       recipientTokenAccount: recipientTokenAccount,
+      wsolUnwrapAccount,
       feeRecipientAccount: extData.feeRecipient,
-      recipient: extData.recipient,
+      relayerAccount,
       relayer: signer,
       systemProgram: anchor.web3.SystemProgram.programId,
-      tokenProgram: TOKEN_PROGRAM_ID
+      tokenProgram,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID
+    })
+    .instruction();
+
+  ixs.push(instruction);
+
+  return ixs;
+}
+
+/**
+ * Build shielded transfer instruction
+ * @param program - Anchor program instance
+ * @param proof - ZK proof data
+ * @param extData - External data (amount, fee, etc.), the fee recipient is a token account of the relayer
+ * @param signer - Relayer public key, must be a registered relayer
+ * @param inputMint - Token mint address the fee is paid in
+ * @returns Transaction instructions, preceded by the creation of missing nullifier shards
+ */
+export async function buildShieldedTransferInstruction(
+  program: anchor.Program<Zkcash>,
+  proof: Proof,
+  extData: ExtData,
+  signer: PublicKey,
+  inputMint: PublicKey,
+  tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+): Promise<TransactionInstruction[]> {
+  const accounts = await findTransactionAccounts(program, proof);
+  const [mintConfig] = findMintConfigPDA(program.programId, inputMint);
+  const [relayerAccount] = findRelayerPDA(program.programId, signer);
+  const reserveTokenAccount = getAssociatedTokenAddressSync(
+    inputMint,
+    accounts.globalConfig,
+    true,
+    tokenProgram
+  );
+
+  const ixs = await buildMissingNullifierShardInstructions(program, proof, signer);
+
+  const instruction = await program.methods
+    .shieldedTransfer(
+      proof,
+      createExtDataMinified(extData),
+      extData.encryptedOutput
+    )
+    .accountsStrict({
+      ...accounts,
+      inputMint,
+      mintConfig,
+      reserveTokenAccount,
+      feeRecipientAccount: extData.feeRecipient,
+      relayerAccount,
+      relayer: signer,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram
     })
     .instruction();
 
@@ -214,13 +388,15 @@ export async function buildWithdrawInstruction(
  * Build swap instruction
  * @param program - Anchor program instance
  * @param proof - ZK proof data
- * @param extData - External data (recipient, amount, fee, etc.)
- * @param signer - Transaction signer public key
+ * @param swapData - External data (amounts, fee, etc.), the fee recipient is an output token account of the signer
+ * @param signer - Transaction signer public key, must be a registered relayer
  * @param inputMint - Input token mint address
  * @param outputMint - Output token mint address
- * @param jupiterSwapData - Optional Jupiter swap data for testing
- * @param jupiterRemainingAccounts - Optional Jupiter remaining accounts for testing
- * @returns Transaction instruction
+ * @param jupiterSwapData - Swap instruction data forwarded to the swap program
+ * @param jupiterRemainingAccounts - Optional swap program remaining accounts
+ * @param priceUpdates - Price update accounts, required when the mints have a price feed
+ * @param swapProgram - Registered swap program the swap is routed through
+ * @returns Transaction instructions, preceded by the creation of missing nullifier shards
  */
 export async function buildSwapInstruction(
   program: anchor.Program<Zkcash>,
@@ -230,69 +406,64 @@ export async function buildSwapInstruction(
   inputMint: PublicKey,
   outputMint: PublicKey,
   jupiterSwapData: Buffer,
-  jupiterRemainingAccounts?: any[]
+  jupiterRemainingAccounts?: any[],
+  priceUpdates?: { input: PublicKey | null; output: PublicKey | null },
+  swapProgram: PublicKey = JUPITER_PROGRAM_ID
 ): Promise<TransactionInstruction[]> {
-  // Derive all necessary PDAs
-  const [treeAccount] = findMerkleTreePDA(program.programId);
-  const [globalConfig] = findGlobalConfigPDA(program.programId);
-  const nullifiers = findNullifierPDAs(program, proof);
+  const accounts = await findTransactionAccounts(program, proof);
+  const [inputMintConfig] = findMintConfigPDA(program.programId, inputMint);
+  const [outputMintConfig] = findMintConfigPDA(program.programId, outputMint);
+  const [relayerAccount] = findRelayerPDA(program.programId, signer);
+  const [swapProgramRegistry] = findSwapProgramRegistryPDA(program.programId);
 
   // Derive reserve token accounts for both mints
   const reserveTokenAccountInput = getAssociatedTokenAddressSync(
     inputMint,
-    globalConfig,
+    accounts.globalConfig,
     true
   );
 
   const reserveTokenAccountOutput = getAssociatedTokenAddressSync(
     outputMint,
-    globalConfig,
+    accounts.globalConfig,
     true
   );
 
-  const userInputTokenAccount = getAssociatedTokenAddressSync(
+  // The swap fee is paid upfront in the input token to the signer
+  const inputFeeRecipientAccount = getAssociatedTokenAddressSync(
     inputMint,
     signer,
     true
   );
-  const userOutputTokenAccount = getAssociatedTokenAddressSync(
-    outputMint,
-    signer,
-    true
-  );
 
-  const ixs = [];
+  const ixs = await buildMissingNullifierShardInstructions(program, proof, signer);
 
-  // Jupiter program ID (required account even when not using Jupiter)
-  const jupiterProgramId = new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
-  console.log("jupiterSwapData length:", jupiterSwapData);
-
-  
-  // Build instruction
-  // Create mock Jupiter swap data if not provided
-  
   let instructionBuilder = program.methods
     .swap(
       proof,
       createSwapExtDataMinified(swapData),
-      swapData.encryptedOutput1,
-      swapData.encryptedOutput2,
+      swapData.encryptedOutput,
       jupiterSwapData
     )
     .accountsStrict({
-      treeAccount,
-      nullifier0: nullifiers.nullifier0PDA,
-      nullifier1: nullifiers.nullifier1PDA,
-      globalConfig,
+      ...accounts,
       inputMint: inputMint,
       outputMint: outputMint,
+      inputMintConfig,
+      outputMintConfig,
       reserveTokenAccountInput: reserveTokenAccountInput,
       reserveTokenAccountOutput: reserveTokenAccountOutput,
       feeRecipientAccount: swapData.feeRecipient,
-      jupiterProgram: jupiterProgramId,
+      inputFeeRecipientAccount,
+      inputPriceUpdate: priceUpdates?.input ?? null,
+      outputPriceUpdate: priceUpdates?.output ?? null,
+      relayerAccount,
+      swapProgramRegistry,
+      jupiterProgram: swapProgram,
       user: signer,
       systemProgram: anchor.web3.SystemProgram.programId,
-      tokenProgram: TOKEN_PROGRAM_ID
+      inputTokenProgram: TOKEN_PROGRAM_ID,
+      outputTokenProgram: TOKEN_PROGRAM_ID
     });
 
   // Add remaining accounts if provided
@@ -308,62 +479,141 @@ export async function buildSwapInstruction(
 }
 
 /**
- * 
- * Execute update deposit limit instruction
+ * Build dual-mint deposit instruction
  * @param program - Anchor program instance
- * @param newLimit - New deposit limit in lamports
- * @param signers - Array of signers (should include authority)
- * @param preInstructions - Optional pre-instructions
- * @returns Transaction signature
+ * @param proof - ZK proof data
+ * @param extData - Dual external data, mintAddressA is deposited as leg 0 and mintAddressB as leg 1
+ * @param signer - Transaction signer public key
+ * @returns Transaction instructions, preceded by the creation of missing nullifier shards
  */
-export async function executeUpdateDepositLimit(
+export async function buildDepositDualInstruction(
   program: anchor.Program<Zkcash>,
-  newLimit: anchor.BN,
-  signers: anchor.web3.Keypair[],
-  preInstructions?: TransactionInstruction[]
-): Promise<string> {
-  const [treeAccount] = findMerkleTreePDA(program.programId);
+  proof: Proof,
+  extData: DualExtData,
+  signer: PublicKey
+): Promise<TransactionInstruction[]> {
+  const accounts = await findTransactionAccounts(program, proof);
+  const [mintConfig0] = findMintConfigPDA(program.programId, extData.mintAddressA);
+  const [mintConfig1] = findMintConfigPDA(program.programId, extData.mintAddressB);
 
-  const txBuilder = program.methods
-    .updateDepositLimit(newLimit)
-    .accounts({
-      treeAccount,
-      authority: signers[0].publicKey
+  const ixs = await buildMissingNullifierShardInstructions(program, proof, signer);
+
+  const instruction = await program.methods
+    .depositDual(
+      proof,
+      createDualExtDataMinified(extData),
+      extData.encryptedOutput
+    )
+    .accountsStrict({
+      ...accounts,
+      inputMint0: extData.mintAddressA,
+      inputMint1: extData.mintAddressB,
+      mintConfig0,
+      mintConfig1,
+      reserveTokenAccount0: getAssociatedTokenAddressSync(extData.mintAddressA, accounts.globalConfig, true),
+      reserveTokenAccount1: getAssociatedTokenAddressSync(extData.mintAddressB, accounts.globalConfig, true),
+      feeRecipientAccount0: extData.feeRecipient0,
+      feeRecipientAccount1: extData.feeRecipient1,
+      userTokenAccount0: getAssociatedTokenAddressSync(extData.mintAddressA, signer, true),
+      userTokenAccount1: getAssociatedTokenAddressSync(extData.mintAddressB, signer, true),
+      user: signer,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram0: TOKEN_PROGRAM_ID,
+      tokenProgram1: TOKEN_PROGRAM_ID
     })
-    .signers(signers);
+    .instruction();
 
-  if (preInstructions && preInstructions.length > 0) {
-    txBuilder.preInstructions(preInstructions);
-  }
+  ixs.push(instruction);
 
-  return await txBuilder.rpc();
+  return ixs;
+}
+
+/**
+ * Build dual-mint withdraw instruction
+ * @param program - Anchor program instance
+ * @param proof - ZK proof data
+ * @param extData - Dual external data, the fee recipients are token accounts of the relayer
+ * @param signer - Relayer public key, must be a registered relayer
+ * @returns Transaction instructions, preceded by the creation of missing nullifier shards
+ */
+export async function buildWithdrawDualInstruction(
+  program: anchor.Program<Zkcash>,
+  proof: Proof,
+  extData: DualExtData,
+  signer: PublicKey
+): Promise<TransactionInstruction[]> {
+  const accounts = await findTransactionAccounts(program, proof);
+  const [mintConfig0] = findMintConfigPDA(program.programId, extData.mintAddressA);
+  const [mintConfig1] = findMintConfigPDA(program.programId, extData.mintAddressB);
+  const [relayerAccount] = findRelayerPDA(program.programId, signer);
+  const recipientTokenAccount = (mint: PublicKey) =>
+    mint.equals(NATIVE_MINT) ? null : getAssociatedTokenAddressSync(mint, extData.recipient, true);
+  const hasNativeLeg = extData.mintAddressA.equals(NATIVE_MINT) || extData.mintAddressB.equals(NATIVE_MINT);
+
+  const ixs = await buildMissingNullifierShardInstructions(program, proof, signer);
+
+  const instruction = await program.methods
+    .withdrawDual(
+      proof,
+      createDualExtDataMinified(extData),
+      extData.encryptedOutput
+    )
+    .accountsStrict({
+      ...accounts,
+      inputMint0: extData.mintAddressA,
+      inputMint1: extData.mintAddressB,
+      mintConfig0,
+      mintConfig1,
+      reserveTokenAccount0: getAssociatedTokenAddressSync(extData.mintAddressA, accounts.globalConfig, true),
+      reserveTokenAccount1: getAssociatedTokenAddressSync(extData.mintAddressB, accounts.globalConfig, true),
+      recipient: extData.recipient,
+      recipientTokenAccount0: recipientTokenAccount(extData.mintAddressA),
+      recipientTokenAccount1: recipientTokenAccount(extData.mintAddressB),
+      wsolUnwrapAccount: hasNativeLeg ? findWsolUnwrapPDA(program.programId, proof)[0] : null,
+      feeRecipientAccount0: extData.feeRecipient0,
+      feeRecipientAccount1: extData.feeRecipient1,
+      relayerAccount,
+      relayer: signer,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram0: TOKEN_PROGRAM_ID,
+      tokenProgram1: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID
+    })
+    .instruction();
+
+  ixs.push(instruction);
+
+  return ixs;
 }
 
 /**
  * Build update global config instruction
  * @param program - Anchor program instance
  * @param authority - Authority public key
- * @param depositFeeRate - Optional new deposit fee rate (in basis points, 0-10000)
- * @param withdrawalFeeRate - Optional new withdrawal fee rate (in basis points, 0-10000)
- * @param feeErrorMargin - Optional new fee error margin (in basis points, 0-10000)
+ * @param update - Settings to change, see GlobalConfigUpdate
  * @returns Transaction instruction
  */
 export async function buildUpdateGlobalConfigInstruction(
   program: anchor.Program<Zkcash>,
   authority: PublicKey,
-  depositFeeRate?: number | null,
-  withdrawalFeeRate?: number | null,
-  feeErrorMargin?: number | null
+  update: GlobalConfigUpdate
 ) {
   const [globalConfig] = findGlobalConfigPDA(program.programId);
 
   return await program.methods
     .updateGlobalConfig(
-      depositFeeRate ?? null,
-      withdrawalFeeRate ?? null,
-      feeErrorMargin ?? null
+      update.depositFeeRate ?? null,
+      update.withdrawalFeeRate ?? null,
+      update.feeErrorMargin ?? null,
+      update.swapFeeRate ?? null,
+      update.maxSwapSurplusRate ?? null,
+      update.guardian ?? null,
+      update.outflowWindowSlots ?? null,
+      update.minRelayerStake ?? null,
+      update.oracleProgram ?? null,
+      update.rootValiditySlots ?? null
     )
-    .accounts({
+    .accountsStrict({
       globalConfig,
       authority
     })
@@ -374,29 +624,32 @@ export async function buildUpdateGlobalConfigInstruction(
  * Execute update global config instruction
  * @param program - Anchor program instance
  * @param signers - Array of signers (should include authority)
- * @param depositFeeRate - Optional new deposit fee rate (in basis points, 0-10000)
- * @param withdrawalFeeRate - Optional new withdrawal fee rate (in basis points, 0-10000)
- * @param feeErrorMargin - Optional new fee error margin (in basis points, 0-10000)
+ * @param update - Settings to change, see GlobalConfigUpdate
  * @param preInstructions - Optional pre-instructions
  * @returns Transaction signature
  */
 export async function executeUpdateGlobalConfig(
   program: anchor.Program<Zkcash>,
   signers: anchor.web3.Keypair[],
-  depositFeeRate?: number | null,
-  withdrawalFeeRate?: number | null,
-  feeErrorMargin?: number | null,
+  update: GlobalConfigUpdate,
   preInstructions?: TransactionInstruction[]
 ): Promise<string> {
   const [globalConfig] = findGlobalConfigPDA(program.programId);
 
   const txBuilder = program.methods
     .updateGlobalConfig(
-      depositFeeRate ?? null,
-      withdrawalFeeRate ?? null,
-      feeErrorMargin ?? null
+      update.depositFeeRate ?? null,
+      update.withdrawalFeeRate ?? null,
+      update.feeErrorMargin ?? null,
+      update.swapFeeRate ?? null,
+      update.maxSwapSurplusRate ?? null,
+      update.guardian ?? null,
+      update.outflowWindowSlots ?? null,
+      update.minRelayerStake ?? null,
+      update.oracleProgram ?? null,
+      update.rootValiditySlots ?? null
     )
-    .accounts({
+    .accountsStrict({
       globalConfig,
       authority: signers[0].publicKey
     })
@@ -413,22 +666,28 @@ export async function executeUpdateGlobalConfig(
  * Build initialize instruction
  * @param program - Anchor program instance
  * @param authority - Authority public key
+ * @param height - Height of the first tree, must match the genesis verifying key
+ * @param rootHistorySize - Number of roots the first tree keeps
  * @returns Transaction instruction
  */
 export async function buildInitializeInstruction(
   program: anchor.Program<Zkcash>,
-  authority: PublicKey
+  authority: PublicKey,
+  height: number = DEFAULT_HEIGHT,
+  rootHistorySize: number = ROOT_HISTORY_SIZE
 ) {
-  const [treeAccount] = findMerkleTreePDA(program.programId);
+  const [treeAccount] = findMerkleTreePDA(program.programId, 0);
   const [treeTokenAccount] = findTreeTokenAccountPDA(program.programId);
   const [globalConfig] = findGlobalConfigPDA(program.programId);
+  const [verifyingKey] = findVerifyingKeyPDA(program.programId, 0);
 
   return await program.methods
-    .initialize()
+    .initialize(height, rootHistorySize)
     .accountsStrict({
       treeAccount,
       treeTokenAccount,
       globalConfig,
+      verifyingKey,
       authority,
       systemProgram: anchor.web3.SystemProgram.programId
     })
@@ -440,23 +699,29 @@ export async function buildInitializeInstruction(
  * @param program - Anchor program instance
  * @param signers - Array of signers (should include authority)
  * @param preInstructions - Optional pre-instructions
+ * @param height - Height of the first tree, must match the genesis verifying key
+ * @param rootHistorySize - Number of roots the first tree keeps
  * @returns Transaction signature
  */
 export async function executeInitialize(
   program: anchor.Program<Zkcash>,
   signers: anchor.web3.Keypair[],
-  preInstructions?: TransactionInstruction[]
+  preInstructions?: TransactionInstruction[],
+  height: number = DEFAULT_HEIGHT,
+  rootHistorySize: number = ROOT_HISTORY_SIZE
 ): Promise<string> {
-  const [treeAccount] = findMerkleTreePDA(program.programId);
+  const [treeAccount] = findMerkleTreePDA(program.programId, 0);
   const [treeTokenAccount] = findTreeTokenAccountPDA(program.programId);
   const [globalConfig] = findGlobalConfigPDA(program.programId);
+  const [verifyingKey] = findVerifyingKeyPDA(program.programId, 0);
 
   const txBuilder = program.methods
-    .initialize()
+    .initialize(height, rootHistorySize)
     .accountsStrict({
       treeAccount,
       treeTokenAccount,
       globalConfig,
+      verifyingKey,
       authority: signers[0].publicKey,
       systemProgram: anchor.web3.SystemProgram.programId
     })
@@ -469,7 +734,119 @@ export async function executeInitialize(
   return await txBuilder.rpc();
 }
 
+/**
+ * Build initialize nullifier shard instruction
+ * @param program - Anchor program instance
+ * @param payer - Payer of the shard account
+ * @param shardId - Last byte of the nullifiers stored in the shard
+ * @returns Transaction instruction
+ */
+export async function buildInitializeNullifierShardInstruction(
+  program: anchor.Program<Zkcash>,
+  payer: PublicKey,
+  shardId: number
+) {
+  const [nullifierShard] = findNullifierShardPDA(program.programId, shardId);
 
+  return await program.methods
+    .initializeNullifierShard(shardId)
+    .accountsStrict({
+      nullifierShard,
+      payer,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .instruction();
+}
+
+/**
+ * Execute register mint instruction
+ * @param program - Anchor program instance
+ * @param signers - Array of signers (should include authority)
+ * @param mint - Mint to accept deposits of
+ * @param maxDepositAmount - Maximum deposit in raw token units
+ * @param minDepositAmount - Minimum deposit in raw token units
+ * @param minTransferFee - Minimum relayer fee of a shielded transfer in raw token units
+ * @param accountCreationFee - Fee reimbursing a recipient token account created on withdrawal, in raw token units
+ * @returns Transaction signature
+ */
+export async function executeRegisterMint(
+  program: anchor.Program<Zkcash>,
+  signers: anchor.web3.Keypair[],
+  mint: PublicKey,
+  maxDepositAmount: anchor.BN,
+  minDepositAmount: anchor.BN = new anchor.BN(0),
+  minTransferFee: anchor.BN = new anchor.BN(0),
+  accountCreationFee: anchor.BN = new anchor.BN(0)
+): Promise<string> {
+  const [globalConfig] = findGlobalConfigPDA(program.programId);
+  const [mintConfig] = findMintConfigPDA(program.programId, mint);
+
+  return await program.methods
+    .registerMint(maxDepositAmount, minDepositAmount, minTransferFee, accountCreationFee)
+    .accountsStrict({
+      globalConfig,
+      mint,
+      mintConfig,
+      authority: signers[0].publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers(signers)
+    .rpc();
+}
+
+/**
+ * Execute register relayer instruction
+ * @param program - Anchor program instance
+ * @param signers - Array of signers (should include authority)
+ * @param relayer - Relayer allowed to submit withdrawals, transfers and swaps
+ * @returns Transaction signature
+ */
+export async function executeRegisterRelayer(
+  program: anchor.Program<Zkcash>,
+  signers: anchor.web3.Keypair[],
+  relayer: PublicKey
+): Promise<string> {
+  const [globalConfig] = findGlobalConfigPDA(program.programId);
+  const [relayerAccount] = findRelayerPDA(program.programId, relayer);
+
+  return await program.methods
+    .registerRelayer(relayer)
+    .accountsStrict({
+      globalConfig,
+      relayerAccount,
+      authority: signers[0].publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers(signers)
+    .rpc();
+}
+
+/**
+ * Execute add swap program instruction
+ * @param program - Anchor program instance
+ * @param signers - Array of signers (should include authority)
+ * @param swapProgram - Program swaps may be routed through
+ * @returns Transaction signature
+ */
+export async function executeAddSwapProgram(
+  program: anchor.Program<Zkcash>,
+  signers: anchor.web3.Keypair[],
+  swapProgram: PublicKey
+): Promise<string> {
+  const [globalConfig] = findGlobalConfigPDA(program.programId);
+  const [swapProgramRegistry] = findSwapProgramRegistryPDA(program.programId);
+
+  return await program.methods
+    .addSwapProgram(swapProgram)
+    .accountsStrict({
+      globalConfig,
+      swapProgramRegistry,
+      authority: signers[0].publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    })
+    .signers(signers)
+    .rpc();
+}
 
 
 
@@ -760,6 +1137,12 @@ export async function startSetupBankrun(
   tx.sign(admin);
   await banksClient.processTransaction(tx);
 
+  // Accept deposits of both mints and let the admin relay withdrawals and swaps
+  const maxDepositAmount = new anchor.BN("1000000000000000");
+  await executeRegisterMint(program, [admin], mintAddressA, maxDepositAmount);
+  await executeRegisterMint(program, [admin], mintAddressB, maxDepositAmount);
+  await executeRegisterRelayer(program, [admin], admin.publicKey);
+
   return {
     mintAddressA,
     mintAddressB,
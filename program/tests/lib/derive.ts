@@ -2,7 +2,20 @@ import * as anchor from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
 
 /**
- * Find nullifier PDAs for the given proof
+ * Find the nullifier shard PDAs for the given proof
+ * @param program - Anchor program instance
+ * @param proof - Proof object containing input nullifiers
+ * @returns Object containing nullifierShard0PDA and nullifierShard1PDA
+ */
+export function findNullifierShardPDAs(program: anchor.Program<any>, proof: any) {
+  const [nullifierShard0PDA] = findNullifierShardPDA(program.programId, proof.inputNullifiers[0][31]);
+  const [nullifierShard1PDA] = findNullifierShardPDA(program.programId, proof.inputNullifiers[1][31]);
+
+  return { nullifierShard0PDA, nullifierShard1PDA };
+}
+
+/**
+ * Find the legacy nullifier PDAs for the given proof, spent nullifiers of a legacy pool
  * @param program - Anchor program instance
  * @param proof - Proof object containing input nullifiers
  * @returns Object containing nullifier0PDA and nullifier1PDA
@@ -12,43 +25,47 @@ export function findNullifierPDAs(program: anchor.Program<any>, proof: any) {
     [Buffer.from("nullifier"), Buffer.from(proof.inputNullifiers[0])],
     program.programId
   );
-  
+
   const [nullifier1PDA] = PublicKey.findProgramAddressSync(
     [Buffer.from("nullifier"), Buffer.from(proof.inputNullifiers[1])],
     program.programId
   );
-  
+
   return { nullifier0PDA, nullifier1PDA };
 }
 
 /**
- * Find commitment PDAs for the given proof
- * @param program - Anchor program instance
- * @param proof - Proof object containing output commitments
- * @returns Object containing commitment0PDA and commitment1PDA
+ * Find a nullifier shard PDA
+ * @param programId - Program ID
+ * @param shardId - Last byte of the nullifiers stored in the shard
+ * @returns Nullifier shard PDA and bump
  */
-export function findCommitmentPDAs(program: anchor.Program<any>, proof: any) {
-  const [commitment0PDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("commitment0"), Buffer.from(proof.outputCommitments[0])],
-    program.programId
-  );
-  
-  const [commitment1PDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("commitment1"), Buffer.from(proof.outputCommitments[1])],
-    program.programId
+export function findNullifierShardPDA(programId: PublicKey, shardId: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("nullifier_shard"), Buffer.from([shardId])],
+    programId
   );
-  
-  return { commitment0PDA, commitment1PDA };
 }
 
-
-
 /**
  * Find the merkle tree PDA
  * @param programId - Program ID
+ * @param treeId - Id of the tree, the active one is stored in the global config
  * @returns Merkle tree PDA and bump
  */
-export function findMerkleTreePDA(programId: PublicKey) {
+export function findMerkleTreePDA(programId: PublicKey, treeId: number | anchor.BN = 0) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("merkle_tree"), new anchor.BN(treeId).toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+}
+
+/**
+ * Find the merkle tree PDA of a legacy pool, created before trees had ids
+ * @param programId - Program ID
+ * @returns Legacy merkle tree PDA and bump
+ */
+export function findLegacyMerkleTreePDA(programId: PublicKey) {
   return PublicKey.findProgramAddressSync(
     [Buffer.from("merkle_tree")],
     programId
@@ -79,3 +96,80 @@ export function findGlobalConfigPDA(programId: PublicKey) {
   );
 }
 
+/**
+ * Find the verifying key PDA
+ * @param programId - Program ID
+ * @param version - Verifying key version, 0 is the genesis circuit
+ * @returns Verifying key PDA and bump
+ */
+export function findVerifyingKeyPDA(programId: PublicKey, version: number = 0) {
+  const versionBytes = Buffer.alloc(4);
+  versionBytes.writeUInt32LE(version);
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("verifying_key"), versionBytes],
+    programId
+  );
+}
+
+/**
+ * Find the mint config PDA
+ * @param programId - Program ID
+ * @param mint - Registered mint
+ * @returns Mint config PDA and bump
+ */
+export function findMintConfigPDA(programId: PublicKey, mint: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_config"), mint.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Find the relayer account PDA
+ * @param programId - Program ID
+ * @param relayer - Relayer public key
+ * @returns Relayer account PDA and bump
+ */
+export function findRelayerPDA(programId: PublicKey, relayer: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("relayer"), relayer.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Find the swap program registry PDA
+ * @param programId - Program ID
+ * @returns Swap program registry PDA and bump
+ */
+export function findSwapProgramRegistryPDA(programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("swap_program_registry")],
+    programId
+  );
+}
+
+/**
+ * Find the temporary WSOL account a native withdrawal is unwrapped through
+ * @param programId - Program ID
+ * @param proof - Proof object containing input nullifiers
+ * @returns WSOL unwrap account PDA and bump
+ */
+export function findWsolUnwrapPDA(programId: PublicKey, proof: any) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("wsol_unwrap"), Buffer.from(proof.inputNullifiers[0])],
+    programId
+  );
+}
+
+/**
+ * Find the event authority PDA signing the program's self-CPI events
+ * @param programId - Program ID
+ * @returns Event authority PDA and bump
+ */
+export function findEventAuthorityPDA(programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("__event_authority")],
+    programId
+  );
+}
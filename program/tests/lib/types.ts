@@ -7,8 +7,7 @@ import { BN } from "@coral-xyz/anchor";
 export interface ExtData {
   recipient: PublicKey;
   extAmount: BN;
-  encryptedOutput1: Buffer;
-  encryptedOutput2: Buffer;
+  encryptedOutput: Buffer;
   fee: BN;
   feeRecipient: PublicKey;
   mintAddressA: PublicKey;
@@ -18,14 +17,29 @@ export interface ExtData {
 export interface SwapData {
   extAmount: BN;
   extMinAmountOut: BN;
-  encryptedOutput1: Buffer;
-  encryptedOutput2: Buffer;
+  encryptedOutput: Buffer;
   fee: BN;
   feeRecipient: PublicKey;
   mintAddressA: PublicKey;
   mintAddressB: PublicKey;
 }
 
+/**
+ * External data structure for dual-mint deposit/withdraw operations
+ */
+export interface DualExtData {
+  recipient: PublicKey;
+  extAmount0: BN;
+  extAmount1: BN;
+  encryptedOutput: Buffer;
+  fee0: BN;
+  fee1: BN;
+  feeRecipient0: PublicKey;
+  feeRecipient1: PublicKey;
+  mintAddressA: PublicKey;
+  mintAddressB: PublicKey;
+}
+
 /**
  * Proof structure ready to submit to the program
  */
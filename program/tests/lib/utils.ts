@@ -56,14 +56,13 @@ export function mockEncrypt(value: Utxo): string {
 
 /**
  * Calculates the hash of ext data using Borsh serialization
- * @param extData External data object containing recipient, amount, encrypted outputs, fee, fee recipient, and mint address
+ * @param extData External data object containing recipient, amount, encrypted output, fee, fee recipient, and mint address
  * @returns The hash as a Uint8Array (32 bytes)
  */
 export function getExtDataHash(extData: {
   recipient: string | PublicKey;
   extAmount: string | number | BN;
-  encryptedOutput1?: string | Uint8Array;  // Optional for Account Data Separation
-  encryptedOutput2?: string | Uint8Array;  // Optional for Account Data Separation
+  encryptedOutput?: string | Uint8Array;
   fee: string | number | BN;
   feeRecipient: string | PublicKey;
   mintAddressA: string | PublicKey;
@@ -89,12 +88,9 @@ export function getExtDataHash(extData: {
   const extAmount = new BN(extData.extAmount.toString());
   const fee = new BN(extData.fee.toString());
   
-  // Handle encrypted outputs - they might not be present in Account Data Separation approach
-  const encryptedOutput1 = extData.encryptedOutput1 
-    ? Buffer.from(extData.encryptedOutput1 as any)
-    : Buffer.alloc(0); // Empty buffer if not provided
-  const encryptedOutput2 = extData.encryptedOutput2 
-    ? Buffer.from(extData.encryptedOutput2 as any)
+  // Handle the encrypted output - it might not be present
+  const encryptedOutput = extData.encryptedOutput
+    ? Buffer.from(extData.encryptedOutput as any)
     : Buffer.alloc(0); // Empty buffer if not provided
 
   // Define the borsh schema matching the Rust struct
@@ -102,8 +98,7 @@ export function getExtDataHash(extData: {
     struct: {
       recipient: { array: { type: 'u8', len: 32 } },
       extAmount: 'i64',
-      encryptedOutput1: { array: { type: 'u8' } },
-      encryptedOutput2: { array: { type: 'u8' } },
+      encryptedOutput: { array: { type: 'u8' } },
       fee: 'u64',
       feeRecipient: { array: { type: 'u8', len: 32 } },
       mintAddressA: { array: { type: 'u8', len: 32 } },
@@ -114,8 +109,7 @@ export function getExtDataHash(extData: {
   const value = {
     recipient: recipient.toBytes(),
     extAmount: extAmount,  // BN instance - Borsh handles it correctly with i64 type
-    encryptedOutput1: encryptedOutput1,
-    encryptedOutput2: encryptedOutput2,
+    encryptedOutput: encryptedOutput,
     fee: fee,  // BN instance - Borsh handles it correctly with u64 type
     feeRecipient: feeRecipient.toBytes(),
     mintAddressA: mintAddressA.toBytes(),
@@ -133,14 +127,13 @@ export function getExtDataHash(extData: {
 
 /**
  * Calculates the hash of ext data using Borsh serialization
- * @param extData External data object containing recipient, amount, encrypted outputs, fee, fee recipient, and mint address
+ * @param extData External data object containing recipient, amount, encrypted output, fee, fee recipient, and mint address
  * @returns The hash as a Uint8Array (32 bytes)
  */
 export function getSwapExtDataHash(extData: {
   extAmount: string | number | BN;
   extMinAmountOut: string | number | BN;
-  encryptedOutput1?: string | Uint8Array;  // Optional for Account Data Separation
-  encryptedOutput2?: string | Uint8Array;  // Optional for Account Data Separation
+  encryptedOutput?: string | Uint8Array;
   fee: string | number | BN;
   feeRecipient: string | PublicKey;
   mintAddressA: string | PublicKey;
@@ -163,12 +156,9 @@ export function getSwapExtDataHash(extData: {
   const extMinAmountOut = new BN(extData.extMinAmountOut.toString());
   const fee = new BN(extData.fee.toString());
 
-  // Handle encrypted outputs - they might not be present in Account Data Separation approach
-  const encryptedOutput1 = extData.encryptedOutput1
-    ? Buffer.from(extData.encryptedOutput1 as any)
-    : Buffer.alloc(0); // Empty buffer if not provided
-  const encryptedOutput2 = extData.encryptedOutput2
-    ? Buffer.from(extData.encryptedOutput2 as any)
+  // Handle the encrypted output - it might not be present
+  const encryptedOutput = extData.encryptedOutput
+    ? Buffer.from(extData.encryptedOutput as any)
     : Buffer.alloc(0); // Empty buffer if not provided
 
   // Define the borsh schema matching the Rust struct
@@ -176,8 +166,7 @@ export function getSwapExtDataHash(extData: {
     struct: {
       extAmount: 'i64',
       extMinAmountOut: 'i64',
-      encryptedOutput1: { array: { type: 'u8' } },
-      encryptedOutput2: { array: { type: 'u8' } },
+      encryptedOutput: { array: { type: 'u8' } },
       fee: 'u64',
       feeRecipient: { array: { type: 'u8', len: 32 } },
       mintAddressA: { array: { type: 'u8', len: 32 } },
@@ -188,8 +177,7 @@ export function getSwapExtDataHash(extData: {
   const value = {
     extAmount: extAmount,  // BN instance - Borsh handles it correctly with i64 type
     extMinAmountOut: extMinAmountOut,  // BN instance - Borsh handles it correctly with i64 type
-    encryptedOutput1: encryptedOutput1,
-    encryptedOutput2: encryptedOutput2,
+    encryptedOutput: encryptedOutput,
     fee: fee,  // BN instance - Borsh handles it correctly with u64 type
     feeRecipient: feeRecipient.toBytes(),
     mintAddressA: mintAddressA.toBytes(),
@@ -205,6 +193,67 @@ export function getSwapExtDataHash(extData: {
 } 
 
 
+/**
+ * Calculates the hash of dual-mint ext data using Borsh serialization
+ * @param extData External data object containing recipient, both amounts, encrypted output, both fees, both fee recipients, and mint addresses
+ * @returns The hash as a Uint8Array (32 bytes)
+ */
+export function getDualExtDataHash(extData: {
+  recipient: string | PublicKey;
+  extAmount0: string | number | BN;
+  extAmount1: string | number | BN;
+  encryptedOutput?: string | Uint8Array;
+  fee0: string | number | BN;
+  fee1: string | number | BN;
+  feeRecipient0: string | PublicKey;
+  feeRecipient1: string | PublicKey;
+  mintAddressA: string | PublicKey;
+  mintAddressB: string | PublicKey;
+}): Uint8Array {
+  const toPublicKey = (key: string | PublicKey) => key instanceof PublicKey ? key : new PublicKey(key);
+
+  // Handle the encrypted output - it might not be present
+  const encryptedOutput = extData.encryptedOutput
+    ? Buffer.from(extData.encryptedOutput as any)
+    : Buffer.alloc(0); // Empty buffer if not provided
+
+  // Define the borsh schema matching the Rust struct
+  const schema = {
+    struct: {
+      recipient: { array: { type: 'u8', len: 32 } },
+      extAmount0: 'i64',
+      extAmount1: 'i64',
+      encryptedOutput: { array: { type: 'u8' } },
+      fee0: 'u64',
+      fee1: 'u64',
+      feeRecipient0: { array: { type: 'u8', len: 32 } },
+      feeRecipient1: { array: { type: 'u8', len: 32 } },
+      mintAddressA: { array: { type: 'u8', len: 32 } },
+      mintAddressB: { array: { type: 'u8', len: 32 } },
+    }
+  };
+
+  const value = {
+    recipient: toPublicKey(extData.recipient).toBytes(),
+    extAmount0: new BN(extData.extAmount0.toString()),
+    extAmount1: new BN(extData.extAmount1.toString()),
+    encryptedOutput: encryptedOutput,
+    fee0: new BN(extData.fee0.toString()),
+    fee1: new BN(extData.fee1.toString()),
+    feeRecipient0: toPublicKey(extData.feeRecipient0).toBytes(),
+    feeRecipient1: toPublicKey(extData.feeRecipient1).toBytes(),
+    mintAddressA: toPublicKey(extData.mintAddressA).toBytes(),
+    mintAddressB: toPublicKey(extData.mintAddressB).toBytes(),
+  };
+  // Serialize with Borsh
+  const serializedData = borsh.serialize(schema, value);
+  // Calculate the SHA-256 hash
+  const hashHex = sha256(serializedData);
+  // Convert from hex string to Uint8Array
+  return Buffer.from(hashHex.slice(2), 'hex');
+}
+
+
 export async function setupATA(
   context: ProgramTestContext,
   mint: PublicKey,
@@ -25,7 +25,7 @@ import {
 import { Zkcash } from "../target/types/zkcash";
 import { LightWasm, WasmFactory } from "@lightprotocol/hasher.rs";
 import { MerkleTree } from "./lib/merkle_tree";
-import { buildDepositInstruction, buildWithdrawInstruction, buildSwapInstruction, sendTransactionWithALT, createSwapExtDataMinified } from "./instructions";
+import { buildDepositInstruction, buildWithdrawInstruction, buildSwapInstruction, sendTransactionWithALT, createSwapExtDataMinified, executeInitialize, executeRegisterMint, executeRegisterRelayer, executeAddSwapProgram, JUPITER_PROGRAM_ID } from "./instructions";
 import { Utxo } from "./lib/utxo";
 import { DEFAULT_HEIGHT, FIELD_SIZE, ROOT_HISTORY_SIZE, ZERO_BYTES, DEPOSIT_FEE_RATE, WITHDRAW_FEE_RATE } from "./lib/constants";
 import { getExtDataHash, getSwapExtDataHash, publicKeyToFieldElement } from "./lib/utils";
@@ -104,26 +104,7 @@ describe("localnet", () => {
   });
 
   it("Initialize", async () => {
-    const [treeAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from("merkle_tree")],
-      program.programId
-    );
-    const [treeTokenAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from("tree_token")],
-      program.programId
-    );
-
-    const tx = await program.methods
-      .initialize()
-      .accountsStrict({
-        treeAccount,
-        treeTokenAccount,
-        globalConfig: globalConfig,
-        authority: admin.publicKey,
-        systemProgram: SystemProgram.programId,
-      })
-      .signers([admin])
-      .rpc();
+    const tx = await executeInitialize(program, [admin], [], DEFAULT_HEIGHT, ROOT_HISTORY_SIZE);
 
     console.log("Initialize tx:", tx);
   });
@@ -217,6 +198,16 @@ describe("localnet", () => {
     console.log("Tokens minted");
   });
 
+  it("Register mints, relayer and swap program", async () => {
+    const maxDepositAmount = new BN(10000 * 10 ** 9);
+    await executeRegisterMint(program, [admin], mintAddressA, maxDepositAmount);
+    await executeRegisterMint(program, [admin], mintAddressB, maxDepositAmount);
+
+    // The admin relays the swap below, and the swap is routed through Jupiter
+    await executeRegisterRelayer(program, [admin], admin.publicKey);
+    await executeAddSwapProgram(program, [admin], JUPITER_PROGRAM_ID);
+  });
+
   it("Create Address Lookup Table", async () => {
     // Get protocol addresses for ALT
     const protocolAddresses = getTestProtocolAddresses(
@@ -289,8 +280,7 @@ describe("localnet", () => {
     const depositExtData: ExtData = {
       recipient: reserveTokenAccount,
       extAmount: new BN(depositAmount),
-      encryptedOutput1: Buffer.from("depositEncryptedOutput1"),
-      encryptedOutput2: Buffer.from("depositEncryptedOutput2"),
+      encryptedOutput: Buffer.from("depositEncryptedOutput"),
       fee: depositFee,
       feeRecipient: feeRecipient.publicKey,
       mintAddressA: mintAddressA,
@@ -437,8 +427,7 @@ describe("localnet", () => {
   //   const swapExtData: SwapData = {
   //     extAmount: new BN(-swapAmountIn),
   //     extMinAmountOut: new BN(swapMinAmountOut),
-  //     encryptedOutput1: Buffer.from("swapEncryptedOutput1"),
-  //     encryptedOutput2: Buffer.from("swapEncryptedOutput2"),
+  //     encryptedOutput: Buffer.from("swapEncryptedOutput"),
   //     fee: swapFee,
   //     feeRecipient: feeRecipient.publicKey,
   //     mintAddressA: mintAddressA,
@@ -586,8 +575,7 @@ describe("localnet", () => {
   //   const withdrawExtData: ExtData = {
   //     recipient: recipientTokenAccount.address,
   //     extAmount: new BN(-withdrawAmount),
-  //     encryptedOutput1: Buffer.from("withdrawEncryptedOutput1"),
-  //     encryptedOutput2: Buffer.from("withdrawEncryptedOutput2"),
+  //     encryptedOutput: Buffer.from("withdrawEncryptedOutput"),
   //     fee: withdrawFee,
   //     feeRecipient: feeRecipient.publicKey,
   //     mintAddressA: mintAddressB,
@@ -725,10 +713,10 @@ describe("localnet", () => {
     const swapExtData: SwapData = {
       extAmount: new BN(-swapAmountIn),
       extMinAmountOut: new BN(swapMinAmountOut),
-      encryptedOutput1: Buffer.from("jupSwapEncryptedOutput1"),
-      encryptedOutput2: Buffer.from("jupSwapEncryptedOutput2"),
+      encryptedOutput: Buffer.from("jupSwapEncryptedOutput"),
       fee: swapFee,
-      feeRecipient: feeRecipient.publicKey,
+      // Swap fees are paid to output token accounts of the user submitting the swap
+      feeRecipient: getAssociatedTokenAddressSync(mintAddressB, admin.publicKey),
       mintAddressA: mintAddressA,
       mintAddressB: mintAddressB,
     };
@@ -837,8 +825,7 @@ describe("localnet", () => {
     //     program,
     //     swapProofToSubmit,
     //     createSwapExtDataMinified(swapExtData),
-    //     swapExtData.encryptedOutput1,
-    //     swapExtData.encryptedOutput2,
+    //     swapExtData.encryptedOutput,
     //     mintAddressA,
     //     mintAddressB,
     //     swapAmountIn.toString(),
@@ -916,8 +903,9 @@ describe("localnet", () => {
 
     console.log("✅ Jupiter data prepared!");
     console.log("\n📊 Size Analysis:");
-    console.log("  Instruction data:", swapTxWithJupiter[0].data.length, "bytes");
-    console.log("  Total accounts:", swapTxWithJupiter[0].keys.length);
+    const swapInstruction = swapTxWithJupiter[swapTxWithJupiter.length - 1];
+    console.log("  Instruction data:", swapInstruction.data.length, "bytes");
+    console.log("  Total accounts:", swapInstruction.keys.length);
 
     const altAddresses = jupiterAltAddress ? [altAddress, jupiterAltAddress] : [altAddress];
     